@@ -0,0 +1,282 @@
+//! A versioned file format for moving accounts between machines and client stores.
+//!
+//! Accounts only enter a client through `add_account`, and the state, seed and keys of an account
+//! live in different places: the store and the keystore. An [`AccountBundle`] carries all of
+//! them: the account itself, its seed while it is not deployed yet, the secret keys it
+//! authenticates with, and the commitment of the exported state. Reading a bundle checks the
+//! account against that commitment; after importing, `account_bundle import` syncs and compares
+//! it with the account's current commitment to tell whether the bundle was stale.
+//!
+//! The encoding is `MAGIC | version | payload | checksum`, like a note file. The bundle holds
+//! secret keys in plaintext, so treat the file like the keystore itself.
+
+use std::{fmt, fs, io, path::Path};
+
+use miden_client::{
+    account::{Account, AccountId, StorageSlot},
+    auth::AuthSecretKey,
+    crypto::Digest,
+    keystore::KeyStoreError,
+    store::AccountRecord,
+    Client, ClientError, Word,
+};
+use miden_lib::{account::interface::AccountInterface, AuthScheme};
+use miden_objects::{
+    utils::{ByteReader, Deserializable, DeserializationError, Serializable, SliceReader},
+    Hasher,
+};
+
+/// Magic bytes at the start of every account bundle.
+const MAGIC: &[u8; 4] = b"MACB";
+
+/// Current version of the account bundle format.
+pub const VERSION: u8 = 1;
+
+const CHECKSUM_LEN: usize = 32;
+
+/// Errors that can occur when reading or importing an account bundle.
+#[derive(Debug)]
+pub enum AccountBundleError {
+    Io(io::Error),
+    InvalidMagic,
+    UnsupportedVersion(u8),
+    ChecksumMismatch,
+    Deserialization(DeserializationError),
+    /// The account state does not match the commitment recorded next to it.
+    CommitmentMismatch {
+        expected: Digest,
+        found: Digest,
+    },
+    /// A new account was exported without the seed it needs to be deployed.
+    MissingSeed(AccountId),
+    /// The keystore has no secret key for a public key the account authenticates with.
+    MissingSecretKey {
+        account_id: AccountId,
+        pub_key: Word,
+    },
+    KeyStore(KeyStoreError),
+    Client(Box<ClientError>),
+}
+
+impl fmt::Display for AccountBundleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to access account bundle: {err}"),
+            Self::InvalidMagic => write!(f, "not an account bundle"),
+            Self::UnsupportedVersion(version) => {
+                write!(
+                    f,
+                    "unsupported account bundle version {version}, expected {VERSION}"
+                )
+            }
+            Self::ChecksumMismatch => {
+                write!(f, "account bundle checksum does not match its contents")
+            }
+            Self::Deserialization(err) => write!(f, "failed to deserialize account bundle: {err}"),
+            Self::CommitmentMismatch { expected, found } => write!(
+                f,
+                "account state has commitment {found}, but the bundle records {expected}"
+            ),
+            Self::MissingSeed(account_id) => {
+                write!(f, "new account {account_id} was exported without its seed")
+            }
+            Self::MissingSecretKey {
+                account_id,
+                pub_key,
+            } => write!(
+                f,
+                "no secret key for public key {} of account {account_id}",
+                Digest::from(*pub_key)
+            ),
+            Self::KeyStore(err) => write!(f, "keystore error: {err}"),
+            Self::Client(err) => write!(f, "client error: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for AccountBundleError {}
+
+impl From<io::Error> for AccountBundleError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<DeserializationError> for AccountBundleError {
+    fn from(err: DeserializationError) -> Self {
+        Self::Deserialization(err)
+    }
+}
+
+impl From<KeyStoreError> for AccountBundleError {
+    fn from(err: KeyStoreError) -> Self {
+        Self::KeyStore(err)
+    }
+}
+
+impl From<ClientError> for AccountBundleError {
+    fn from(err: ClientError) -> Self {
+        Self::Client(Box::new(err))
+    }
+}
+
+/// An account with everything a client needs to use it.
+#[derive(Debug, Clone)]
+pub struct AccountBundle {
+    pub account: Account,
+    /// The seed of an account that is not deployed yet.
+    pub seed: Option<Word>,
+    pub secret_keys: Vec<AuthSecretKey>,
+    /// Commitment of the account state when it was exported.
+    pub commitment: Digest,
+}
+
+impl AccountBundle {
+    /// Bundles `account` with its seed and keys.
+    ///
+    /// Fails if the account is new but `seed` is missing, since the importer could not deploy it.
+    pub fn new(
+        account: Account,
+        seed: Option<Word>,
+        secret_keys: Vec<AuthSecretKey>,
+    ) -> Result<Self, AccountBundleError> {
+        if account.is_new() && seed.is_none() {
+            return Err(AccountBundleError::MissingSeed(account.id()));
+        }
+        Ok(Self {
+            commitment: account.commitment(),
+            account,
+            seed,
+            secret_keys,
+        })
+    }
+
+    /// Bundles an account from the client's store, looking up the secret key of every public
+    /// key in its storage with `get_key`.
+    ///
+    /// Public keys are found in value slots and in the values of map slots, so both single-key
+    /// accounts and multisig accounts export all the keys the keystore has for them. The export
+    /// fails if `get_key` fails, or if it has no secret key for the public key of the account's
+    /// Falcon authentication component.
+    pub fn from_record(
+        record: &AccountRecord,
+        get_key: impl Fn(Word) -> Result<Option<AuthSecretKey>, KeyStoreError>,
+    ) -> Result<Self, AccountBundleError> {
+        let account = record.account().clone();
+        let mut secret_keys = vec![];
+        for pub_key in storage_words(&account) {
+            if let Some(key) = get_key(pub_key)? {
+                secret_keys.push(key);
+            }
+        }
+
+        let bundle = Self::new(account, record.seed().copied(), secret_keys)?;
+        let exported = bundle.public_keys();
+        for AuthScheme::RpoFalcon512 { pub_key } in AccountInterface::from(&bundle.account).auth() {
+            let pub_key = Word::from(*pub_key);
+            if !exported.contains(&pub_key) {
+                return Err(AccountBundleError::MissingSecretKey {
+                    account_id: bundle.account.id(),
+                    pub_key,
+                });
+            }
+        }
+        Ok(bundle)
+    }
+
+    /// The public keys of the secret keys in the bundle.
+    pub fn public_keys(&self) -> Vec<Word> {
+        self.secret_keys
+            .iter()
+            .map(|key| {
+                let AuthSecretKey::RpoFalcon512(secret_key) = key;
+                secret_key.public_key().into()
+            })
+            .collect()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION);
+        self.account.write_into(&mut bytes);
+        self.seed.write_into(&mut bytes);
+        self.secret_keys.write_into(&mut bytes);
+        self.commitment.write_into(&mut bytes);
+
+        let checksum = Hasher::hash(&bytes);
+        bytes.extend_from_slice(&checksum.as_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, AccountBundleError> {
+        if bytes.len() < MAGIC.len() + 1 + CHECKSUM_LEN || !bytes.starts_with(MAGIC) {
+            return Err(AccountBundleError::InvalidMagic);
+        }
+        let version = bytes[MAGIC.len()];
+        if version != VERSION {
+            return Err(AccountBundleError::UnsupportedVersion(version));
+        }
+
+        let (contents, checksum) = bytes.split_at(bytes.len() - CHECKSUM_LEN);
+        if Hasher::hash(contents).as_bytes() != checksum {
+            return Err(AccountBundleError::ChecksumMismatch);
+        }
+
+        let mut reader = SliceReader::new(&contents[MAGIC.len() + 1..]);
+        let account = Account::read_from(&mut reader)?;
+        let seed = Option::<Word>::read_from(&mut reader)?;
+        let secret_keys = Vec::<AuthSecretKey>::read_from(&mut reader)?;
+        let commitment = Digest::read_from(&mut reader)?;
+        if reader.has_more_bytes() {
+            return Err(DeserializationError::UnconsumedBytes.into());
+        }
+
+        if account.commitment() != commitment {
+            return Err(AccountBundleError::CommitmentMismatch {
+                expected: commitment,
+                found: account.commitment(),
+            });
+        }
+        Ok(Self {
+            account,
+            seed,
+            secret_keys,
+            commitment,
+        })
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_bytes())
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, AccountBundleError> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+}
+
+/// Adds the bundled account to `client`, returning the secret keys for the caller to add to
+/// its keystore.
+///
+/// An account the client already tracks is only replaced if `overwrite` is set.
+pub async fn import_bundle(
+    client: &mut Client,
+    bundle: AccountBundle,
+    overwrite: bool,
+) -> Result<Vec<AuthSecretKey>, AccountBundleError> {
+    client
+        .add_account(&bundle.account, bundle.seed, overwrite)
+        .await?;
+    Ok(bundle.secret_keys)
+}
+
+/// Every word in the account's storage, including the values of storage maps.
+fn storage_words(account: &Account) -> impl Iterator<Item = Word> + '_ {
+    account
+        .storage()
+        .slots()
+        .iter()
+        .flat_map(|slot| match slot {
+            StorageSlot::Value(value) => vec![*value],
+            StorageSlot::Map(map) => map.entries().map(|(_, value)| *value).collect(),
+        })
+}
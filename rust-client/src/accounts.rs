@@ -0,0 +1,69 @@
+//! Basic wallets and faucets for the tutorial flows, with their keys in the workspace keystore.
+//!
+//! These are `miden_client_tools::create_basic_account` and `create_basic_faucet`, except that
+//! they take a [`WorkspaceKeyStore`] instead of a plaintext `FilesystemKeyStore`, so flows keep
+//! working once the keystore was encrypted.
+
+use miden_client::{
+    account::{
+        component::{BasicWallet, RpoFalcon512},
+        Account, AccountBuilder, AccountStorageMode, AccountType,
+    },
+    auth::AuthSecretKey,
+    crypto::SecretKey,
+    keystore::KeyStoreError,
+    Client, ClientError,
+};
+use rand::RngCore;
+
+use crate::{faucet::FaucetSpec, workspace::WorkspaceKeyStore};
+
+/// Creates a public wallet with updatable code, tracks it in `client` and adds its key to
+/// `keystore`, returning the wallet and its key.
+pub async fn create_basic_account(
+    client: &mut Client,
+    keystore: WorkspaceKeyStore,
+) -> Result<(Account, SecretKey), ClientError> {
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+
+    let key_pair = SecretKey::with_rng(client.rng());
+    let (account, seed) = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountUpdatableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(RpoFalcon512::new(key_pair.public_key()))
+        .with_component(BasicWallet)
+        .build()?;
+    client.add_account(&account, Some(seed), false).await?;
+    keystore
+        .add_key(&AuthSecretKey::RpoFalcon512(key_pair.clone()))
+        .map_err(keystore_error)?;
+
+    Ok((account, key_pair))
+}
+
+/// Creates a public faucet for the default `MID` token, tracks it in `client` and adds its key to
+/// `keystore`.
+pub async fn create_basic_faucet(
+    client: &mut Client,
+    keystore: WorkspaceKeyStore,
+) -> Result<Account, ClientError> {
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+
+    let key_pair = SecretKey::with_rng(client.rng());
+    let (account, seed) = FaucetSpec::default()
+        .build_account(init_seed, key_pair.public_key())
+        .expect("the default faucet spec is valid");
+    client.add_account(&account, Some(seed), false).await?;
+    keystore
+        .add_key(&AuthSecretKey::RpoFalcon512(key_pair.clone()))
+        .map_err(keystore_error)?;
+
+    Ok(account)
+}
+
+/// Keystore failures surface like the other setup failures of the tutorial clients.
+fn keystore_error(err: KeyStoreError) -> ClientError {
+    ClientError::ClientInitializationError(format!("failed to store the account key: {err}"))
+}
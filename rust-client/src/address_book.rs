@@ -0,0 +1,341 @@
+//! Named accounts, so flows can take `alice` or `mid-faucet` where they take an account id.
+//!
+//! The address book is a JSON file kept next to the client's store, `address_book.json` in the
+//! current [workspace](crate::workspace) unless `MIDEN_ADDRESS_BOOK` names another path:
+//!
+//! ```json
+//! {
+//!   "network": "mtst",
+//!   "entries": {
+//!     "alice": { "account_id": "mtst1qz...", "role": "wallet" },
+//!     "mid-faucet": { "account_id": "mtst1qr...", "role": "faucet" }
+//!   }
+//! }
+//! ```
+//!
+//! Every address book belongs to one network. Bech32 ids of other networks are rejected both
+//! when they are added and when they are resolved, so a mainnet id pasted into a testnet flow is
+//! caught before any transaction is built.
+
+use std::{
+    collections::BTreeMap,
+    env, fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use miden_client::account::AccountId;
+use miden_objects::account::NetworkId;
+use serde::{Deserialize, Serialize};
+
+use crate::workspace::{Workspace, WorkspaceError};
+
+/// Environment variable holding the path of the address book.
+pub const ADDRESS_BOOK_ENV: &str = "MIDEN_ADDRESS_BOOK";
+
+/// Errors that can occur when reading, editing or resolving names in an address book.
+#[derive(Debug)]
+pub enum AddressBookError {
+    Io(io::Error),
+    InvalidFile(String),
+    /// Names are lowercase ASCII letters, digits, `-` and `_`, starting with a letter, and
+    /// cannot be mistaken for a bech32 id.
+    InvalidName(String),
+    InvalidAccountId(String),
+    InvalidRole(String),
+    DuplicateName(String),
+    UnknownName(String),
+    WrongNetwork {
+        expected: Box<NetworkId>,
+        found: Box<NetworkId>,
+    },
+    WrongRole {
+        name: String,
+        expected: Role,
+        found: Role,
+    },
+    Workspace(WorkspaceError),
+}
+
+impl fmt::Display for AddressBookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to access address book: {err}"),
+            Self::InvalidFile(err) => write!(f, "invalid address book: {err}"),
+            Self::InvalidName(name) => write!(f, "invalid account name {name}"),
+            Self::InvalidAccountId(err) => write!(f, "invalid account id: {err}"),
+            Self::InvalidRole(role) => {
+                write!(
+                    f,
+                    "unknown role {role}, expected wallet, faucet or contract"
+                )
+            }
+            Self::DuplicateName(name) => write!(f, "the address book already has {name}"),
+            Self::UnknownName(name) => {
+                write!(f, "{name} is neither an account id nor a known name")
+            }
+            Self::WrongNetwork { expected, found } => write!(
+                f,
+                "account id is for network {found}, but the address book is for {expected}"
+            ),
+            Self::WrongRole {
+                name,
+                expected,
+                found,
+            } => write!(f, "{name} is a {found}, expected a {expected}"),
+            Self::Workspace(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for AddressBookError {}
+
+impl From<io::Error> for AddressBookError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<WorkspaceError> for AddressBookError {
+    fn from(err: WorkspaceError) -> Self {
+        Self::Workspace(err)
+    }
+}
+
+/// What a named account is used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Wallet,
+    Faucet,
+    Contract,
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::Wallet => "wallet",
+            Self::Faucet => "faucet",
+            Self::Contract => "contract",
+        })
+    }
+}
+
+impl std::str::FromStr for Role {
+    type Err = AddressBookError;
+
+    fn from_str(role: &str) -> Result<Self, Self::Err> {
+        match role.trim().to_ascii_lowercase().as_str() {
+            "wallet" => Ok(Self::Wallet),
+            "faucet" => Ok(Self::Faucet),
+            "contract" => Ok(Self::Contract),
+            _ => Err(AddressBookError::InvalidRole(role.to_string())),
+        }
+    }
+}
+
+/// A named account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Entry {
+    pub account_id: AccountId,
+    pub role: Role,
+}
+
+/// The JSON layout of an address book file.
+#[derive(Debug, Serialize, Deserialize)]
+struct BookFile {
+    network: String,
+    entries: BTreeMap<String, EntryFile>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EntryFile {
+    account_id: String,
+    role: Role,
+}
+
+/// Names of the accounts of one network, persisted as JSON.
+#[derive(Debug, Clone)]
+pub struct AddressBook {
+    path: PathBuf,
+    network: NetworkId,
+    entries: BTreeMap<String, Entry>,
+}
+
+impl AddressBook {
+    /// An empty address book that will be saved to `path`.
+    pub fn new(path: impl Into<PathBuf>, network: NetworkId) -> Self {
+        Self {
+            path: path.into(),
+            network,
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Loads the address book at `path`, or starts an empty one for `network` if the file does
+    /// not exist yet.
+    pub fn open(path: impl Into<PathBuf>, network: NetworkId) -> Result<Self, AddressBookError> {
+        let path = path.into();
+        if !path.exists() {
+            return Ok(Self::new(path, network));
+        }
+
+        let file: BookFile = serde_json::from_slice(&fs::read(&path)?)
+            .map_err(|err| AddressBookError::InvalidFile(err.to_string()))?;
+        let file_network = NetworkId::new(&file.network)
+            .map_err(|err| AddressBookError::InvalidFile(err.to_string()))?;
+        if file_network != network {
+            return Err(AddressBookError::WrongNetwork {
+                expected: Box::new(network),
+                found: Box::new(file_network),
+            });
+        }
+
+        let mut book = Self::new(path, network);
+        for (name, entry) in file.entries {
+            let account_id = book.parse_id(&entry.account_id)?;
+            book.insert(&name, account_id, entry.role)?;
+        }
+        Ok(book)
+    }
+
+    /// Opens the address book named by [`ADDRESS_BOOK_ENV`], or the one in the current
+    /// workspace.
+    pub fn from_env(network: NetworkId) -> Result<Self, AddressBookError> {
+        let path = match env::var(ADDRESS_BOOK_ENV) {
+            Ok(path) => PathBuf::from(path),
+            Err(_) => Workspace::current()?.address_book_path(),
+        };
+        Self::open(path, network)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn network(&self) -> NetworkId {
+        self.network
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &Entry)> {
+        self.entries
+            .iter()
+            .map(|(name, entry)| (name.as_str(), entry))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Entry> {
+        self.entries.get(name)
+    }
+
+    /// Names `account_id`. Fails if the name is taken, use [`Self::remove`] first to rename.
+    pub fn insert(
+        &mut self,
+        name: &str,
+        account_id: AccountId,
+        role: Role,
+    ) -> Result<(), AddressBookError> {
+        self.check_name(name)?;
+        if self.entries.contains_key(name) {
+            return Err(AddressBookError::DuplicateName(name.to_string()));
+        }
+        self.entries
+            .insert(name.to_string(), Entry { account_id, role });
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Entry> {
+        self.entries.remove(name)
+    }
+
+    /// The name of `account_id`, if it has one.
+    pub fn name_of(&self, account_id: AccountId) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(_, entry)| entry.account_id == account_id)
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Resolves a name, a bech32 id of this book's network or a `0x` hex id.
+    pub fn resolve(&self, input: &str) -> Result<AccountId, AddressBookError> {
+        let input = input.trim();
+        if let Some(entry) = self.entries.get(input) {
+            return Ok(entry.account_id);
+        }
+        if input.starts_with("0x") || AccountId::from_bech32(input).is_ok() {
+            return self.parse_id(input);
+        }
+        Err(AddressBookError::UnknownName(input.to_string()))
+    }
+
+    /// Resolves like [`Self::resolve`], but a name has to have `role`.
+    pub fn resolve_as(&self, input: &str, role: Role) -> Result<AccountId, AddressBookError> {
+        match self.entries.get(input.trim()) {
+            Some(entry) if entry.role != role => Err(AddressBookError::WrongRole {
+                name: input.trim().to_string(),
+                expected: role,
+                found: entry.role,
+            }),
+            _ => self.resolve(input),
+        }
+    }
+
+    /// Formats `account_id` as its name, or as bech32 if it has none.
+    pub fn display(&self, account_id: AccountId) -> String {
+        self.name_of(account_id)
+            .map(str::to_string)
+            .unwrap_or_else(|| account_id.to_bech32(self.network))
+    }
+
+    pub fn save(&self) -> Result<(), AddressBookError> {
+        let file = BookFile {
+            network: self.network.as_str().to_string(),
+            entries: self
+                .entries
+                .iter()
+                .map(|(name, entry)| {
+                    let entry = EntryFile {
+                        account_id: entry.account_id.to_bech32(self.network),
+                        role: entry.role,
+                    };
+                    (name.clone(), entry)
+                })
+                .collect(),
+        };
+        let json = serde_json::to_vec_pretty(&file)
+            .map_err(|err| AddressBookError::InvalidFile(err.to_string()))?;
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, json)?;
+        Ok(())
+    }
+
+    /// Parses a bech32 id, which has to be for this book's network, or a `0x` hex id.
+    fn parse_id(&self, input: &str) -> Result<AccountId, AddressBookError> {
+        if input.starts_with("0x") {
+            return AccountId::from_hex(input)
+                .map_err(|err| AddressBookError::InvalidAccountId(err.to_string()));
+        }
+        let (network, account_id) = AccountId::from_bech32(input)
+            .map_err(|err| AddressBookError::InvalidAccountId(err.to_string()))?;
+        if network != self.network {
+            return Err(AddressBookError::WrongNetwork {
+                expected: Box::new(self.network),
+                found: Box::new(network),
+            });
+        }
+        Ok(account_id)
+    }
+
+    fn check_name(&self, name: &str) -> Result<(), AddressBookError> {
+        let valid_chars = name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+        let starts_with_letter = name.starts_with(|c: char| c.is_ascii_lowercase());
+        // `mtst1...` style names would shadow the bech32 ids they look like
+        let looks_like_id = name.starts_with(&format!("{}1", self.network.as_str()));
+        if !valid_chars || !starts_with_letter || looks_like_id {
+            return Err(AddressBookError::InvalidName(name.to_string()));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,343 @@
+//! Benchmark harness for chains of unauthenticated notes.
+//!
+//! Every chain passes a P2ID note from one account to the next, consuming each note as an
+//! unauthenticated input note right after it was created. The time spent building, executing,
+//! proving and submitting every transaction is recorded. Once the chains are done,
+//! [`record_inclusion`] waits for the consume transactions to be committed and records the block
+//! each of them landed in.
+//! Reports are written as JSON (summary and raw steps) and CSV (raw steps) so runs against
+//! different client versions can be compared.
+
+use std::{
+    fmt::Write as _,
+    time::{Duration, Instant},
+};
+
+use miden_client::{
+    account::{Account, AccountId},
+    asset::FungibleAsset,
+    note::{create_p2id_note, NoteType},
+    store::TransactionFilter,
+    transaction::{
+        OutputNote, TransactionId, TransactionRequest, TransactionRequestBuilder, TransactionStatus,
+    },
+    Client, ClientError, Felt,
+};
+use serde::{Serialize, Serializer};
+
+/// Share of private notes in a chain.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct NoteMix {
+    /// Between 0 (only public notes) and 1 (only private notes).
+    pub private_ratio: f64,
+}
+
+impl NoteMix {
+    pub fn new(private_ratio: f64) -> Self {
+        Self {
+            private_ratio: private_ratio.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Spreads private notes evenly over a chain, e.g. every other note for a ratio of 0.5.
+    pub fn note_type(&self, step: usize) -> NoteType {
+        let private_before = (step as f64 * self.private_ratio).floor();
+        let private_after = ((step + 1) as f64 * self.private_ratio).floor();
+        if private_after > private_before {
+            NoteType::Private
+        } else {
+            NoteType::Public
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchConfig {
+    /// Free-form label for the run, e.g. the client version under test.
+    pub label: String,
+    /// Number of chains running concurrently.
+    pub chains: usize,
+    /// Number of notes passed along each chain.
+    pub chain_length: usize,
+    pub note_mix: NoteMix,
+    /// Amount moved by every note.
+    pub amount: u64,
+}
+
+/// Latencies of one note transfer, i.e. one send and one consume transaction.
+///
+/// Each phase adds up the time of both transactions.
+#[derive(Debug, Clone, Serialize)]
+pub struct StepRecord {
+    pub chain: usize,
+    pub step: usize,
+    pub private: bool,
+    pub build_ms: f64,
+    pub execute_ms: f64,
+    pub prove_ms: f64,
+    pub submit_ms: f64,
+    pub total_ms: f64,
+    #[serde(serialize_with = "serialize_transaction_id")]
+    pub consume_tx: TransactionId,
+    /// Block the consume transaction was committed in, `None` until [`record_inclusion`] saw it
+    /// committed and for transactions the node discarded.
+    pub block_num: Option<u32>,
+}
+
+fn serialize_transaction_id<S: Serializer>(
+    id: &TransactionId,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&id.to_hex())
+}
+
+#[derive(Debug, Default)]
+struct StepTimer {
+    build: Duration,
+    execute: Duration,
+    prove: Duration,
+    submit: Duration,
+}
+
+impl StepTimer {
+    /// Executes, proves and submits a transaction, recording the time of each phase.
+    async fn run(
+        &mut self,
+        client: &mut Client,
+        account_id: AccountId,
+        request: TransactionRequest,
+    ) -> Result<TransactionId, ClientError> {
+        let start = Instant::now();
+        let tx_result = client.new_transaction(account_id, request).await?;
+        self.execute += start.elapsed();
+
+        let start = Instant::now();
+        let proven = client.testing_prove_transaction(&tx_result).await?;
+        self.prove += start.elapsed();
+
+        // The node answers with its chain tip, the inclusion block is only known once the
+        // transaction is committed
+        let start = Instant::now();
+        let id = tx_result.executed_transaction().id();
+        client.testing_submit_proven_transaction(proven).await?;
+        client.testing_apply_transaction(tx_result).await?;
+        self.submit += start.elapsed();
+
+        Ok(id)
+    }
+}
+
+/// Runs one chain over `accounts`, where the first account holds the funds.
+pub async fn run_chain(
+    client: &mut Client,
+    chain: usize,
+    accounts: &[Account],
+    faucet_id: AccountId,
+    config: &BenchConfig,
+) -> Result<Vec<StepRecord>, ClientError> {
+    let mut records = Vec::with_capacity(config.chain_length);
+
+    for (step, pair) in accounts.windows(2).take(config.chain_length).enumerate() {
+        let (sender, receiver) = (pair[0].id(), pair[1].id());
+        let note_type = config.note_mix.note_type(step);
+        let mut timer = StepTimer::default();
+        let step_start = Instant::now();
+
+        let start = Instant::now();
+        let asset = FungibleAsset::new(faucet_id, config.amount)?;
+        let note = create_p2id_note(
+            sender,
+            receiver,
+            vec![asset.into()],
+            note_type,
+            Felt::new(0),
+            client.rng(),
+        )?;
+        let send_request = TransactionRequestBuilder::new()
+            .own_output_notes(vec![OutputNote::Full(note.clone())])
+            .build()?;
+        timer.build += start.elapsed();
+        timer.run(client, sender, send_request).await?;
+
+        let start = Instant::now();
+        let consume_request = TransactionRequestBuilder::new()
+            .unauthenticated_input_notes([(note, None)])
+            .build()?;
+        timer.build += start.elapsed();
+        let consume_tx = timer.run(client, receiver, consume_request).await?;
+
+        records.push(StepRecord {
+            chain,
+            step,
+            private: note_type == NoteType::Private,
+            build_ms: millis(timer.build),
+            execute_ms: millis(timer.execute),
+            prove_ms: millis(timer.prove),
+            submit_ms: millis(timer.submit),
+            total_ms: millis(step_start.elapsed()),
+            consume_tx,
+            block_num: None,
+        });
+    }
+
+    Ok(records)
+}
+
+/// Syncs `client` every `poll_interval` until the consume transaction of every step is committed
+/// or discarded, and records the block each committed one landed in.
+pub async fn record_inclusion(
+    client: &mut Client,
+    steps: &mut [StepRecord],
+    poll_interval: Duration,
+) -> Result<(), ClientError> {
+    let mut pending: Vec<TransactionId> = steps.iter().map(|step| step.consume_tx).collect();
+
+    while !pending.is_empty() {
+        client.sync_state().await?;
+        let transactions = client
+            .get_transactions(TransactionFilter::Ids(pending.clone()))
+            .await?;
+        for record in transactions {
+            let block_num = match record.status {
+                TransactionStatus::Committed(block_num) => Some(block_num.as_u32()),
+                TransactionStatus::Discarded(_) => None,
+                TransactionStatus::Pending => continue,
+            };
+            for step in steps.iter_mut().filter(|step| step.consume_tx == record.id) {
+                step.block_num = block_num;
+            }
+            pending.retain(|id| *id != record.id);
+        }
+
+        if !pending.is_empty() {
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Percentiles of one phase, in milliseconds.
+#[derive(Debug, Clone, Serialize)]
+pub struct Percentiles {
+    pub min: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub max: f64,
+    pub mean: f64,
+}
+
+impl Percentiles {
+    /// Uses the nearest-rank method. Returns zeros for no samples.
+    pub fn from_samples(samples: impl IntoIterator<Item = f64>) -> Self {
+        let mut samples: Vec<f64> = samples.into_iter().collect();
+        if samples.is_empty() {
+            return Self {
+                min: 0.0,
+                p50: 0.0,
+                p90: 0.0,
+                p99: 0.0,
+                max: 0.0,
+                mean: 0.0,
+            };
+        }
+        samples.sort_by(f64::total_cmp);
+
+        let rank = |percentile: f64| {
+            let index = (percentile / 100.0 * samples.len() as f64).ceil() as usize;
+            samples[index.saturating_sub(1)]
+        };
+        Self {
+            min: samples[0],
+            p50: rank(50.0),
+            p90: rank(90.0),
+            p99: rank(99.0),
+            max: samples[samples.len() - 1],
+            mean: samples.iter().sum::<f64>() / samples.len() as f64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PhaseSummary {
+    pub build: Percentiles,
+    pub execute: Percentiles,
+    pub prove: Percentiles,
+    pub submit: Percentiles,
+    pub total: Percentiles,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchReport {
+    pub config: BenchConfig,
+    pub elapsed_ms: f64,
+    /// Note transfers per second over the whole run.
+    pub transfers_per_second: f64,
+    /// Distinct blocks the committed consume transactions landed in, in ascending order.
+    pub landed_blocks: Vec<u32>,
+    pub summary: PhaseSummary,
+    pub steps: Vec<StepRecord>,
+}
+
+impl BenchReport {
+    pub fn new(config: BenchConfig, elapsed: Duration, steps: Vec<StepRecord>) -> Self {
+        let phase =
+            |select: fn(&StepRecord) -> f64| Percentiles::from_samples(steps.iter().map(select));
+        let summary = PhaseSummary {
+            build: phase(|step| step.build_ms),
+            execute: phase(|step| step.execute_ms),
+            prove: phase(|step| step.prove_ms),
+            submit: phase(|step| step.submit_ms),
+            total: phase(|step| step.total_ms),
+        };
+
+        let mut landed_blocks: Vec<u32> = steps.iter().filter_map(|step| step.block_num).collect();
+        landed_blocks.sort_unstable();
+        landed_blocks.dedup();
+
+        Self {
+            config,
+            elapsed_ms: millis(elapsed),
+            transfers_per_second: steps.len() as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+            landed_blocks,
+            summary,
+            steps,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("report only contains serializable values")
+    }
+
+    /// One row per step.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "chain,step,private,build_ms,execute_ms,prove_ms,submit_ms,total_ms,consume_tx,block_num\n",
+        );
+        for step in &self.steps {
+            writeln!(
+                csv,
+                "{},{},{},{:.3},{:.3},{:.3},{:.3},{:.3},{},{}",
+                step.chain,
+                step.step,
+                step.private,
+                step.build_ms,
+                step.execute_ms,
+                step.prove_ms,
+                step.submit_ms,
+                step.total_ms,
+                step.consume_tx.to_hex(),
+                step.block_num
+                    .map(|block_num| block_num.to_string())
+                    .unwrap_or_default()
+            )
+            .expect("writing to a string cannot fail");
+        }
+        csv
+    }
+}
@@ -0,0 +1,98 @@
+use miden_client::{account::AccountId, rpc::Endpoint, ClientError};
+use miden_objects::account::NetworkId;
+use rust_client::{
+    account_bundle::{import_bundle, AccountBundle},
+    address_book::AddressBook,
+    workspace::Workspace,
+};
+
+const USAGE: &str = "usage:
+  account_bundle export <account> <file>     write an account with its seed and keys to a file
+  account_bundle import <file> [--overwrite] add a bundled account and its keys to this client
+
+The bundle holds the account's secret keys in plaintext, keep it as safe as ./keystore.";
+
+#[tokio::main]
+async fn main() -> Result<(), Box<ClientError>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("export") if args.len() == 3 => {
+            let account_id = AddressBook::from_env(NetworkId::Testnet)
+                .unwrap()
+                .resolve(&args[1])
+                .unwrap();
+            export(account_id, &args[2]).await
+        }
+        Some("import") if args.len() >= 2 => {
+            let overwrite = args[2..].iter().any(|arg| arg == "--overwrite");
+            import(&args[1], overwrite).await
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn export(account_id: AccountId, path: &str) -> Result<(), Box<ClientError>> {
+    let workspace = Workspace::current().unwrap();
+    let mut client = workspace
+        .instantiate_client(Endpoint::testnet(), None)
+        .await
+        .unwrap();
+    let keystore = workspace.open_keystore().unwrap();
+    client.sync_state().await?;
+
+    let record = client.try_get_account(account_id).await?;
+    let bundle = AccountBundle::from_record(&record, |pub_key| keystore.get_key(pub_key)).unwrap();
+    bundle.write(path).unwrap();
+
+    println!(
+        "Exported {} with {} key(s) to {path}",
+        account_id.to_bech32(NetworkId::Testnet),
+        bundle.secret_keys.len()
+    );
+    println!("State commitment: {}", bundle.commitment.to_hex());
+    if bundle.seed.is_some() {
+        println!("The account is not deployed yet, the bundle includes its seed");
+    }
+
+    Ok(())
+}
+
+async fn import(path: &str, overwrite: bool) -> Result<(), Box<ClientError>> {
+    let workspace = Workspace::current().unwrap();
+    let mut client = workspace
+        .instantiate_client(Endpoint::testnet(), None)
+        .await
+        .unwrap();
+    let keystore = workspace.open_keystore().unwrap();
+    client.sync_state().await?;
+
+    let bundle = AccountBundle::read(path).unwrap();
+    let account_id = bundle.account.id();
+    let commitment = bundle.commitment;
+    for key in import_bundle(&mut client, bundle, overwrite).await.unwrap() {
+        keystore.add_key(&key).unwrap();
+    }
+    println!("Imported {}", account_id.to_bech32(NetworkId::Testnet));
+
+    // Syncing brings a public account up to its on-chain state, a private one has to match it
+    client.sync_state().await?;
+    let (header, status) = client
+        .get_account_header_by_id(account_id)
+        .await?
+        .expect("the account was just imported");
+    if header.commitment() != commitment {
+        println!(
+            "The bundle was stale: exported at {}, the account is now at {}",
+            commitment.to_hex(),
+            header.commitment().to_hex()
+        );
+    }
+    if status.is_locked() {
+        println!("The account is locked, its on-chain state is newer than the bundle");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use miden_objects::account::NetworkId;
+use rust_client::address_book::{AddressBook, Role};
+
+const USAGE: &str = "usage:
+  address_book add <name> <account_id> <role>  name a testnet account, role is wallet, faucet or
+                                               contract
+  address_book remove <name>                   forget a name
+  address_book list                            list the named accounts
+
+The address book is address_book.json in the current workspace, or the file named by
+MIDEN_ADDRESS_BOOK. Every flow that takes an account id also takes a name from it.";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut address_book = AddressBook::from_env(NetworkId::Testnet).unwrap();
+    match args.first().map(String::as_str) {
+        Some("add") if args.len() == 4 => {
+            let account_id = address_book.resolve(&args[2]).unwrap();
+            let role: Role = args[3].parse().unwrap();
+            address_book.insert(&args[1], account_id, role).unwrap();
+            address_book.save().unwrap();
+            println!("Added {} as {role} {}", args[1], args[2]);
+        }
+        Some("remove") if args.len() == 2 => {
+            if address_book.remove(&args[1]).is_none() {
+                eprintln!("{} is not in the address book", args[1]);
+                std::process::exit(1);
+            }
+            address_book.save().unwrap();
+            println!("Removed {}", args[1]);
+        }
+        Some("list") if args.len() == 1 => {
+            for (name, entry) in address_book.entries() {
+                println!(
+                    "{name:<20} {:<8} {}",
+                    entry.role,
+                    entry.account_id.to_bech32(address_book.network())
+                );
+            }
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use miden_client::{
+    asset::FungibleAsset,
+    crypto::FeltRng,
+    note::NoteType,
+    rpc::Endpoint,
+    transaction::{OutputNote, TransactionRequestBuilder},
+    ClientError, Felt,
+};
+use miden_client_tools::mint_from_faucet_for_account;
+use miden_objects::account::NetworkId;
+use rust_client::{
+    accounts::{create_basic_account, create_basic_faucet},
+    notes::PreimageNote,
+    workspace::Workspace,
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<ClientError>> {
+    // Initialize client & keystore
+    let endpoint = Endpoint::testnet();
+    let workspace = Workspace::current().unwrap();
+    let mut client = workspace.instantiate_client(endpoint, None).await.unwrap();
+
+    let keystore = workspace.open_keystore().unwrap();
+
+    let sync_summary = client.sync_state().await.unwrap();
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create accounts, deploy faucet and fund Alice
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating new accounts");
+    let (alice_account, _) = create_basic_account(&mut client, keystore.clone()).await?;
+    println!(
+        "Alice's account ID: {:?}",
+        alice_account.id().to_bech32(NetworkId::Testnet)
+    );
+    let (bob_account, _) = create_basic_account(&mut client, keystore.clone()).await?;
+    println!(
+        "Bob's account ID: {:?}",
+        bob_account.id().to_bech32(NetworkId::Testnet)
+    );
+
+    let faucet = create_basic_faucet(&mut client, keystore).await?;
+    println!(
+        "Faucet account ID: {:?}",
+        faucet.id().to_bech32(NetworkId::Testnet)
+    );
+    client.sync_state().await?;
+
+    mint_from_faucet_for_account(&mut client, &alice_account, &faucet, 100, None).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Create a preimage note bound to Bob
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Create preimage note bound to Bob");
+    let secret = [Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)];
+    let asset = FungibleAsset::new(faucet.id(), 100).unwrap();
+
+    // The builder hashes the secret and stores Bob's account id in the note inputs
+    let preimage_note = PreimageNote::new(secret, bob_account.id(), vec![asset.into()]);
+    println!("digest: {:?}", preimage_note.digest());
+
+    let serial_num = client.rng().draw_word();
+    let custom_note = preimage_note
+        .build(alice_account.id(), NoteType::Public, serial_num)
+        .unwrap();
+    println!("note hash: {:?}", custom_note.id().to_hex());
+
+    let note_request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(custom_note.clone())])
+        .build()
+        .unwrap();
+    let tx_result = client
+        .new_transaction(alice_account.id(), note_request)
+        .await?;
+    println!(
+        "View transaction on MidenScan: https://testnet.midenscan.com/tx/{:?}",
+        tx_result.executed_transaction().id()
+    );
+    client.submit_transaction(tx_result).await?;
+    client.sync_state().await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Bob consumes the note with the secret
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Bob consumes the note with the correct secret");
+
+    // Anyone else providing the same secret would fail the account id check
+    let consume_request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(custom_note, Some(preimage_note.secret()))])
+        .build()
+        .unwrap();
+    let tx_result = client
+        .new_transaction(bob_account.id(), consume_request)
+        .await?;
+    println!(
+        "Consumed Note Tx on MidenScan: https://testnet.midenscan.com/tx/{:?}",
+        tx_result.executed_transaction().id()
+    );
+    println!("account delta: {:?}", tx_result.account_delta().vault());
+    client.submit_transaction(tx_result).await?;
+
+    Ok(())
+}
@@ -7,8 +7,7 @@ use miden_assembly::{
 };
 use miden_client::{
     account::{AccountBuilder, AccountStorageMode, AccountType, StorageSlot},
-    builder::ClientBuilder,
-    rpc::{Endpoint, TonicRpcClient},
+    rpc::Endpoint,
     transaction::{TransactionKernel, TransactionRequestBuilder, TransactionScript},
     ClientError, Felt,
 };
@@ -17,6 +16,7 @@ use miden_objects::{
     assembly::Assembler,
     assembly::DefaultSourceManager,
 };
+use rust_client::workspace::Workspace;
 
 fn create_library(
     assembler: Assembler,
@@ -34,18 +34,11 @@ fn create_library(
 }
 
 #[tokio::main]
-async fn main() -> Result<(), ClientError> {
+async fn main() -> Result<(), Box<ClientError>> {
     // Initialize client
     let endpoint = Endpoint::testnet();
-    let timeout_ms = 10_000;
-    let rpc_api = Arc::new(TonicRpcClient::new(&endpoint, timeout_ms));
-
-    let mut client = ClientBuilder::new()
-        .rpc(rpc_api)
-        .filesystem_keystore("./keystore")
-        .in_debug_mode(true)
-        .build()
-        .await?;
+    let workspace = Workspace::current().unwrap();
+    let mut client = workspace.instantiate_client(endpoint, None).await.unwrap();
 
     let sync_summary = client.sync_state().await.unwrap();
     println!("Latest block: {}", sync_summary.block_num);
@@ -5,13 +5,16 @@ use miden_assembly::{
     LibraryPath,
 };
 use miden_client::{
-    account::AccountId,
-    builder::ClientBuilder,
-    rpc::{Endpoint, TonicRpcClient},
+    rpc::Endpoint,
     transaction::{TransactionKernel, TransactionRequestBuilder, TransactionScript},
     ClientError,
 };
-use miden_objects::{assembly::Assembler, assembly::DefaultSourceManager};
+use miden_objects::{account::NetworkId, assembly::Assembler, assembly::DefaultSourceManager};
+use rust_client::{address_book::AddressBook, workspace::Workspace};
+
+/// The counter contract deployed by `counter_contract_deploy`, unless a contract is passed as
+/// the first argument.
+const DEFAULT_COUNTER_CONTRACT: &str = "mtst1qz43ftxkrzcjsqz3hpw332qwny2ggsp0";
 
 fn create_library(
     assembler: Assembler,
@@ -29,18 +32,11 @@ fn create_library(
 }
 
 #[tokio::main]
-async fn main() -> Result<(), ClientError> {
+async fn main() -> Result<(), Box<ClientError>> {
     // Initialize client
     let endpoint = Endpoint::testnet();
-    let timeout_ms = 10_000;
-    let rpc_api = Arc::new(TonicRpcClient::new(&endpoint, timeout_ms));
-
-    let mut client = ClientBuilder::new()
-        .rpc(rpc_api)
-        .filesystem_keystore("./keystore")
-        .in_debug_mode(true)
-        .build()
-        .await?;
+    let workspace = Workspace::current().unwrap();
+    let mut client = workspace.instantiate_client(endpoint, None).await.unwrap();
 
     let sync_summary = client.sync_state().await.unwrap();
     println!("Latest block: {}", sync_summary.block_num);
@@ -50,9 +46,17 @@ async fn main() -> Result<(), ClientError> {
     // -------------------------------------------------------------------------
     println!("\n[STEP 1] Reading data from public state");
 
-    // Define the Counter Contract account id from counter contract deploy
-    let (_, counter_contract_id) =
-        AccountId::from_bech32("mtst1qz43ftxkrzcjsqz3hpw332qwny2ggsp0").unwrap();
+    // Define the Counter Contract account id from counter contract deploy, a name from the
+    // address book or a bech32 id
+    let counter_contract_arg = std::env::args().nth(1);
+    let counter_contract_id = AddressBook::from_env(NetworkId::Testnet)
+        .unwrap()
+        .resolve(
+            counter_contract_arg
+                .as_deref()
+                .unwrap_or(DEFAULT_COUNTER_CONTRACT),
+        )
+        .unwrap();
 
     client
         .import_account_by_id(counter_contract_id)
@@ -1,43 +1,50 @@
 use rand::RngCore;
-use std::sync::Arc;
+use std::path::Path;
 use tokio::time::Duration;
 
 use miden_client::{
     account::{
-        component::{BasicFungibleFaucet, BasicWallet, RpoFalcon512},
-        AccountBuilder, AccountId, AccountStorageMode, AccountType,
+        component::{BasicWallet, RpoFalcon512},
+        AccountBuilder, AccountStorageMode, AccountType,
     },
-    asset::{FungibleAsset, TokenSymbol},
+    asset::FungibleAsset,
     auth::AuthSecretKey,
-    builder::ClientBuilder,
     crypto::SecretKey,
-    keystore::FilesystemKeyStore,
-    note::{create_p2id_note, NoteType},
-    rpc::{Endpoint, TonicRpcClient},
+    note::create_p2id_note,
+    rpc::Endpoint,
     transaction::{OutputNote, PaymentNoteDescription, TransactionRequestBuilder},
     ClientError, Felt,
 };
-use miden_objects::account::{AccountIdVersion, NetworkId};
+use miden_objects::account::NetworkId;
+use rust_client::{
+    address_book::AddressBook,
+    consolidation::{consolidate, ConsolidationPolicy},
+    faucet::FaucetSpec,
+    note_policy::{NoteDelivery, NotePolicy},
+    reclaim::{reclaim, reclaimable_after},
+    workspace::Workspace,
+};
+
+/// Number of blocks the target of a reclaimable payment has to claim it.
+const RECLAIM_AFTER: u32 = 10;
 
 #[tokio::main]
-async fn main() -> Result<(), ClientError> {
+async fn main() -> Result<(), Box<ClientError>> {
     // Initialize client & keystore
     let endpoint = Endpoint::testnet();
-    let timeout_ms = 10_000;
-    let rpc_api = Arc::new(TonicRpcClient::new(&endpoint, timeout_ms));
-
-    let mut client = ClientBuilder::new()
-        .rpc(rpc_api)
-        .filesystem_keystore("./keystore")
-        .in_debug_mode(true)
-        .build()
-        .await?;
+    let workspace = Workspace::current().unwrap();
+    let mut client = workspace.instantiate_client(endpoint, None).await.unwrap();
 
     let sync_summary = client.sync_state().await.unwrap();
     println!("Latest block: {}", sync_summary.block_num);
 
-    let keystore: FilesystemKeyStore<rand::prelude::StdRng> =
-        FilesystemKeyStore::new("./keystore".into()).unwrap();
+    let keystore = workspace.open_keystore().unwrap();
+
+    // Public or private notes, set with MIDEN_NOTE_TYPE or a MIDEN_NOTE_POLICY file
+    let address_book = AddressBook::from_env(NetworkId::Testnet).unwrap();
+    let note_policy = NotePolicy::from_env(&address_book).unwrap();
+    // Private notes only put their commitment on chain, their details are exported here
+    let notes_dir = Path::new("./notes");
 
     //------------------------------------------------------------
     // STEP 1: Create a basic wallet for Alice
@@ -83,20 +90,16 @@ async fn main() -> Result<(), ClientError> {
     let mut init_seed = [0u8; 32];
     client.rng().fill_bytes(&mut init_seed);
 
-    // Faucet parameters
-    let symbol = TokenSymbol::new("MID").unwrap();
-    let decimals = 8;
-    let max_supply = Felt::new(1_000_000);
+    // Faucet parameters, read from the file named by MIDEN_FAUCET_SPEC if set
+    let faucet_spec = FaucetSpec::from_env().unwrap();
 
     // Generate key pair
     let key_pair = SecretKey::with_rng(client.rng());
 
     // Build the account
-    let builder = AccountBuilder::new(init_seed)
-        .account_type(AccountType::FungibleFaucet)
-        .storage_mode(AccountStorageMode::Public)
-        .with_auth_component(RpoFalcon512::new(key_pair.public_key()))
-        .with_component(BasicFungibleFaucet::new(symbol, decimals, max_supply).unwrap());
+    let builder = faucet_spec
+        .account_builder(init_seed, key_pair.public_key())
+        .unwrap();
 
     let (faucet_account, seed) = builder.build().unwrap();
 
@@ -132,7 +135,7 @@ async fn main() -> Result<(), ClientError> {
             .build_mint_fungible_asset(
                 fungible_asset,
                 alice_account.id(),
-                NoteType::Public,
+                note_policy.note_type_for(alice_account.id()),
                 client.rng(),
             )
             .unwrap();
@@ -155,60 +158,80 @@ async fn main() -> Result<(), ClientError> {
     //------------------------------------------------------------
     println!("\n[STEP 4] Alice will now consume all of her notes to consolidate them.");
 
-    // Consume all minted notes in a single transaction
-    loop {
+    // Consume the minted notes as they get committed, as many per transaction as the kernel
+    // allows
+    let consolidation_policy = ConsolidationPolicy::all().for_faucet(faucet_account.id());
+    let mut consumed = 0;
+    while consumed < 5 {
         // Resync to get the latest data
         client.sync_state().await?;
 
-        let consumable_notes = client
-            .get_consumable_notes(Some(alice_account.id()))
-            .await?;
-        let list_of_note_ids: Vec<_> = consumable_notes.iter().map(|(note, _)| note.id()).collect();
-
-        if list_of_note_ids.len() == 5 {
-            println!("Found 5 consumable notes for Alice. Consuming them now...");
-            let transaction_request = TransactionRequestBuilder::new()
-                .build_consume_notes(list_of_note_ids)
-                .unwrap();
-            let tx_execution_result = client
-                .new_transaction(alice_account.id(), transaction_request)
-                .await?;
-
-            client.submit_transaction(tx_execution_result).await?;
-            println!("All of Alice's notes consumed successfully.");
-            break;
-        } else {
+        let report = consolidate(&mut client, alice_account.id(), &consolidation_policy).await?;
+        if report.consumed.is_empty() {
+            println!("Alice has consumed {consumed} of 5 notes. Waiting for the rest...");
+            tokio::time::sleep(Duration::from_secs(3)).await;
+            continue;
+        }
+
+        consumed += report.consumed.len();
+        println!(
+            "Consumed {} notes in {} transaction(s).",
+            report.consumed.len(),
+            report.transactions.len()
+        );
+        for (faucet_id, balance) in &report.balances {
             println!(
-                "Currently, Alice has {} consumable notes. Waiting...",
-                list_of_note_ids.len()
+                "Alice's balance of {}: {}",
+                faucet_id.to_bech32(NetworkId::Testnet),
+                balance
             );
-            tokio::time::sleep(Duration::from_secs(3)).await;
         }
     }
 
     //------------------------------------------------------------
-    // STEP 5: Alice sends 5 notes of 50 tokens to 5 users
+    // STEP 5: Create wallets for 5 users
     //------------------------------------------------------------
-    println!("\n[STEP 5] Alice sends 5 notes of 50 tokens each to 5 different users.");
+    println!("\n[STEP 5] Creating wallets for 5 users.");
+
+    // Tokens sent to an account nobody holds the keys for are lost, so every payment goes to a
+    // real wallet
+    let mut target_account_ids = vec![];
+    for i in 1..=5 {
+        let mut init_seed = [0_u8; 32];
+        client.rng().fill_bytes(&mut init_seed);
+
+        let key_pair = SecretKey::with_rng(client.rng());
+
+        let builder = AccountBuilder::new(init_seed)
+            .account_type(AccountType::RegularAccountUpdatableCode)
+            .storage_mode(AccountStorageMode::Public)
+            .with_auth_component(RpoFalcon512::new(key_pair.public_key()))
+            .with_component(BasicWallet);
+
+        let (account, seed) = builder.build().unwrap();
+        client.add_account(&account, Some(seed), false).await?;
+        keystore
+            .add_key(&AuthSecretKey::RpoFalcon512(key_pair))
+            .unwrap();
+
+        println!(
+            "User {}'s account ID: {:?}",
+            i,
+            account.id().to_bech32(NetworkId::Testnet)
+        );
+        target_account_ids.push(account.id());
+    }
+
+    //------------------------------------------------------------
+    // STEP 6: Alice sends 5 notes of 50 tokens to 5 users
+    //------------------------------------------------------------
+    println!("\n[STEP 6] Alice sends 5 notes of 50 tokens each to 5 different users.");
 
     // Send 50 tokens to 4 accounts in one transaction
     println!("Creating multiple P2ID notes for 4 target accounts in one transaction...");
     let mut p2id_notes = vec![];
 
-    // Creating 4 P2ID notes to 4 'dummy' AccountIds
-    for _ in 1..=4 {
-        let init_seed: [u8; 15] = {
-            let mut init_seed = [0_u8; 15];
-            client.rng().fill_bytes(&mut init_seed);
-            init_seed
-        };
-        let target_account_id = AccountId::dummy(
-            init_seed,
-            AccountIdVersion::Version0,
-            AccountType::RegularAccountUpdatableCode,
-            AccountStorageMode::Public,
-        );
-
+    for &target_account_id in &target_account_ids[..4] {
         let send_amount = 50;
         let fungible_asset = FungibleAsset::new(faucet_account.id(), send_amount).unwrap();
 
@@ -216,15 +239,16 @@ async fn main() -> Result<(), ClientError> {
             alice_account.id(),
             target_account_id,
             vec![fungible_asset.into()],
-            NoteType::Public,
+            note_policy.note_type_for(target_account_id),
             Felt::new(0),
             client.rng(),
-        )?;
+        )
+        .map_err(ClientError::from)?;
         p2id_notes.push(p2id_note);
     }
 
     // Specifying output notes and creating a tx request to create them
-    let output_notes: Vec<OutputNote> = p2id_notes.into_iter().map(OutputNote::Full).collect();
+    let output_notes: Vec<OutputNote> = p2id_notes.iter().cloned().map(OutputNote::Full).collect();
     let transaction_request = TransactionRequestBuilder::new()
         .own_output_notes(output_notes)
         .build()
@@ -238,45 +262,98 @@ async fn main() -> Result<(), ClientError> {
     client.submit_transaction(tx_execution_result).await?;
     println!("Submitted a transaction with 4 P2ID notes.");
 
-    println!("Submitting one more single P2ID transaction...");
-    let init_seed: [u8; 15] = {
-        let mut init_seed = [0_u8; 15];
-        client.rng().fill_bytes(&mut init_seed);
-        init_seed
-    };
-    let target_account_id = AccountId::dummy(
-        init_seed,
-        AccountIdVersion::Version0,
-        AccountType::RegularAccountUpdatableCode,
-        AccountStorageMode::Public,
-    );
+    for note in &p2id_notes {
+        if let Some(note_id) = NoteDelivery::Export(notes_dir).deliver(note).await.unwrap() {
+            println!(
+                "Exported private note {} to {:?}",
+                note_id.to_hex(),
+                notes_dir
+            );
+        }
+    }
+
+    // The last payment is reclaimable: if the user does not claim it within RECLAIM_AFTER
+    // blocks, Alice can take the tokens back
+    println!("Submitting one more reclaimable P2ID transaction...");
+    let target_account_id = target_account_ids[4];
 
     let send_amount = 50;
     let fungible_asset = FungibleAsset::new(faucet_account.id(), send_amount).unwrap();
 
-    let payment_transaction = PaymentNoteDescription::new(
-        vec![fungible_asset.into()],
-        alice_account.id(),
-        target_account_id,
+    let current_block = client.get_sync_height().await?;
+    let payment_transaction = reclaimable_after(
+        PaymentNoteDescription::new(
+            vec![fungible_asset.into()],
+            alice_account.id(),
+            target_account_id,
+        ),
+        current_block,
+        RECLAIM_AFTER,
     );
+    let reclaim_height = payment_transaction.reclaim_height().unwrap();
+    println!("The payment can be reclaimed from block {}", reclaim_height);
 
     let transaction_request = TransactionRequestBuilder::new()
         .build_pay_to_id(
             payment_transaction,
-            NoteType::Public, // note type
-            client.rng(),     // rng
+            note_policy.note_type_for(target_account_id), // note type
+            client.rng(),                                 // rng
         )
         .unwrap();
     let tx_execution_result = client
         .new_transaction(alice_account.id(), transaction_request)
         .await?;
 
+    let payment_note = match tx_execution_result.created_notes().get_note(0) {
+        OutputNote::Full(note) => note.clone(),
+        _ => panic!("Expected Full note type"),
+    };
     client.submit_transaction(tx_execution_result).await?;
 
+    if let Some(note_id) = NoteDelivery::Export(notes_dir)
+        .deliver(&payment_note)
+        .await
+        .unwrap()
+    {
+        println!(
+            "Exported private note {} to {:?}",
+            note_id.to_hex(),
+            notes_dir
+        );
+    }
+
+    //------------------------------------------------------------
+    // STEP 7: Alice reclaims the unclaimed payment
+    //------------------------------------------------------------
+    println!("\n[STEP 7] Alice reclaims the payment once block {reclaim_height} has passed.");
+
+    loop {
+        client.sync_state().await?;
+        let sync_height = client.get_sync_height().await?;
+
+        if sync_height >= reclaim_height {
+            match reclaim(&mut client, alice_account.id()).await? {
+                Some((tx_id, note_ids)) => println!(
+                    "Reclaimed {} note(s) in transaction {}",
+                    note_ids.len(),
+                    tx_id.to_hex()
+                ),
+                None => println!(
+                    "Nothing to reclaim, the payment was claimed or is not reclaimable yet."
+                ),
+            }
+            break;
+        } else {
+            println!("Currently at block {}. Waiting...", sync_height);
+            tokio::time::sleep(Duration::from_secs(3)).await;
+        }
+    }
+
     println!("\nAll steps completed successfully!");
     println!("Alice created a wallet, a faucet was deployed,");
     println!("5 notes of 100 tokens were minted to Alice, those notes were consumed,");
-    println!("and then Alice sent 5 separate 50-token notes to 5 different users.");
+    println!("Alice sent 5 separate 50-token notes to 5 different users,");
+    println!("and then reclaimed the payment nobody claimed.");
 
     Ok(())
 }
@@ -1,31 +1,36 @@
 use std::sync::Arc;
 
-use miden_client::account::AccountId;
 use miden_client::crypto::FeltRng;
 use miden_client::{
     asset::FungibleAsset,
-    keystore::FilesystemKeyStore,
     note::NoteType,
     rpc::Endpoint,
-    transaction::{OutputNote, TransactionProver, TransactionRequestBuilder},
-    ClientError, Felt, RemoteTransactionProver,
+    transaction::{OutputNote, TransactionRequestBuilder},
+    ClientError, Felt,
 };
-use miden_client_tools::{
-    create_basic_account, create_exact_p2id_note, instantiate_client, mint_from_faucet_for_account,
+use miden_client_tools::{create_exact_p2id_note, mint_from_faucet_for_account};
+use miden_objects::account::NetworkId;
+use rust_client::{
+    accounts::create_basic_account, address_book::AddressBook, prover::ProverSelector,
+    workspace::Workspace,
 };
 
+/// The public testnet faucet minting to Alice, unless a faucet is passed as the first argument.
+const DEFAULT_FAUCET: &str = "mtst1qq932n3zkt5rxgpw5tgd9szxp58tllml";
+
 #[tokio::main]
-async fn main() -> Result<(), ClientError> {
+#[allow(clippy::let_unit_value, clippy::needless_borrow)]
+async fn main() -> Result<(), Box<ClientError>> {
     // Initialize client, keystore, & delegated prover endpoint
     let endpoint = Endpoint::testnet();
-    let mut client = instantiate_client(endpoint, None).await.unwrap();
+    let workspace = Workspace::current().unwrap();
+    let mut client = workspace.instantiate_client(endpoint, None).await.unwrap();
 
-    let keystore: FilesystemKeyStore<rand::prelude::StdRng> =
-        FilesystemKeyStore::new("./keystore".into()).unwrap();
+    let keystore = workspace.open_keystore().unwrap();
 
-    let remote_tx_prover: RemoteTransactionProver =
-        RemoteTransactionProver::new("https://tx-prover.testnet.miden.io");
-    let tx_prover: Arc<dyn TransactionProver + 'static> = Arc::new(remote_tx_prover);
+    // Remote provers from MIDEN_PROVERS, or the testnet delegated prover, with local proving
+    // as the fallback
+    let tx_prover = Arc::new(ProverSelector::from_env());
 
     let sync_summary = client.sync_state().await.unwrap();
     println!("Latest block: {}", sync_summary.block_num);
@@ -38,8 +43,12 @@ async fn main() -> Result<(), ClientError> {
         .await
         .unwrap();
 
-    // import public faucet id
-    let (_, faucet_id) = AccountId::from_bech32("mtst1qq932n3zkt5rxgpw5tgd9szxp58tllml").unwrap();
+    // import public faucet id, a name from the address book or a bech32 id
+    let faucet_arg = std::env::args().nth(1);
+    let faucet_id = AddressBook::from_env(NetworkId::Testnet)
+        .unwrap()
+        .resolve(faucet_arg.as_deref().unwrap_or(DEFAULT_FAUCET))
+        .unwrap();
     client.import_account_by_id(faucet_id).await.unwrap();
     let binding = client.get_account(faucet_id).await.unwrap().unwrap();
     let faucet = binding.account();
@@ -71,7 +80,8 @@ async fn main() -> Result<(), ClientError> {
             NoteType::Public,
             Felt::new(0),
             client.rng().draw_word(),
-        )?;
+        )
+        .map_err(ClientError::from)?;
         p2id_notes.push(p2id_note);
     }
 
@@ -92,6 +102,16 @@ async fn main() -> Result<(), ClientError> {
         .await
         .unwrap();
 
+    for record in tx_prover.records() {
+        println!(
+            "Transaction {} proven by the {} in {:?}",
+            record.transaction_id, record.source, record.proving_time
+        );
+        for failure in &record.failures {
+            println!("  {} failed: {}", failure.endpoint, failure.reason);
+        }
+    }
+
     client.sync_state().await.unwrap();
 
     let account = client
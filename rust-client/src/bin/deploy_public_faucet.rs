@@ -1,19 +1,23 @@
-use miden_client::{keystore::FilesystemKeyStore, rpc::Endpoint, ClientError};
-use miden_client_tools::{
-    create_basic_account, create_basic_faucet, delete_keystore_and_store, instantiate_client,
-    mint_from_faucet_for_account,
-};
+use miden_client::{rpc::Endpoint, ClientError};
+use miden_client_tools::mint_from_faucet_for_account;
 use miden_objects::account::NetworkId;
+use rust_client::{
+    accounts::{create_basic_account, create_basic_faucet},
+    workspace::Workspace,
+};
 #[tokio::main]
-async fn main() -> Result<(), ClientError> {
-    delete_keystore_and_store(None).await;
+#[allow(clippy::let_unit_value)]
+async fn main() -> Result<(), Box<ClientError>> {
+    // Start from an empty store and keystore, other workspaces are left alone
+    let workspace = Workspace::current().unwrap();
+    workspace.reset().unwrap();
+    println!("Reset workspace {}", workspace.name());
 
     // Initialize client, keystore, & delegated prover endpoint
     let endpoint = Endpoint::testnet();
-    let mut client = instantiate_client(endpoint, None).await.unwrap();
+    let mut client = workspace.instantiate_client(endpoint, None).await.unwrap();
 
-    let keystore: FilesystemKeyStore<rand::prelude::StdRng> =
-        FilesystemKeyStore::new("./keystore".into()).unwrap();
+    let keystore = workspace.open_keystore().unwrap();
 
     let sync_summary = client.sync_state().await.unwrap();
     println!("Latest block: {}", sync_summary.block_num);
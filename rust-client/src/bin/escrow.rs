@@ -0,0 +1,191 @@
+use rand::RngCore;
+use tokio::time::Duration;
+
+use miden_client::{
+    asset::FungibleAsset,
+    crypto::{FeltRng, SecretKey},
+    note::NoteType,
+    rpc::Endpoint,
+    transaction::{OutputNote, PaymentNoteDescription, TransactionRequestBuilder},
+    ClientError,
+};
+use miden_client_tools::mint_from_faucet_for_account;
+use miden_objects::account::NetworkId;
+use rust_client::{
+    accounts::{create_basic_account, create_basic_faucet},
+    escrow::{sign_release, EscrowPayout, EscrowTerms},
+    workspace::Workspace,
+};
+
+/// Number of blocks after which Alice can take back whatever was not released.
+const TIMEOUT_BLOCKS: u32 = 10;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<ClientError>> {
+    // Initialize client & keystore
+    let endpoint = Endpoint::testnet();
+    let workspace = Workspace::current().unwrap();
+    let mut client = workspace.instantiate_client(endpoint, None).await.unwrap();
+
+    let keystore = workspace.open_keystore().unwrap();
+
+    let sync_summary = client.sync_state().await.unwrap();
+    println!("Latest block: {}", sync_summary.block_num);
+
+    //------------------------------------------------------------
+    // STEP 1: Create Alice (depositor), Bob (beneficiary) and the arbiter
+    //------------------------------------------------------------
+    println!("\n[STEP 1] Creating Alice, Bob, a faucet and the arbiter's key");
+
+    let (alice, _) = create_basic_account(&mut client, keystore.clone()).await?;
+    let (bob, _) = create_basic_account(&mut client, keystore.clone()).await?;
+    let faucet = create_basic_faucet(&mut client, keystore).await?;
+    println!("Alice: {}", alice.id().to_bech32(NetworkId::Testnet));
+    println!("Bob: {}", bob.id().to_bech32(NetworkId::Testnet));
+
+    // The arbiter only needs a key pair; it never sends a transaction
+    let arbiter_key = SecretKey::new();
+
+    client.sync_state().await?;
+    mint_from_faucet_for_account(&mut client, &alice, &faucet, 100, None).await?;
+
+    //------------------------------------------------------------
+    // STEP 2: Deploy the escrow
+    //------------------------------------------------------------
+    println!("\n[STEP 2] Deploying the escrow");
+
+    let current_block = client.get_sync_height().await?;
+    let terms = EscrowTerms::new(
+        arbiter_key.public_key().into(),
+        alice.id(),
+        bob.id(),
+        current_block + TIMEOUT_BLOCKS,
+    );
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let (escrow, escrow_seed) = terms.build_account(init_seed).unwrap();
+    client
+        .add_account(&escrow, Some(escrow_seed), false)
+        .await?;
+    println!("Escrow: {}", escrow.id().to_bech32(NetworkId::Testnet));
+    println!("Alice can be refunded from block {}", terms.timeout);
+
+    //------------------------------------------------------------
+    // STEP 3: Alice deposits 100 tokens
+    //------------------------------------------------------------
+    println!("\n[STEP 3] Alice sends 100 tokens to the escrow, which deposits them");
+
+    let deposit = FungibleAsset::new(faucet.id(), 100).unwrap();
+    let transaction_request = TransactionRequestBuilder::new()
+        .build_pay_to_id(
+            PaymentNoteDescription::new(vec![deposit.into()], alice.id(), escrow.id()),
+            NoteType::Public,
+            client.rng(),
+        )
+        .unwrap();
+    let tx_execution_result = client
+        .new_transaction(alice.id(), transaction_request)
+        .await?;
+    let deposit_note = match tx_execution_result.created_notes().get_note(0) {
+        OutputNote::Full(note) => note.clone(),
+        _ => panic!("Expected Full note type"),
+    };
+    client.submit_transaction(tx_execution_result).await?;
+
+    // The escrow consumes the deposit right away as an unauthenticated note
+    let transaction_request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(deposit_note, None)])
+        .build()
+        .unwrap();
+    let tx_execution_result = client
+        .new_transaction(escrow.id(), transaction_request)
+        .await?;
+    client.submit_transaction(tx_execution_result).await?;
+
+    //------------------------------------------------------------
+    // STEP 4: The arbiter releases 60 tokens to Bob
+    //------------------------------------------------------------
+    println!("\n[STEP 4] The arbiter signs the release of 60 tokens to Bob");
+
+    let release = EscrowPayout::new(
+        FungibleAsset::new(faucet.id(), 60).unwrap().into(),
+        client.rng().draw_word(),
+    );
+
+    // The escrow records the serial number once it is released, so the signature works only once
+    let signature = sign_release(&arbiter_key, escrow.id(), &release).unwrap();
+
+    let transaction_request = terms.release_request(&[release], vec![signature]).unwrap();
+    let tx_execution_result = client
+        .new_transaction(escrow.id(), transaction_request)
+        .await?;
+    client.submit_transaction(tx_execution_result).await?;
+
+    // Bob claims the payout without waiting for it to be committed
+    let release_note = terms.payout_note(escrow.id(), bob.id(), &release).unwrap();
+    println!("Release note: {}", release_note.id().to_hex());
+    let transaction_request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(release_note, None)])
+        .build()
+        .unwrap();
+    let tx_execution_result = client
+        .new_transaction(bob.id(), transaction_request)
+        .await?;
+    client.submit_transaction(tx_execution_result).await?;
+
+    //------------------------------------------------------------
+    // STEP 5: After the timeout, the rest is refunded to Alice
+    //------------------------------------------------------------
+    println!(
+        "\n[STEP 5] Waiting for block {} to refund Alice",
+        terms.timeout
+    );
+
+    loop {
+        client.sync_state().await?;
+        let sync_height = client.get_sync_height().await?;
+        if sync_height >= terms.timeout {
+            break;
+        }
+        println!("Current block is {}. Waiting...", sync_height);
+        tokio::time::sleep(Duration::from_secs(3)).await;
+    }
+
+    // Refunds need no signature, anyone can trigger them once the escrow expired
+    let refund = EscrowPayout::new(
+        FungibleAsset::new(faucet.id(), 40).unwrap().into(),
+        client.rng().draw_word(),
+    );
+    let transaction_request = terms.refund_request(&[refund]).unwrap();
+    let tx_execution_result = client
+        .new_transaction(escrow.id(), transaction_request)
+        .await?;
+    client.submit_transaction(tx_execution_result).await?;
+
+    let refund_note = terms.payout_note(escrow.id(), alice.id(), &refund).unwrap();
+    println!("Refund note: {}", refund_note.id().to_hex());
+    let transaction_request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(refund_note, None)])
+        .build()
+        .unwrap();
+    let tx_execution_result = client
+        .new_transaction(alice.id(), transaction_request)
+        .await?;
+    client.submit_transaction(tx_execution_result).await?;
+
+    client.sync_state().await?;
+    for (name, account_id) in [
+        ("Alice", alice.id()),
+        ("Bob", bob.id()),
+        ("Escrow", escrow.id()),
+    ] {
+        let record = client.get_account(account_id).await?.unwrap();
+        println!(
+            "{name}: {} tokens",
+            record.account().vault().get_balance(faucet.id()).unwrap()
+        );
+    }
+
+    Ok(())
+}
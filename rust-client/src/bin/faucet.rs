@@ -0,0 +1,316 @@
+use std::path::Path;
+
+use rand::RngCore;
+
+use miden_client::{
+    account::{Account, AccountId},
+    asset::FungibleAsset,
+    auth::AuthSecretKey,
+    crypto::{FeltRng, SecretKey},
+    rpc::Endpoint,
+    transaction::{OutputNote, TransactionRequestBuilder},
+    Client, ClientError,
+};
+use miden_objects::account::NetworkId;
+use rust_client::{
+    address_book::{AddressBook, Role},
+    faucet::{reconcile_supply, FaucetInfo, FaucetSpec},
+    faucet_service::{FaucetService, FaucetServiceConfig, DEFAULT_FAUCET_ADDR},
+    note_policy::{NoteDelivery, NotePolicy},
+    notes::BurnNote,
+    workspace::Workspace,
+};
+use tokio::net::TcpListener;
+
+const USAGE: &str = "usage:
+  faucet deploy [name]                           deploy a faucet from MIDEN_FAUCET_SPEC, adding it
+                                                 to the address book under name
+  faucet info <faucet_id>                        show the token metadata and supply of a faucet
+  faucet mint <faucet_id> <account_id> <amount>  mint e.g. \"12.5 MID\" to an account
+  faucet burn <faucet_id> <account_id> <amount>  return tokens from an account to the faucet
+  faucet reconcile <faucet_id>                   compare the issued supply with the local store
+  faucet serve <faucet_id> [--local]             serve rate-limited mints over HTTP on 127.0.0.1:8080,
+                                                 against a local node with --local
+
+Accounts are given as bech32 ids or as names from the address book. Minted and burn notes are
+public unless MIDEN_NOTE_TYPE or a MIDEN_NOTE_POLICY file says otherwise, private minted notes
+are exported to ./notes.";
+
+#[tokio::main]
+async fn main() -> Result<(), Box<ClientError>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let address_book = AddressBook::from_env(NetworkId::Testnet).unwrap();
+    let faucet_id = || address_book.resolve_as(&args[1], Role::Faucet).unwrap();
+    match args.first().map(String::as_str) {
+        Some("deploy") if args.len() <= 2 => deploy(address_book, args.get(1)).await,
+        Some("info") if args.len() == 2 => info(faucet_id()).await,
+        Some("mint") if args.len() >= 4 => {
+            let target_id = address_book.resolve(&args[2]).unwrap();
+            let note_policy = NotePolicy::from_env(&address_book).unwrap();
+            mint(faucet_id(), target_id, &args[3..].join(" "), &note_policy).await
+        }
+        Some("burn") if args.len() >= 4 => {
+            let account_id = address_book.resolve(&args[2]).unwrap();
+            let note_policy = NotePolicy::from_env(&address_book).unwrap();
+            burn(faucet_id(), account_id, &args[3..].join(" "), &note_policy).await
+        }
+        Some("reconcile") if args.len() == 2 => reconcile(faucet_id()).await,
+        Some("serve") if args.len() >= 2 => {
+            let endpoint = if args[2..].iter().any(|arg| arg == "--local") {
+                Endpoint::localhost()
+            } else {
+                Endpoint::testnet()
+            };
+            serve(faucet_id(), endpoint).await
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn deploy(
+    mut address_book: AddressBook,
+    name: Option<&String>,
+) -> Result<(), Box<ClientError>> {
+    let workspace = Workspace::current().unwrap();
+    let mut client = workspace
+        .instantiate_client(Endpoint::testnet(), None)
+        .await
+        .unwrap();
+    let keystore = workspace.open_keystore().unwrap();
+    client.sync_state().await?;
+
+    let faucet_spec = FaucetSpec::from_env().unwrap();
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let key_pair = SecretKey::with_rng(client.rng());
+
+    let (faucet, seed) = faucet_spec
+        .build_account(init_seed, key_pair.public_key())
+        .unwrap();
+    client.add_account(&faucet, Some(seed), false).await?;
+    keystore
+        .add_key(&AuthSecretKey::RpoFalcon512(key_pair))
+        .unwrap();
+
+    let info = FaucetInfo::from_account(&faucet).unwrap();
+    println!("Faucet ID: {}", faucet.id().to_bech32(NetworkId::Testnet));
+    println!(
+        "{} with {} decimals, max supply {}",
+        info.symbol_name(),
+        info.decimals,
+        info.format_amount(info.max_supply)
+    );
+    if let Some(name) = name {
+        address_book
+            .insert(name, faucet.id(), Role::Faucet)
+            .unwrap();
+        address_book.save().unwrap();
+        println!("Added the faucet to the address book as {name}");
+    }
+    println!("The faucet is published on chain with its first mint");
+
+    Ok(())
+}
+
+async fn info(faucet_id: AccountId) -> Result<(), Box<ClientError>> {
+    let workspace = Workspace::current().unwrap();
+    let mut client = workspace
+        .instantiate_client(Endpoint::testnet(), None)
+        .await
+        .unwrap();
+    client.sync_state().await?;
+
+    let faucet = load_faucet(&mut client, faucet_id).await?;
+    let info = FaucetInfo::from_account(&faucet).unwrap();
+    println!("symbol:     {}", info.symbol_name());
+    println!("decimals:   {}", info.decimals);
+    println!("max supply: {}", info.format_amount(info.max_supply));
+    println!("issued:     {}", info.format_amount(info.issued));
+    println!("remaining:  {}", info.format_amount(info.remaining()));
+
+    Ok(())
+}
+
+async fn mint(
+    faucet_id: AccountId,
+    target_id: AccountId,
+    amount: &str,
+    note_policy: &NotePolicy,
+) -> Result<(), Box<ClientError>> {
+    let workspace = Workspace::current().unwrap();
+    let mut client = workspace
+        .instantiate_client(Endpoint::testnet(), None)
+        .await
+        .unwrap();
+    client.sync_state().await?;
+
+    // Amounts beyond the remaining supply are rejected before a transaction is built
+    let faucet = load_faucet(&mut client, faucet_id).await?;
+    let info = FaucetInfo::from_account(&faucet).unwrap();
+    let amount = info.parse_amount(amount).unwrap();
+    let asset = info.mint_asset(faucet_id, amount).unwrap();
+
+    let transaction_request = TransactionRequestBuilder::new()
+        .build_mint_fungible_asset(
+            asset,
+            target_id,
+            note_policy.note_type_for(target_id),
+            client.rng(),
+        )
+        .unwrap();
+    let tx_execution_result = client
+        .new_transaction(faucet_id, transaction_request)
+        .await?;
+    let minted_note = match tx_execution_result.created_notes().get_note(0) {
+        OutputNote::Full(note) => note.clone(),
+        _ => panic!("Expected Full note type"),
+    };
+    client.submit_transaction(tx_execution_result).await?;
+
+    // The target needs the details of a private note to consume it
+    let notes_dir = Path::new("./notes");
+    if let Some(note_id) = NoteDelivery::Export(notes_dir)
+        .deliver(&minted_note)
+        .await
+        .unwrap()
+    {
+        println!(
+            "Exported private note {} to {:?}",
+            note_id.to_hex(),
+            notes_dir
+        );
+    }
+
+    println!(
+        "Minted {} for {}, {} left to issue",
+        info.format_amount(amount),
+        target_id.to_bech32(NetworkId::Testnet),
+        info.format_amount(info.remaining() - amount)
+    );
+
+    Ok(())
+}
+
+async fn burn(
+    faucet_id: AccountId,
+    account_id: AccountId,
+    amount: &str,
+    note_policy: &NotePolicy,
+) -> Result<(), Box<ClientError>> {
+    let workspace = Workspace::current().unwrap();
+    let mut client = workspace
+        .instantiate_client(Endpoint::testnet(), None)
+        .await
+        .unwrap();
+    client.sync_state().await?;
+
+    let faucet = load_faucet(&mut client, faucet_id).await?;
+    let info = FaucetInfo::from_account(&faucet).unwrap();
+    let amount = info.parse_amount(amount).unwrap();
+    let asset = FungibleAsset::new(faucet_id, amount).unwrap();
+
+    // The account sends the tokens back to the faucet in a burn note
+    let burn_note = BurnNote::new(asset)
+        .build(
+            account_id,
+            note_policy.note_type_for(faucet_id),
+            client.rng().draw_word(),
+        )
+        .unwrap();
+    let transaction_request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(burn_note.clone())])
+        .build()
+        .unwrap();
+    let tx_execution_result = client
+        .new_transaction(account_id, transaction_request)
+        .await?;
+    client.submit_transaction(tx_execution_result).await?;
+
+    // The faucet consumes the note right away as an unauthenticated note, burning the tokens
+    let transaction_request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(burn_note, None)])
+        .build()
+        .unwrap();
+    let tx_execution_result = client
+        .new_transaction(faucet_id, transaction_request)
+        .await?;
+    client.submit_transaction(tx_execution_result).await?;
+
+    // The issued supply is read back from the faucet the burn was applied to
+    let faucet = load_faucet(&mut client, faucet_id).await?;
+    let issued = FaucetInfo::from_account(&faucet).unwrap().issued;
+    println!(
+        "Burned {} from {}, {} issued",
+        info.format_amount(amount),
+        account_id.to_bech32(NetworkId::Testnet),
+        info.format_amount(issued)
+    );
+
+    Ok(())
+}
+
+async fn reconcile(faucet_id: AccountId) -> Result<(), Box<ClientError>> {
+    let workspace = Workspace::current().unwrap();
+    let mut client = workspace
+        .instantiate_client(Endpoint::testnet(), None)
+        .await
+        .unwrap();
+    client.sync_state().await?;
+
+    let faucet = load_faucet(&mut client, faucet_id).await?;
+    let info = FaucetInfo::from_account(&faucet).unwrap();
+    let report = reconcile_supply(&client, faucet_id).await.unwrap();
+    for (account_id, balance) in &report.balances {
+        println!(
+            "{}: {}",
+            account_id.to_bech32(NetworkId::Testnet),
+            info.format_amount(*balance)
+        );
+    }
+    println!("in notes: {}", info.format_amount(report.in_notes));
+    println!("tracked:  {}", info.format_amount(report.tracked()));
+    println!("issued:   {}", info.format_amount(report.issued));
+    match report.discrepancy() {
+        None => println!("The issued supply matches the local store"),
+        Some(discrepancy) => println!("Discrepancy: {discrepancy}"),
+    }
+
+    Ok(())
+}
+
+async fn serve(faucet_id: AccountId, endpoint: Endpoint) -> Result<(), Box<ClientError>> {
+    let workspace = Workspace::current().unwrap();
+    let mut client = workspace.instantiate_client(endpoint, None).await.unwrap();
+    client.sync_state().await?;
+    load_faucet(&mut client, faucet_id).await?;
+
+    // Every request is logged, and the log restores the rate limits after a restart
+    let config = FaucetServiceConfig::new("./faucet_requests.jsonl");
+    let service = FaucetService::new(client, faucet_id, config).unwrap();
+    let listener = TcpListener::bind(DEFAULT_FAUCET_ADDR).await.unwrap();
+    println!(
+        "Faucet service listening on {}",
+        listener.local_addr().unwrap()
+    );
+    println!(
+        "curl -X POST http://{DEFAULT_FAUCET_ADDR}/mint -d '{{\"account_id\": \"<account_id>\", \"amount\": \"10\"}}'"
+    );
+    service.serve(listener).await.unwrap();
+
+    Ok(())
+}
+
+/// The latest state of the faucet, imported from the network if the client does not track it.
+async fn load_faucet(
+    client: &mut Client,
+    faucet_id: AccountId,
+) -> Result<Account, Box<ClientError>> {
+    if client.get_account(faucet_id).await?.is_none() {
+        client.import_account_by_id(faucet_id).await?;
+    }
+    let record = client.get_account(faucet_id).await?.unwrap();
+    Ok(record.account().clone())
+}
@@ -1,33 +1,31 @@
-use rand::{rngs::StdRng, RngCore};
-use std::{fs, path::Path, sync::Arc};
+use rand::RngCore;
 use tokio::time::{sleep, Duration};
 
 use miden_client::{
     account::{
-        component::{BasicFungibleFaucet, BasicWallet, RpoFalcon512},
+        component::{BasicWallet, RpoFalcon512},
         Account, AccountBuilder, AccountId, AccountStorageMode, AccountType,
     },
-    asset::{FungibleAsset, TokenSymbol},
+    asset::FungibleAsset,
     auth::AuthSecretKey,
-    builder::ClientBuilder,
     crypto::{FeltRng, SecretKey},
-    keystore::FilesystemKeyStore,
-    note::{
-        Note, NoteAssets, NoteExecutionHint, NoteExecutionMode, NoteInputs, NoteMetadata,
-        NoteRecipient, NoteRelevance, NoteScript, NoteTag, NoteType,
-    },
-    rpc::{Endpoint, TonicRpcClient},
+    note::{Note, NoteRelevance, NoteType},
+    rpc::Endpoint,
     store::InputNoteRecord,
-    transaction::{OutputNote, TransactionKernel, TransactionRequestBuilder},
-    Client, ClientError, Felt, Word,
+    transaction::{OutputNote, TransactionRequestBuilder},
+    Client, ClientError, Felt,
 };
 use miden_objects::account::NetworkId;
-use miden_objects::Hasher;
+use rust_client::{
+    faucet::FaucetSpec,
+    notes::PreimageNote,
+    workspace::{Workspace, WorkspaceKeyStore},
+};
 // Helper to create a basic account
 async fn create_basic_account(
     client: &mut Client,
-    keystore: FilesystemKeyStore<StdRng>,
-) -> Result<miden_client::account::Account, ClientError> {
+    keystore: WorkspaceKeyStore,
+) -> Result<miden_client::account::Account, Box<ClientError>> {
     let mut init_seed = [0_u8; 32];
     client.rng().fill_bytes(&mut init_seed);
 
@@ -48,20 +46,15 @@ async fn create_basic_account(
 
 async fn create_basic_faucet(
     client: &mut Client,
-    keystore: FilesystemKeyStore<StdRng>,
-) -> Result<miden_client::account::Account, ClientError> {
+    keystore: WorkspaceKeyStore,
+) -> Result<miden_client::account::Account, Box<ClientError>> {
     let mut init_seed = [0u8; 32];
     client.rng().fill_bytes(&mut init_seed);
     let key_pair = SecretKey::with_rng(client.rng());
-    let symbol = TokenSymbol::new("MID").unwrap();
-    let decimals = 8;
-    let max_supply = Felt::new(1_000_000);
-    let builder = AccountBuilder::new(init_seed)
-        .account_type(AccountType::FungibleFaucet)
-        .storage_mode(AccountStorageMode::Public)
-        .with_auth_component(RpoFalcon512::new(key_pair.public_key()))
-        .with_component(BasicFungibleFaucet::new(symbol, decimals, max_supply).unwrap());
-    let (account, seed) = builder.build().unwrap();
+    let (account, seed) = FaucetSpec::from_env()
+        .unwrap()
+        .build_account(init_seed, key_pair.public_key())
+        .unwrap();
     client.add_account(&account, Some(seed), false).await?;
     keystore
         .add_key(&AuthSecretKey::RpoFalcon512(key_pair))
@@ -74,7 +67,7 @@ pub async fn wait_for_note(
     client: &mut Client,
     account_id: &Account,
     expected: &Note,
-) -> Result<(), ClientError> {
+) -> Result<(), Box<ClientError>> {
     loop {
         client.sync_state().await?;
 
@@ -95,23 +88,16 @@ pub async fn wait_for_note(
 }
 
 #[tokio::main]
-async fn main() -> Result<(), ClientError> {
+async fn main() -> Result<(), Box<ClientError>> {
     // Initialize client & keystore
     let endpoint = Endpoint::testnet();
-    let timeout_ms = 10_000;
-    let rpc_api = Arc::new(TonicRpcClient::new(&endpoint, timeout_ms));
-
-    let mut client = ClientBuilder::new()
-        .rpc(rpc_api)
-        .filesystem_keystore("./keystore")
-        .in_debug_mode(true)
-        .build()
-        .await?;
+    let workspace = Workspace::current().unwrap();
+    let mut client = workspace.instantiate_client(endpoint, None).await.unwrap();
 
     let sync_summary = client.sync_state().await.unwrap();
     println!("Latest block: {}", sync_summary.block_num);
 
-    let keystore = FilesystemKeyStore::new("./keystore".into()).unwrap();
+    let keystore = workspace.open_keystore().unwrap();
 
     // -------------------------------------------------------------------------
     // STEP 1: Create accounts and deploy faucet
@@ -175,28 +161,18 @@ async fn main() -> Result<(), ClientError> {
     // -------------------------------------------------------------------------
     // STEP 3: Create custom note
     // -------------------------------------------------------------------------
-    println!("\n[STEP 3] Create custom note");
-    let mut secret_vals = vec![Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)];
-    secret_vals.splice(0..0, Word::default().iter().cloned());
-    let digest = Hasher::hash_elements(&secret_vals);
-    println!("digest: {:?}", digest);
-
-    let assembler = TransactionKernel::assembler().with_debug_mode(true);
-    let code = fs::read_to_string(Path::new("../masm/notes/hash_preimage_note.masm")).unwrap();
+    println!("\n[STEP 3] Create custom note bound to Bob");
+    let secret = [Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)];
+
+    // The builder hashes the secret like the note script does and stores Bob's account id in
+    // the note inputs, so only Bob can consume the note even once the secret is public
+    let preimage_note = PreimageNote::new(secret, bob_account.id(), vec![mint_amount.into()]);
+    println!("digest: {:?}", preimage_note.digest());
+
     let serial_num = client.rng().draw_word();
-    let note_script = NoteScript::compile(code, assembler).unwrap();
-    let note_inputs = NoteInputs::new(digest.to_vec()).unwrap();
-    let recipient = NoteRecipient::new(serial_num, note_script, note_inputs);
-    let tag = NoteTag::for_public_use_case(0, 0, NoteExecutionMode::Local).unwrap();
-    let metadata = NoteMetadata::new(
-        alice_account.id(),
-        NoteType::Public,
-        tag,
-        NoteExecutionHint::always(),
-        Felt::new(0),
-    )?;
-    let vault = NoteAssets::new(vec![mint_amount.into()])?;
-    let custom_note = Note::new(vault, metadata, recipient);
+    let custom_note = preimage_note
+        .build(alice_account.id(), NoteType::Public, serial_num)
+        .unwrap();
     println!("note hash: {:?}", custom_note.id().to_hex());
 
     let note_request = TransactionRequestBuilder::new()
@@ -219,9 +195,8 @@ async fn main() -> Result<(), ClientError> {
     // -------------------------------------------------------------------------
     println!("\n[STEP 4] Bob consumes the Custom Note with Correct Secret");
 
-    let secret = [Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)];
     let consume_custom_request = TransactionRequestBuilder::new()
-        .unauthenticated_input_notes([(custom_note, Some(secret))])
+        .unauthenticated_input_notes([(custom_note, Some(preimage_note.secret()))])
         .build()
         .unwrap();
     let tx_result = client
@@ -0,0 +1,49 @@
+use std::path::PathBuf;
+
+use miden_client::crypto::Digest;
+use rust_client::keystore::{migrate_keystore, read_password, EncryptedKeyStore};
+
+const USAGE: &str = "usage:
+  keystore init [dir]     create an empty encrypted keystore, ./keystore by default
+  keystore migrate [dir]  encrypt a plaintext keystore in place, ./keystore by default
+  keystore list [dir]     unlock an encrypted keystore and list its public keys
+
+The password is read from MIDEN_KEYSTORE_PASSWORD, or asked for on the terminal.";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let dir = PathBuf::from(args.get(1).map_or("./keystore", String::as_str));
+    match args.first().map(String::as_str) {
+        Some("init") if args.len() <= 2 => init(dir),
+        Some("migrate") if args.len() <= 2 => migrate(dir),
+        Some("list") if args.len() <= 2 => list(dir),
+        _ => {
+            eprintln!("{USAGE}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn init(dir: PathBuf) {
+    let password = read_password().unwrap();
+    EncryptedKeyStore::create(&dir, &password).unwrap();
+    println!("Created an encrypted keystore in {}", dir.display());
+}
+
+fn migrate(dir: PathBuf) {
+    let password = read_password().unwrap();
+    let migrated = migrate_keystore(&dir, &password).unwrap();
+    println!("Encrypted {migrated} key(s) in {}", dir.display());
+    println!("Tools using the plaintext FilesystemKeyStore can no longer read this directory.");
+    println!("Workspace clients unlock it with the password from MIDEN_KEYSTORE_PASSWORD.");
+}
+
+fn list(dir: PathBuf) {
+    let password = read_password().unwrap();
+    let keystore = EncryptedKeyStore::unlock(&dir, &password).unwrap();
+    let public_keys = keystore.public_keys().unwrap();
+    for public_key in &public_keys {
+        println!("{}", Digest::from(*public_key).to_hex());
+    }
+    println!("{} key(s) in {}", public_keys.len(), dir.display());
+}
@@ -0,0 +1,158 @@
+use miden_client::{
+    account::AccountStorageMode, auth::AuthSecretKey, rpc::Endpoint, Client, ClientError,
+};
+use miden_objects::account::NetworkId;
+use rust_client::{
+    faucet::FaucetSpec,
+    mnemonic::{
+        generate_mnemonic, recover_accounts, AccountDeriver, AccountTemplate, DerivedAccount,
+        DEFAULT_GAP_LIMIT,
+    },
+    workspace::{Workspace, WorkspaceKeyStore},
+};
+
+const USAGE: &str = "usage:
+  mnemonic new                        print a new 24-word mnemonic
+  mnemonic wallet <index> [--private] derive and add the wallet at an index
+  mnemonic faucet <index>             derive and add the faucet at an index, from MIDEN_FAUCET_SPEC
+  mnemonic recover [gap_limit]        re-derive wallets and faucets and import the public ones,
+                                      stopping after gap_limit (default 5) missing indices
+
+The mnemonic is read from MIDEN_MNEMONIC and an optional passphrase from
+MIDEN_MNEMONIC_PASSPHRASE.";
+
+#[tokio::main]
+async fn main() -> Result<(), Box<ClientError>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("new") if args.len() == 1 => {
+            println!("{}", generate_mnemonic());
+            println!("Write the words down, they are the only backup of the derived accounts");
+            Ok(())
+        }
+        Some("wallet") if args.len() >= 2 => {
+            let storage_mode = if args[2..].iter().any(|arg| arg == "--private") {
+                AccountStorageMode::Private
+            } else {
+                AccountStorageMode::Public
+            };
+            derive(
+                AccountTemplate::Wallet(storage_mode),
+                args[1].parse().unwrap(),
+            )
+            .await
+        }
+        Some("faucet") if args.len() == 2 => {
+            let faucet_spec = FaucetSpec::from_env().unwrap();
+            derive(
+                AccountTemplate::Faucet(faucet_spec),
+                args[1].parse().unwrap(),
+            )
+            .await
+        }
+        Some("recover") if args.len() <= 2 => {
+            let gap_limit = args
+                .get(1)
+                .map_or(DEFAULT_GAP_LIMIT, |arg| arg.parse().unwrap());
+            recover(gap_limit).await
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn derive(template: AccountTemplate, index: u32) -> Result<(), Box<ClientError>> {
+    let workspace = Workspace::current().unwrap();
+    let mut client = workspace
+        .instantiate_client(Endpoint::testnet(), None)
+        .await
+        .unwrap();
+    let keystore = workspace.open_keystore().unwrap();
+    client.sync_state().await?;
+
+    let deriver = AccountDeriver::from_env().unwrap();
+    let derived = deriver.account(&template, index).unwrap();
+    add_derived(&mut client, &keystore, &derived).await?;
+
+    println!(
+        "Account {index}: {}",
+        derived.account.id().to_bech32(NetworkId::Testnet)
+    );
+    println!("The same mnemonic and index always give this account");
+
+    Ok(())
+}
+
+async fn recover(gap_limit: u32) -> Result<(), Box<ClientError>> {
+    let workspace = Workspace::current().unwrap();
+    let mut client = workspace
+        .instantiate_client(Endpoint::testnet(), None)
+        .await
+        .unwrap();
+    let keystore = workspace.open_keystore().unwrap();
+    client.sync_state().await?;
+
+    let deriver = AccountDeriver::from_env().unwrap();
+    let templates = [
+        (
+            "wallet",
+            AccountTemplate::Wallet(AccountStorageMode::Public),
+        ),
+        (
+            "wallet",
+            AccountTemplate::Wallet(AccountStorageMode::Private),
+        ),
+        (
+            "faucet",
+            AccountTemplate::Faucet(FaucetSpec::from_env().unwrap()),
+        ),
+    ];
+
+    for (kind, template) in &templates {
+        let recovery = recover_accounts(&mut client, &deriver, template, gap_limit)
+            .await
+            .unwrap();
+
+        // Imported accounts are already in the store, only their keys are missing
+        for derived in &recovery.imported {
+            keystore
+                .add_key(&AuthSecretKey::RpoFalcon512(derived.secret_key.clone()))
+                .unwrap();
+            println!(
+                "Recovered {kind} {}: {}",
+                derived.index,
+                derived.account.id().to_bech32(NetworkId::Testnet)
+            );
+        }
+        for derived in &recovery.private {
+            keystore
+                .add_key(&AuthSecretKey::RpoFalcon512(derived.secret_key.clone()))
+                .unwrap();
+            println!(
+                "Found private {kind} {}: {}, restore its state from a store backup",
+                derived.index,
+                derived.account.id().to_bech32(NetworkId::Testnet)
+            );
+        }
+    }
+    client.sync_state().await?;
+
+    Ok(())
+}
+
+/// Adds a derived account to the client and its key to the keystore.
+async fn add_derived(
+    client: &mut Client,
+    keystore: &WorkspaceKeyStore,
+    derived: &DerivedAccount,
+) -> Result<(), Box<ClientError>> {
+    client
+        .add_account(&derived.account, Some(derived.seed), false)
+        .await?;
+    keystore
+        .add_key(&AuthSecretKey::RpoFalcon512(derived.secret_key.clone()))
+        .unwrap();
+    Ok(())
+}
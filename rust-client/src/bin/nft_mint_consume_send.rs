@@ -0,0 +1,167 @@
+use rand::RngCore;
+use tokio::time::Duration;
+
+use miden_client::{
+    account::AccountId,
+    auth::AuthSecretKey,
+    crypto::{FeltRng, SecretKey},
+    note::NoteType,
+    rpc::Endpoint,
+    transaction::{PaymentNoteDescription, TransactionRequestBuilder},
+    Client, ClientError,
+};
+use miden_objects::account::NetworkId;
+use rust_client::{
+    accounts::create_basic_account,
+    nft::{
+        build_nft_faucet, metadata_commitment, mint_request, nft_asset, owned_nfts, NftMetadata,
+        NftMint,
+    },
+    workspace::Workspace,
+};
+
+/// Number of NFTs minted for Alice.
+const NUM_NFTS: u64 = 3;
+
+// Helper to wait until an account has the expected number of consumable notes, and consume them
+async fn consume_notes(
+    client: &mut Client,
+    account_id: AccountId,
+    expected: usize,
+) -> Result<(), Box<ClientError>> {
+    loop {
+        client.sync_state().await?;
+        let notes = client.get_consumable_notes(Some(account_id)).await?;
+        if notes.len() >= expected {
+            let note_ids = notes.iter().map(|(note, _)| note.id()).collect();
+            let transaction_request = TransactionRequestBuilder::new()
+                .build_consume_notes(note_ids)
+                .unwrap();
+            let tx_execution_result = client
+                .new_transaction(account_id, transaction_request)
+                .await?;
+            client.submit_transaction(tx_execution_result).await?;
+            return Ok(());
+        }
+        println!(
+            "{} consumable notes found for account {}. Waiting...",
+            notes.len(),
+            account_id.to_bech32(NetworkId::Testnet)
+        );
+        tokio::time::sleep(Duration::from_secs(3)).await;
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<ClientError>> {
+    // Initialize client & keystore
+    let endpoint = Endpoint::testnet();
+    let workspace = Workspace::current().unwrap();
+    let mut client = workspace.instantiate_client(endpoint, None).await.unwrap();
+
+    let keystore = workspace.open_keystore().unwrap();
+
+    let sync_summary = client.sync_state().await.unwrap();
+    println!("Latest block: {}", sync_summary.block_num);
+
+    //------------------------------------------------------------
+    // STEP 1: Create Alice and Bob
+    //------------------------------------------------------------
+    println!("\n[STEP 1] Creating wallets for Alice and Bob");
+
+    let (alice, _) = create_basic_account(&mut client, keystore.clone()).await?;
+    let (bob, _) = create_basic_account(&mut client, keystore.clone()).await?;
+    println!("Alice: {}", alice.id().to_bech32(NetworkId::Testnet));
+    println!("Bob: {}", bob.id().to_bech32(NetworkId::Testnet));
+
+    //------------------------------------------------------------
+    // STEP 2: Deploy the NFT faucet
+    //------------------------------------------------------------
+    println!("\n[STEP 2] Deploying the NFT faucet");
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let key_pair = SecretKey::with_rng(client.rng());
+    let (faucet, faucet_seed) = build_nft_faucet(init_seed, key_pair.public_key()).unwrap();
+    client
+        .add_account(&faucet, Some(faucet_seed), false)
+        .await?;
+    keystore
+        .add_key(&AuthSecretKey::RpoFalcon512(key_pair))
+        .unwrap();
+    println!("NFT faucet: {}", faucet.id().to_bech32(NetworkId::Testnet));
+
+    client.sync_state().await?;
+
+    //------------------------------------------------------------
+    // STEP 3: Mint NFTs for Alice
+    //------------------------------------------------------------
+    println!("\n[STEP 3] Minting {NUM_NFTS} NFTs for Alice");
+
+    // The metadata stays off chain, the faucet only stores its commitment
+    let mut mints = vec![];
+    for token_id in 1..=NUM_NFTS {
+        let metadata = NftMetadata::new(
+            format!("Miden Tutorial #{token_id}"),
+            "An NFT minted in the NFT tutorial",
+            format!("ipfs://tutorial/{token_id}.json"),
+        );
+        mints.push(NftMint::new(
+            nft_asset(faucet.id(), token_id).unwrap(),
+            metadata,
+            alice.id(),
+            client.rng().draw_word(),
+        ));
+    }
+
+    // All NFTs are minted in a single transaction, one note each
+    let transaction_request = mint_request(&mints).unwrap();
+    let tx_execution_result = client
+        .new_transaction(faucet.id(), transaction_request)
+        .await?;
+    client.submit_transaction(tx_execution_result).await?;
+
+    //------------------------------------------------------------
+    // STEP 4: Alice consumes her NFTs
+    //------------------------------------------------------------
+    println!("\n[STEP 4] Alice consumes the minted NFTs");
+
+    consume_notes(&mut client, alice.id(), mints.len()).await?;
+    client.sync_state().await?;
+
+    let faucet = client.get_account(faucet.id()).await?.unwrap();
+    let alice_record = client.get_account(alice.id()).await?.unwrap();
+    for nft in owned_nfts(alice_record.account()) {
+        let commitment = metadata_commitment(faucet.account(), &nft).unwrap();
+        println!("Alice owns {nft:?} with metadata commitment {commitment:?}");
+    }
+
+    //------------------------------------------------------------
+    // STEP 5: Alice sends an NFT to Bob
+    //------------------------------------------------------------
+    println!("\n[STEP 5] Alice sends her first NFT to Bob");
+
+    // An NFT is sent in a P2ID note like any other asset
+    let nft = mints[0].asset;
+    let transaction_request = TransactionRequestBuilder::new()
+        .build_pay_to_id(
+            PaymentNoteDescription::new(vec![nft.into()], alice.id(), bob.id()),
+            NoteType::Public,
+            client.rng(),
+        )
+        .unwrap();
+    let tx_execution_result = client
+        .new_transaction(alice.id(), transaction_request)
+        .await?;
+    client.submit_transaction(tx_execution_result).await?;
+
+    consume_notes(&mut client, bob.id(), 1).await?;
+    client.sync_state().await?;
+
+    for (name, account_id) in [("Alice", alice.id()), ("Bob", bob.id())] {
+        let record = client.get_account(account_id).await?.unwrap();
+        println!("{name} owns {} NFT(s)", owned_nfts(record.account()).len());
+    }
+
+    Ok(())
+}
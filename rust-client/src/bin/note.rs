@@ -0,0 +1,140 @@
+use miden_client::{
+    account::AccountId, note::NoteId, rpc::Endpoint, transaction::TransactionRequestBuilder,
+    ClientError, Felt, Word,
+};
+use miden_objects::{account::NetworkId, block::BlockNumber};
+use rust_client::{
+    address_book::AddressBook,
+    note_file::{find_note, NoteExport, NoteFileFormat},
+    workspace::Workspace,
+};
+
+const USAGE: &str = "usage:
+  note export <note_id> <file> [--text] [--note-args <a,b,c,d>]
+      write a note this client created or received to a note file
+  note import <file> [--after-block <n>] [--consume <account>]
+      add the note in a note file to this client, or consume it right away
+
+--note-args attaches the argument the receiver needs, e.g. the secret of a hash-preimage note.
+--after-block is the block the sender created the note after, the client looks for the note on
+chain from there (default 0).";
+
+/// Parses four comma-separated field elements.
+fn parse_word(value: &str) -> Word {
+    let felts: Vec<Felt> = value
+        .split(',')
+        .map(|felt| Felt::new(felt.trim().parse().expect("note args are integers")))
+        .collect();
+    felts.try_into().expect("note args are four elements")
+}
+
+/// The value following `flag`, if present.
+fn flag<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .map(|index| args.get(index + 1).expect("flag has a value").as_str())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<ClientError>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("export") if args.len() >= 3 => {
+            let note_id = NoteId::try_from_hex(&args[1]).unwrap();
+            let format = if args[3..].iter().any(|arg| arg == "--text") {
+                NoteFileFormat::Text
+            } else {
+                NoteFileFormat::Binary
+            };
+            let note_args = flag(&args[3..], "--note-args").map(parse_word);
+            export(note_id, &args[2], format, note_args).await
+        }
+        Some("import") if args.len() >= 2 => {
+            let after_block = flag(&args[2..], "--after-block")
+                .map(|block| block.parse::<u32>().unwrap())
+                .unwrap_or(0);
+            let consumer = flag(&args[2..], "--consume").map(|account| {
+                AddressBook::from_env(NetworkId::Testnet)
+                    .unwrap()
+                    .resolve(account)
+                    .unwrap()
+            });
+            import(&args[1], after_block.into(), consumer).await
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn export(
+    note_id: NoteId,
+    path: &str,
+    format: NoteFileFormat,
+    note_args: Option<Word>,
+) -> Result<(), Box<ClientError>> {
+    let workspace = Workspace::current().unwrap();
+    let mut client = workspace
+        .instantiate_client(Endpoint::testnet(), None)
+        .await
+        .unwrap();
+    client.sync_state().await?;
+
+    let Some(note) = find_note(&client, note_id).await? else {
+        eprintln!(
+            "This client does not have the details of note {}",
+            note_id.to_hex()
+        );
+        std::process::exit(1);
+    };
+    let mut note_file = NoteExport::new(note);
+    if let Some(note_args) = note_args {
+        note_file = note_file.with_note_args(note_args);
+    }
+    note_file.write(path, format).unwrap();
+    println!("Exported note {} to {path}", note_id.to_hex());
+
+    Ok(())
+}
+
+async fn import(
+    path: &str,
+    after_block: BlockNumber,
+    consumer: Option<AccountId>,
+) -> Result<(), Box<ClientError>> {
+    let workspace = Workspace::current().unwrap();
+    let mut client = workspace
+        .instantiate_client(Endpoint::testnet(), None)
+        .await
+        .unwrap();
+    client.sync_state().await?;
+
+    let note_file = NoteExport::read(path).unwrap();
+    let note_id = note_file.note.id();
+    println!("Read note {} from {path}", note_id.to_hex());
+    if let Some(note_args) = note_file.note_args {
+        println!("Note args: {note_args:?}");
+    }
+
+    let Some(consumer) = consumer else {
+        note_file.import(&mut client, after_block).await?;
+        client.sync_state().await?;
+        println!("Imported note {}", note_id.to_hex());
+        return Ok(());
+    };
+
+    // Consuming the note as an unauthenticated note works before it is committed
+    let consume_request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(note_file.note, note_file.note_args)])
+        .build()
+        .unwrap();
+    let tx_result = client.new_transaction(consumer, consume_request).await?;
+    println!(
+        "Consumed Note Tx on MidenScan: https://testnet.midenscan.com/tx/{:?}",
+        tx_result.executed_transaction().id()
+    );
+    client.submit_transaction(tx_result).await?;
+
+    Ok(())
+}
@@ -1,34 +1,32 @@
-use rand::{prelude::StdRng, RngCore};
-use std::{fs, path::Path, sync::Arc};
+use rand::RngCore;
 use tokio::time::{sleep, Duration};
 
 use miden_client::{
     account::{
-        component::{BasicFungibleFaucet, BasicWallet, RpoFalcon512},
+        component::{BasicWallet, RpoFalcon512},
         AccountBuilder, AccountStorageMode, AccountType,
     },
-    asset::{FungibleAsset, TokenSymbol},
+    asset::FungibleAsset,
     auth::AuthSecretKey,
-    builder::ClientBuilder,
     crypto::{FeltRng, SecretKey},
-    keystore::FilesystemKeyStore,
-    note::{
-        Note, NoteAssets, NoteExecutionHint, NoteExecutionMode, NoteInputs, NoteMetadata,
-        NoteRecipient, NoteScript, NoteTag, NoteType,
-    },
-    rpc::{Endpoint, TonicRpcClient},
-    transaction::{OutputNote, TransactionKernel, TransactionRequestBuilder},
-    Client, ClientError, Felt,
+    note::{NoteExecutionMode, NoteTag, NoteType},
+    rpc::Endpoint,
+    transaction::{OutputNote, TransactionRequestBuilder},
+    Client, ClientError,
 };
 
 use miden_objects::account::NetworkId;
-use miden_objects::note::NoteDetails;
+use rust_client::{
+    faucet::FaucetSpec,
+    notes::IterativeOutputNote,
+    workspace::{Workspace, WorkspaceKeyStore},
+};
 
 // Helper to create a basic account
 async fn create_basic_account(
     client: &mut Client,
-    keystore: FilesystemKeyStore<StdRng>,
-) -> Result<miden_client::account::Account, ClientError> {
+    keystore: WorkspaceKeyStore,
+) -> Result<miden_client::account::Account, Box<ClientError>> {
     let mut init_seed = [0u8; 32];
     client.rng().fill_bytes(&mut init_seed);
     let key_pair = SecretKey::with_rng(client.rng());
@@ -47,20 +45,15 @@ async fn create_basic_account(
 
 async fn create_basic_faucet(
     client: &mut Client,
-    keystore: FilesystemKeyStore<StdRng>,
-) -> Result<miden_client::account::Account, ClientError> {
+    keystore: WorkspaceKeyStore,
+) -> Result<miden_client::account::Account, Box<ClientError>> {
     let mut init_seed = [0u8; 32];
     client.rng().fill_bytes(&mut init_seed);
     let key_pair = SecretKey::with_rng(client.rng());
-    let symbol = TokenSymbol::new("MID").unwrap();
-    let decimals = 8;
-    let max_supply = Felt::new(1_000_000);
-    let builder = AccountBuilder::new(init_seed)
-        .account_type(AccountType::FungibleFaucet)
-        .storage_mode(AccountStorageMode::Public)
-        .with_auth_component(RpoFalcon512::new(key_pair.public_key()))
-        .with_component(BasicFungibleFaucet::new(symbol, decimals, max_supply).unwrap());
-    let (account, seed) = builder.build().unwrap();
+    let (account, seed) = FaucetSpec::from_env()
+        .unwrap()
+        .build_account(init_seed, key_pair.public_key())
+        .unwrap();
     client.add_account(&account, Some(seed), false).await?;
     keystore
         .add_key(&AuthSecretKey::RpoFalcon512(key_pair))
@@ -73,7 +66,7 @@ async fn wait_for_notes(
     client: &mut Client,
     account_id: &miden_client::account::Account,
     expected: usize,
-) -> Result<(), ClientError> {
+) -> Result<(), Box<ClientError>> {
     loop {
         client.sync_state().await?;
         let notes = client.get_consumable_notes(Some(account_id.id())).await?;
@@ -90,24 +83,16 @@ async fn wait_for_notes(
     Ok(())
 }
 #[tokio::main]
-async fn main() -> Result<(), ClientError> {
+async fn main() -> Result<(), Box<ClientError>> {
     // Initialize client & keystore
     let endpoint = Endpoint::testnet();
-    let timeout_ms = 10_000;
-    let rpc_api = Arc::new(TonicRpcClient::new(&endpoint, timeout_ms));
-
-    let mut client = ClientBuilder::new()
-        .rpc(rpc_api)
-        .filesystem_keystore("./keystore")
-        .in_debug_mode(true)
-        .build()
-        .await?;
+    let workspace = Workspace::current().unwrap();
+    let mut client = workspace.instantiate_client(endpoint, None).await.unwrap();
 
     let sync_summary = client.sync_state().await.unwrap();
     println!("Latest block: {}", sync_summary.block_num);
 
-    let keystore: FilesystemKeyStore<rand::prelude::StdRng> =
-        FilesystemKeyStore::new("./keystore".into()).unwrap();
+    let keystore = workspace.open_keystore().unwrap();
 
     // -------------------------------------------------------------------------
     // STEP 1: Create accounts and deploy faucet
@@ -175,32 +160,30 @@ async fn main() -> Result<(), ClientError> {
     // -------------------------------------------------------------------------
     println!("\n[STEP 3] Create iterative output note");
 
-    let assembler = TransactionKernel::assembler().with_debug_mode(true);
-    let code = fs::read_to_string(Path::new("../masm/notes/iterative_output_note.masm")).unwrap();
-    let rng = client.rng();
-    let serial_num = rng.draw_word();
-
-    // Create note metadata and tag
+    let serial_num = client.rng().draw_word();
     let tag = NoteTag::for_public_use_case(0, 0, NoteExecutionMode::Local).unwrap();
-    let metadata = NoteMetadata::new(
-        alice_account.id(),
-        NoteType::Public,
-        tag,
-        NoteExecutionHint::always(),
-        Felt::new(0),
-    )?;
-    let note_script = NoteScript::compile(code, assembler.clone()).unwrap();
-    let note_inputs = NoteInputs::new(vec![
-        alice_account.id().prefix().as_felt(),
-        alice_account.id().suffix(),
-        tag.into(),
-        Felt::new(0),
-    ])
-    .unwrap();
 
-    let recipient = NoteRecipient::new(serial_num, note_script.clone(), note_inputs.clone());
-    let vault = NoteAssets::new(vec![mint_amount.into()])?;
-    let custom_note = Note::new(vault, metadata, recipient);
+    // The note re-creates itself at most 3 times and never moves less than 10 tokens
+    let iterative_note =
+        IterativeOutputNote::new(alice_account.id(), tag, vec![mint_amount.into()])
+            .with_depth(3)
+            .with_min_amount(10);
+    let custom_note = iterative_note
+        .build(alice_account.id(), serial_num)
+        .map_err(ClientError::from)?;
+
+    // Predict every note of the chain before anything is submitted
+    let future_notes = iterative_note
+        .simulate(serial_num)
+        .map_err(ClientError::from)?;
+    for (i, future_note) in future_notes.iter().enumerate() {
+        println!(
+            "note {}: recipient {:?}, assets {:?}",
+            i + 1,
+            future_note.details.recipient().digest(),
+            future_note.note.assets()
+        );
+    }
 
     let note_req = TransactionRequestBuilder::new()
         .own_output_notes(vec![OutputNote::Full(custom_note.clone())])
@@ -222,38 +205,13 @@ async fn main() -> Result<(), ClientError> {
     // -------------------------------------------------------------------------
     println!("\n[STEP 4] Bob consumes the note and creates a copy");
 
-    // Increment the serial number for the new note
-    let serial_num_1 = [
-        serial_num[0],
-        serial_num[1],
-        serial_num[2],
-        Felt::new(serial_num[3].as_int() + 1),
-    ];
-
-    // Reuse the note_script and note_inputs
-    let recipient = NoteRecipient::new(serial_num_1, note_script, note_inputs);
-
-    // Note: Change metadata to include Bob's account as the creator
-    let metadata = NoteMetadata::new(
-        bob_account.id(),
-        NoteType::Public,
-        tag,
-        NoteExecutionHint::always(),
-        Felt::new(0),
-    )?;
-
-    let asset_amount_1 = FungibleAsset::new(faucet_id, 50).unwrap();
-    let vault = NoteAssets::new(vec![asset_amount_1.into()])?;
-    let output_note = Note::new(vault, metadata, recipient);
+    // The first predicted note is the copy Bob creates with half of the tokens
+    let output_note = &future_notes[0];
 
     let consume_custom_req = TransactionRequestBuilder::new()
         .unauthenticated_input_notes([(custom_note, None)])
-        .expected_future_notes(vec![(
-            NoteDetails::from(output_note.clone()),
-            output_note.metadata().tag(),
-        )
-            .clone()])
-        .expected_output_recipients(vec![output_note.recipient().clone()])
+        .expected_future_notes(vec![(output_note.details.clone(), tag)])
+        .expected_output_recipients(vec![output_note.details.recipient().clone()])
         .build()
         .unwrap();
     let tx_result = client
@@ -0,0 +1,181 @@
+use std::time::Duration;
+
+use miden_client::{
+    asset::FungibleAsset,
+    crypto::FeltRng,
+    note::{create_p2id_note, NoteType},
+    rpc::Endpoint,
+    transaction::{OutputNote, TransactionRequestBuilder},
+    ClientError, Felt,
+};
+use miden_client_tools::mint_from_faucet_for_account;
+use miden_objects::account::NetworkId;
+use rust_client::{
+    accounts::{create_basic_account, create_basic_faucet},
+    note_file::{NoteExport, NoteFileFormat},
+    note_policy::NoteDelivery,
+    notes::HashPreimageNote,
+    workspace::Workspace,
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<ClientError>> {
+    // Alice and Bob each run their own client with a separate store
+    let workspace = Workspace::current().unwrap();
+    let mut alice_client = workspace
+        .instantiate_client(Endpoint::testnet(), Some("alice_store.sqlite3"))
+        .await
+        .unwrap();
+    let mut bob_client = workspace
+        .instantiate_client(Endpoint::testnet(), Some("bob_store.sqlite3"))
+        .await
+        .unwrap();
+
+    let keystore = workspace.open_keystore().unwrap();
+
+    let sync_summary = alice_client.sync_state().await.unwrap();
+    println!("Latest block: {}", sync_summary.block_num);
+    bob_client.sync_state().await.unwrap();
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create accounts, deploy faucet and fund Alice
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating new accounts");
+    let (alice_account, _) = create_basic_account(&mut alice_client, keystore.clone()).await?;
+    println!(
+        "Alice's account ID: {:?}",
+        alice_account.id().to_bech32(NetworkId::Testnet)
+    );
+    let (bob_account, _) = create_basic_account(&mut bob_client, keystore.clone()).await?;
+    println!(
+        "Bob's account ID: {:?}",
+        bob_account.id().to_bech32(NetworkId::Testnet)
+    );
+
+    let faucet = create_basic_faucet(&mut alice_client, keystore).await?;
+    println!(
+        "Faucet account ID: {:?}",
+        faucet.id().to_bech32(NetworkId::Testnet)
+    );
+    alice_client.sync_state().await?;
+
+    mint_from_faucet_for_account(&mut alice_client, &alice_account, &faucet, 150, None).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Alice creates a private note and exports it to a file
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Alice creates a private note and exports it");
+    let secret = [Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)];
+    let asset = FungibleAsset::new(faucet.id(), 100).unwrap();
+
+    let serial_num = alice_client.rng().draw_word();
+    let private_note = HashPreimageNote::new(secret, vec![asset.into()])
+        .build(alice_account.id(), NoteType::Private, serial_num)
+        .unwrap();
+    println!("note hash: {:?}", private_note.id().to_hex());
+
+    let note_request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(private_note.clone())])
+        .build()
+        .unwrap();
+    let tx_result = alice_client
+        .new_transaction(alice_account.id(), note_request)
+        .await?;
+    println!(
+        "View transaction on MidenScan: https://testnet.midenscan.com/tx/{:?}",
+        tx_result.executed_transaction().id()
+    );
+    alice_client.submit_transaction(tx_result).await?;
+
+    // Only the note commitment is on chain, so Bob needs the note and the secret from Alice
+    let note_file = NoteExport::new(private_note).with_note_args(secret);
+    note_file
+        .write("./private_note.mnote", NoteFileFormat::Binary)
+        .unwrap();
+    println!("Wrote ./private_note.mnote");
+    println!("Text encoding for chat or email:\n{}", note_file.to_text());
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Bob imports the note file in his client and consumes the note
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Bob imports the note file and consumes the note");
+    let imported = NoteExport::read("./private_note.mnote").unwrap();
+    println!("imported note hash: {:?}", imported.note.id().to_hex());
+
+    bob_client.sync_state().await?;
+    let consume_request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(imported.note, imported.note_args)])
+        .build()
+        .unwrap();
+    let tx_result = bob_client
+        .new_transaction(bob_account.id(), consume_request)
+        .await?;
+    println!(
+        "Consumed Note Tx on MidenScan: https://testnet.midenscan.com/tx/{:?}",
+        tx_result.executed_transaction().id()
+    );
+    println!("account delta: {:?}", tx_result.account_delta().vault());
+    bob_client.submit_transaction(tx_result).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Alice sends a private P2ID note and hands it to Bob's client directly
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Alice sends a private P2ID note to Bob's client");
+    let after_block_num = alice_client.sync_state().await?.block_num;
+    let asset = FungibleAsset::new(faucet.id(), 50).unwrap();
+    let p2id_note = create_p2id_note(
+        alice_account.id(),
+        bob_account.id(),
+        vec![asset.into()],
+        NoteType::Private,
+        Felt::new(0),
+        alice_client.rng(),
+    )
+    .unwrap();
+    let note_request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(p2id_note.clone())])
+        .build()
+        .unwrap();
+    let tx_result = alice_client
+        .new_transaction(alice_account.id(), note_request)
+        .await?;
+    alice_client.submit_transaction(tx_result).await?;
+
+    // Both clients run in this process, so no note file is needed
+    NoteDelivery::Import {
+        receiver: &mut bob_client,
+        after_block_num,
+    }
+    .deliver(&p2id_note)
+    .await
+    .unwrap();
+
+    // Bob's client finds the note commitment on chain and consumes the note once it is committed
+    loop {
+        bob_client.sync_state().await?;
+        let consumable = bob_client
+            .get_consumable_notes(Some(bob_account.id()))
+            .await?;
+        if consumable
+            .iter()
+            .any(|(record, _)| record.id() == p2id_note.id())
+        {
+            break;
+        }
+        println!("Note not committed yet. Waiting...");
+        tokio::time::sleep(Duration::from_secs(3)).await;
+    }
+    let consume_request = TransactionRequestBuilder::new()
+        .build_consume_notes(vec![p2id_note.id()])
+        .unwrap();
+    let tx_result = bob_client
+        .new_transaction(bob_account.id(), consume_request)
+        .await?;
+    println!(
+        "Consumed Note Tx on MidenScan: https://testnet.midenscan.com/tx/{:?}",
+        tx_result.executed_transaction().id()
+    );
+    bob_client.submit_transaction(tx_result).await?;
+
+    Ok(())
+}
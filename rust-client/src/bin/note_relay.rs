@@ -0,0 +1,179 @@
+use std::collections::BTreeSet;
+
+use miden_client::{
+    account::AccountId,
+    asset::FungibleAsset,
+    note::{create_p2id_note, Note, NoteTag, NoteType},
+    rpc::Endpoint,
+    transaction::{OutputNote, TransactionRequestBuilder},
+    Client, ClientError, Felt, Word,
+};
+use miden_client_tools::mint_from_faucet_for_account;
+use miden_objects::account::NetworkId;
+use rust_client::{
+    accounts::{create_basic_account, create_basic_faucet},
+    address_book::AddressBook,
+    note_file::NoteExport,
+    relay::{post_note, NoteRelay, RelaySubscription, DEFAULT_RELAY_ADDR},
+    workspace::Workspace,
+};
+
+const USAGE: &str = "usage:
+  note_relay serve                        run the relay on 127.0.0.1:7878
+  note_relay receive                      create an account and consume every note sent to it
+  note_relay send <receiver_id> [count]   send `count` P2ID notes to the receiver, given as a bech32
+                                          id or a name from the address book";
+
+#[tokio::main]
+async fn main() -> Result<(), Box<ClientError>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("serve") => serve().await,
+        Some("receive") => receive().await,
+        Some("send") if args.len() >= 2 => {
+            let receiver_id = AddressBook::from_env(NetworkId::Testnet)
+                .unwrap()
+                .resolve(&args[1])
+                .unwrap();
+            let count = args.get(2).map_or(3, |count| count.parse().unwrap());
+            send(receiver_id, count).await
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            std::process::exit(1);
+        }
+    }
+}
+
+// -------------------------------------------------------------------------
+// Relay process
+// -------------------------------------------------------------------------
+async fn serve() -> Result<(), Box<ClientError>> {
+    let relay = NoteRelay::bind(DEFAULT_RELAY_ADDR).await.unwrap();
+    println!("Note relay listening on {}", relay.local_addr().unwrap());
+    relay.run().await.unwrap();
+    Ok(())
+}
+
+// -------------------------------------------------------------------------
+// Receiver process
+// -------------------------------------------------------------------------
+async fn receive() -> Result<(), Box<ClientError>> {
+    let workspace = Workspace::current().unwrap();
+    let mut client = workspace
+        .instantiate_client(Endpoint::testnet(), Some("receiver_store.sqlite3"))
+        .await
+        .unwrap();
+    let keystore = workspace.open_keystore().unwrap();
+    client.sync_state().await?;
+
+    println!("\n[STEP 1] Creating the receiver account");
+    let (receiver, _) = create_basic_account(&mut client, keystore).await?;
+    println!(
+        "Receiver account ID: {}",
+        receiver.id().to_bech32(NetworkId::Testnet)
+    );
+    println!(
+        "Run `cargo run --release --bin note_relay send {}` in another terminal",
+        receiver.id().to_bech32(NetworkId::Testnet)
+    );
+
+    // P2ID notes are tagged with the target account id
+    println!("\n[STEP 2] Waiting for notes from the relay");
+    let tag = NoteTag::from_account_id(receiver.id());
+    let mut subscription = RelaySubscription::connect(DEFAULT_RELAY_ADDR, tag)
+        .await
+        .unwrap();
+
+    let mut consumed = BTreeSet::new();
+    while let Some(NoteExport { note, note_args }) = subscription.next_note().await.unwrap() {
+        if !consumed.insert(note.id()) {
+            continue;
+        }
+        println!("\nreceived note {}", note.id().to_hex());
+
+        // A note that fails to consume is reported and may be retried if the relay sends it again
+        let note_id = note.id();
+        if let Err(err) = consume(&mut client, receiver.id(), note, note_args).await {
+            eprintln!("failed to consume note {}: {err}", note_id.to_hex());
+            consumed.remove(&note_id);
+        }
+    }
+
+    println!("Relay closed the connection");
+    Ok(())
+}
+
+async fn consume(
+    client: &mut Client,
+    receiver_id: AccountId,
+    note: Note,
+    note_args: Option<Word>,
+) -> Result<(), ClientError> {
+    let consume_request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(note, note_args)])
+        .build()?;
+    let tx_result = client.new_transaction(receiver_id, consume_request).await?;
+    println!(
+        "Consumed Note Tx on MidenScan: https://testnet.midenscan.com/tx/{:?}",
+        tx_result.executed_transaction().id()
+    );
+    println!("account delta: {:?}", tx_result.account_delta().vault());
+    client.submit_transaction(tx_result).await
+}
+
+// -------------------------------------------------------------------------
+// Sender process
+// -------------------------------------------------------------------------
+async fn send(receiver_id: AccountId, count: u64) -> Result<(), Box<ClientError>> {
+    let workspace = Workspace::current().unwrap();
+    let mut client = workspace
+        .instantiate_client(Endpoint::testnet(), Some("sender_store.sqlite3"))
+        .await
+        .unwrap();
+    let keystore = workspace.open_keystore().unwrap();
+    client.sync_state().await?;
+
+    println!("\n[STEP 1] Creating the sender account and funding it");
+    let (sender, _) = create_basic_account(&mut client, keystore.clone()).await?;
+    println!(
+        "Sender account ID: {}",
+        sender.id().to_bech32(NetworkId::Testnet)
+    );
+    let faucet = create_basic_faucet(&mut client, keystore).await?;
+    client.sync_state().await?;
+    mint_from_faucet_for_account(&mut client, &sender, &faucet, 100, None).await?;
+
+    println!("\n[STEP 2] Sending notes through the relay");
+    for i in 0..count {
+        let asset = FungibleAsset::new(faucet.id(), 10).unwrap();
+
+        // The note never has to be public, the relay delivers the details
+        let p2id_note = create_p2id_note(
+            sender.id(),
+            receiver_id,
+            vec![asset.into()],
+            NoteType::Private,
+            Felt::new(0),
+            client.rng(),
+        )
+        .unwrap();
+
+        let transaction_request = TransactionRequestBuilder::new()
+            .own_output_notes(vec![OutputNote::Full(p2id_note.clone())])
+            .build()
+            .unwrap();
+        let tx_result = client
+            .new_transaction(sender.id(), transaction_request)
+            .await?;
+        client.submit_transaction(tx_result).await?;
+
+        let tag = p2id_note.metadata().tag();
+        post_note(DEFAULT_RELAY_ADDR, tag, &NoteExport::new(p2id_note))
+            .await
+            .unwrap();
+        println!("posted note {} of {count}", i + 1);
+    }
+
+    Ok(())
+}
@@ -16,7 +16,12 @@ use miden_client::{
     Client, ClientError, Felt, Word, ZERO,
 };
 
-use miden_client_tools::{create_library, instantiate_client};
+use miden_client_tools::create_library;
+use miden_objects::account::NetworkId;
+use rust_client::{address_book::AddressBook, workspace::Workspace};
+
+/// The Pragma oracle on testnet, unless an oracle is passed as the first argument.
+const DEFAULT_ORACLE: &str = "mtst1qq0zffxzdykm7qqqqdt24cc2du5ghx99";
 
 /// Import the oracle + its publishers and return the ForeignAccount list
 /// Due to Pragma's decentralized oracle architecture, we need to get the
@@ -73,20 +78,24 @@ pub async fn get_oracle_foreign_accounts(
 }
 
 #[tokio::main]
-async fn main() -> Result<(), ClientError> {
+async fn main() -> Result<(), Box<ClientError>> {
     // -------------------------------------------------------------------------
     // Initialize Client
     // -------------------------------------------------------------------------
     let endpoint = Endpoint::testnet();
-    let mut client = instantiate_client(endpoint, None).await.unwrap();
+    let workspace = Workspace::current().unwrap();
+    let mut client = workspace.instantiate_client(endpoint, None).await.unwrap();
 
     println!("Latest block: {}", client.sync_state().await?.block_num);
 
     // -------------------------------------------------------------------------
     // Get all foreign accounts for oracle data
     // -------------------------------------------------------------------------
-    let (_, oracle_account_id) =
-        AccountId::from_bech32("mtst1qq0zffxzdykm7qqqqdt24cc2du5ghx99").unwrap();
+    let oracle_arg = std::env::args().nth(1);
+    let oracle_account_id = AddressBook::from_env(NetworkId::Testnet)
+        .unwrap()
+        .resolve(oracle_arg.as_deref().unwrap_or(DEFAULT_ORACLE))
+        .unwrap();
     let btc_usd_pair_id = 120195681;
     let foreign_accounts: Vec<ForeignAccount> =
         get_oracle_foreign_accounts(&mut client, oracle_account_id, btc_usd_pair_id).await?;
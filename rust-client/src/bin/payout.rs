@@ -0,0 +1,156 @@
+use std::{path::Path, sync::Arc};
+
+use miden_client::{rpc::Endpoint, transaction::TransactionProver, ClientError};
+use miden_objects::{account::NetworkId, MAX_OUTPUT_NOTES_PER_TX};
+use rust_client::{
+    address_book::AddressBook,
+    note_policy::{NoteDelivery, NotePolicy},
+    payout::{read_payouts, send_payouts, totals, PayoutError},
+    prover::ProverSelector,
+    workspace::Workspace,
+};
+
+const USAGE: &str = "usage:
+  payout <sender_id> <payouts.csv> [options]
+
+Accounts are given as bech32 ids or as names from the address book.
+
+options:
+  --max-notes <n>   output notes per transaction, at most 1024 (default 1024)
+  --delegated       prove transactions with the provers in MIDEN_PROVERS, or the testnet
+                    delegated prover, falling back to local proving
+  --report <path>   where to write the JSON report (default ./payout_report.json)";
+
+#[tokio::main]
+async fn main() -> Result<(), Box<ClientError>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.len() < 2 {
+        eprintln!("{USAGE}");
+        std::process::exit(1);
+    }
+    let address_book = AddressBook::from_env(NetworkId::Testnet).unwrap();
+    let sender_id = address_book.resolve(&args[0]).unwrap();
+    let csv_path = &args[1];
+
+    let mut max_notes = MAX_OUTPUT_NOTES_PER_TX;
+    let mut delegated = false;
+    let mut report_path = "./payout_report.json".to_string();
+    let mut options = args[2..].iter();
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--max-notes" => max_notes = options.next().unwrap().parse().unwrap(),
+            "--delegated" => delegated = true,
+            "--report" => report_path = options.next().unwrap().clone(),
+            _ => {
+                eprintln!("{USAGE}");
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // Sender account must already be tracked by the client, e.g. Alice from
+    // `create_mint_consume_send`
+    let workspace = Workspace::current().unwrap();
+    let mut client = workspace
+        .instantiate_client(Endpoint::testnet(), None)
+        .await
+        .unwrap();
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    //------------------------------------------------------------
+    // STEP 1: Read the payouts
+    //------------------------------------------------------------
+    println!("\n[STEP 1] Reading payouts from {csv_path}");
+    let payouts = read_payouts(csv_path, &address_book).unwrap();
+    println!("{} payouts", payouts.len());
+    for (faucet_id, total) in totals(&payouts).unwrap() {
+        println!(
+            "  {} tokens of faucet {}",
+            total,
+            address_book.display(faucet_id)
+        );
+    }
+
+    //------------------------------------------------------------
+    // STEP 2: Send the payouts in batches
+    //------------------------------------------------------------
+    let transactions = payouts
+        .len()
+        .div_ceil(max_notes.clamp(1, MAX_OUTPUT_NOTES_PER_TX));
+    println!("\n[STEP 2] Sending the payouts in {transactions} transaction(s)");
+
+    let selector = delegated.then(|| Arc::new(ProverSelector::from_env()));
+    if let Some(selector) = &selector {
+        let endpoints: Vec<&str> = selector.endpoints().collect();
+        println!(
+            "Proving with {}, falling back to local proving",
+            endpoints.join(", ")
+        );
+    }
+    let tx_prover = selector
+        .clone()
+        .map(|selector| selector as Arc<dyn TransactionProver>);
+
+    // Public or private notes, set with MIDEN_NOTE_TYPE or a MIDEN_NOTE_POLICY file
+    let note_policy = NotePolicy::from_env(&address_book).unwrap();
+    // If a batch fails, the report of the batches sent before it is still written below, so the
+    // payouts that went out are not sent again on a rerun
+    let (report, failure) = match send_payouts(
+        &mut client,
+        sender_id,
+        &payouts,
+        &note_policy,
+        max_notes,
+        tx_prover,
+    )
+    .await
+    {
+        Ok(report) => (report, None),
+        Err(PayoutError::Interrupted { report, cause }) => (*report, Some(cause)),
+        Err(err) => panic!("{err}"),
+    };
+
+    for tx_id in &report.transactions {
+        println!("View transaction on MidenScan: https://testnet.midenscan.com/tx/{tx_id}");
+    }
+    for record in selector.iter().flat_map(|selector| selector.records()) {
+        println!(
+            "Transaction {} proven by the {}",
+            record.transaction_id, record.source
+        );
+    }
+
+    // Private notes only put their commitment on chain, their details are exported here
+    let notes_dir = Path::new("./notes");
+    for note in &report.notes {
+        if let Some(note_id) = NoteDelivery::Export(notes_dir).deliver(note).await.unwrap() {
+            println!(
+                "Exported private note {} to {:?}",
+                note_id.to_hex(),
+                notes_dir
+            );
+        }
+    }
+
+    //------------------------------------------------------------
+    // STEP 3: Write the report
+    //------------------------------------------------------------
+    println!("\n[STEP 3] Writing the report to {report_path}");
+    std::fs::write(&report_path, report.to_json()).unwrap();
+    for (recipient, note_ids) in &report.recipients {
+        println!("{recipient}: {}", note_ids.join(", "));
+    }
+
+    if let Some(cause) = failure {
+        eprintln!(
+            "\nPayouts stopped: {cause}\nThe first {} of {} payouts were sent, rerun with the \
+             remaining rows only.",
+            report.notes.len(),
+            payouts.len()
+        );
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
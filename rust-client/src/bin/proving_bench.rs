@@ -0,0 +1,390 @@
+use rand::RngCore;
+use std::{fs, path::Path, sync::Arc};
+use tokio::time::{sleep, Duration};
+
+use miden_assembly::{
+    ast::{Module, ModuleKind},
+    LibraryPath,
+};
+use miden_client::{
+    account::{Account, AccountBuilder, AccountStorageMode, AccountType, StorageSlot},
+    asset::FungibleAsset,
+    crypto::FeltRng,
+    note::NoteType,
+    rpc::{domain::account::AccountStorageRequirements, Endpoint},
+    store::TransactionFilter,
+    transaction::{
+        ForeignAccount, LocalTransactionProver, OutputNote, TransactionId, TransactionKernel,
+        TransactionProver, TransactionRequestBuilder, TransactionScript, TransactionStatus,
+    },
+    Client, ClientError, Felt, RemoteTransactionProver, Word,
+};
+use miden_client_tools::{create_exact_p2id_note, mint_from_faucet_for_account};
+use miden_objects::{
+    account::{AccountComponent, NetworkId},
+    assembly::{Assembler, DefaultSourceManager},
+};
+use rust_client::{
+    accounts::{create_basic_account, create_basic_faucet},
+    notes::HashPreimageNote,
+    prover::{endpoints_from_env, ProofSource, DEFAULT_PROVER_TIMEOUT},
+    proving_bench::{bench_transaction, ProvingBenchReport, ProvingSample, Workload},
+    workspace::Workspace,
+};
+
+const USAGE: &str = "usage: proving_bench [iterations] [output_dir] [label]";
+
+fn create_library(
+    assembler: Assembler,
+    library_path: &str,
+    source_code: &str,
+) -> Result<miden_assembly::Library, Box<dyn std::error::Error>> {
+    let source_manager = Arc::new(DefaultSourceManager::default());
+    let module = Module::parser(ModuleKind::Library).parse_str(
+        LibraryPath::new(library_path)?,
+        source_code,
+        &source_manager,
+    )?;
+    let library = assembler.clone().assemble_library([module])?;
+    Ok(library)
+}
+
+// Builds a public contract with the no-auth component and one empty value slot
+fn build_contract(client: &mut Client, code: &str, assembler: &Assembler) -> (Account, Word) {
+    let no_auth_code = fs::read_to_string(Path::new("../masm/accounts/auth/no_auth.masm")).unwrap();
+    let no_auth_component = AccountComponent::compile(
+        no_auth_code,
+        assembler.clone(),
+        vec![StorageSlot::empty_value()],
+    )
+    .unwrap()
+    .with_supports_all_types();
+    let component = AccountComponent::compile(
+        code.to_string(),
+        assembler.clone(),
+        vec![StorageSlot::empty_value()],
+    )
+    .unwrap()
+    .with_supports_all_types();
+
+    let mut seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut seed);
+    let (account, account_seed) = AccountBuilder::new(seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_component(component)
+        .with_auth_component(no_auth_component)
+        .build()
+        .unwrap();
+    (account, account_seed)
+}
+
+// Waits until a transaction is committed, so its account state can be read by other accounts
+async fn wait_for_commit(client: &mut Client, id: TransactionId) -> Result<(), Box<ClientError>> {
+    loop {
+        client.sync_state().await?;
+
+        let transactions = client
+            .get_transactions(TransactionFilter::Ids(vec![id]))
+            .await?;
+        match transactions.first().map(|record| &record.status) {
+            Some(TransactionStatus::Committed(block_num)) => {
+                println!(
+                    "✅ transaction {} committed in block {block_num}",
+                    id.to_hex()
+                );
+                return Ok(());
+            }
+            Some(TransactionStatus::Discarded(cause)) => {
+                panic!("transaction {} was discarded: {cause}", id.to_hex())
+            }
+            _ => {}
+        }
+
+        println!("Transaction {} not committed. Waiting...", id.to_hex());
+        sleep(Duration::from_secs(3)).await;
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<ClientError>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "--help") {
+        println!("{USAGE}");
+        return Ok(());
+    }
+    let arg = |index: usize, default: &str| args.get(index).cloned().unwrap_or(default.into());
+    let iterations: usize = arg(0, "3").parse().unwrap();
+    let output_dir = arg(1, "./proving_bench");
+    let label = arg(2, "miden-client 0.10");
+
+    let workspace = Workspace::current().unwrap();
+    let mut client = workspace
+        .instantiate_client(Endpoint::testnet(), None)
+        .await
+        .unwrap();
+    let keystore = workspace.open_keystore().unwrap();
+
+    let sync_summary = client.sync_state().await.unwrap();
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // The local prover goes first, so the proof that gets submitted does not depend on the
+    // remote provers being up. Remote provers get the selector's timeout for every proof
+    let local = LocalTransactionProver::default();
+    let remotes: Vec<(String, RemoteTransactionProver)> = endpoints_from_env()
+        .into_iter()
+        .map(|endpoint| (endpoint.clone(), RemoteTransactionProver::new(endpoint)))
+        .collect();
+    let mut provers: Vec<(ProofSource, &dyn TransactionProver)> =
+        vec![(ProofSource::Local, &local)];
+    for (endpoint, remote) in &remotes {
+        provers.push((ProofSource::Remote(endpoint.clone()), remote));
+    }
+    for (source, _) in &provers {
+        println!("Benchmarking the {source}");
+    }
+    let mut samples: Vec<ProvingSample> = vec![];
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create and fund the accounts
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating accounts");
+    let (alice_account, _) = create_basic_account(&mut client, keystore.clone()).await?;
+    let (bob_account, _) = create_basic_account(&mut client, keystore.clone()).await?;
+    let faucet = create_basic_faucet(&mut client, keystore).await?;
+    client.sync_state().await?;
+    mint_from_faucet_for_account(&mut client, &alice_account, &faucet, 1000, None).await?;
+    println!(
+        "Alice's account ID: {:?}",
+        alice_account.id().to_bech32(NetworkId::Testnet)
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Ten P2ID notes from Alice to Bob
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Proving {}", Workload::P2idBatch);
+    let fungible_asset = FungibleAsset::new(faucet.id(), 10).unwrap();
+    let mut output_notes = vec![];
+    for _ in 0..10 {
+        let p2id_note = create_exact_p2id_note(
+            alice_account.id(),
+            bob_account.id(),
+            vec![fungible_asset.into()],
+            NoteType::Public,
+            Felt::new(0),
+            client.rng().draw_word(),
+        )
+        .map_err(ClientError::from)?;
+        output_notes.push(OutputNote::Full(p2id_note));
+    }
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(output_notes)
+        .build()
+        .unwrap();
+    let (workload_samples, _) = bench_transaction(
+        &mut client,
+        Workload::P2idBatch,
+        alice_account.id(),
+        request,
+        &provers,
+        iterations,
+        DEFAULT_PROVER_TIMEOUT,
+    )
+    .await?;
+    samples.extend(workload_samples);
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Deploy and increment a counter contract
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Proving {}", Workload::CounterIncrement);
+    let assembler: Assembler = TransactionKernel::assembler().with_debug_mode(true);
+    let counter_code = fs::read_to_string(Path::new("../masm/accounts/counter.masm")).unwrap();
+    let (counter_contract, counter_seed) = build_contract(&mut client, &counter_code, &assembler);
+    client
+        .add_account(&counter_contract, Some(counter_seed), false)
+        .await
+        .unwrap();
+
+    let counter_lib = create_library(
+        assembler.clone(),
+        "external_contract::counter_contract",
+        &counter_code,
+    )
+    .unwrap();
+    let script_code = fs::read_to_string(Path::new("../masm/scripts/counter_script.masm")).unwrap();
+    let tx_script = TransactionScript::compile(
+        script_code,
+        assembler.clone().with_library(&counter_lib).unwrap(),
+    )
+    .unwrap();
+    let request = TransactionRequestBuilder::new()
+        .custom_script(tx_script)
+        .build()
+        .unwrap();
+    // The first increment also deploys the contract
+    let (workload_samples, increment_id) = bench_transaction(
+        &mut client,
+        Workload::CounterIncrement,
+        counter_contract.id(),
+        request,
+        &provers,
+        iterations,
+        DEFAULT_PROVER_TIMEOUT,
+    )
+    .await?;
+    samples.extend(workload_samples);
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Read the counter with a foreign procedure invocation
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Proving {}", Workload::ForeignProcedureCall);
+    // The reader fetches the counter's public state from the node
+    wait_for_commit(&mut client, increment_id).await?;
+
+    let reader_code = fs::read_to_string(Path::new("../masm/accounts/count_reader.masm")).unwrap();
+    let (reader_contract, reader_seed) = build_contract(&mut client, &reader_code, &assembler);
+    client
+        .add_account(&reader_contract, Some(reader_seed), false)
+        .await
+        .unwrap();
+
+    let counter_component = AccountComponent::compile(counter_code, assembler.clone(), vec![])
+        .unwrap()
+        .with_supports_all_types();
+    let get_count_export = counter_component
+        .library()
+        .exports()
+        .find(|export| export.name.as_str() == "get_count")
+        .unwrap();
+    let get_count_hash = counter_component
+        .library()
+        .mast_forest()
+        .get_node_by_id(
+            counter_component
+                .library()
+                .get_export_node_id(get_count_export),
+        )
+        .unwrap()
+        .digest()
+        .to_hex();
+
+    let script_code = fs::read_to_string(Path::new("../masm/scripts/reader_script.masm"))
+        .unwrap()
+        .replace("{get_count_proc_hash}", &get_count_hash)
+        .replace(
+            "{account_id_suffix}",
+            &counter_contract.id().suffix().to_string(),
+        )
+        .replace(
+            "{account_id_prefix}",
+            &counter_contract.id().prefix().to_string(),
+        );
+    let reader_lib = create_library(
+        assembler.clone(),
+        "external_contract::count_reader_contract",
+        &reader_code,
+    )
+    .unwrap();
+    let tx_script = TransactionScript::compile(
+        script_code,
+        assembler.clone().with_library(&reader_lib).unwrap(),
+    )
+    .unwrap();
+    let foreign_account =
+        ForeignAccount::public(counter_contract.id(), AccountStorageRequirements::default())
+            .unwrap();
+    let request = TransactionRequestBuilder::new()
+        .foreign_accounts([foreign_account])
+        .custom_script(tx_script)
+        .build()
+        .unwrap();
+    let (workload_samples, _) = bench_transaction(
+        &mut client,
+        Workload::ForeignProcedureCall,
+        reader_contract.id(),
+        request,
+        &provers,
+        iterations,
+        DEFAULT_PROVER_TIMEOUT,
+    )
+    .await?;
+    samples.extend(workload_samples);
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Bob consumes a hash-preimage note from Alice
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 5] Proving {}", Workload::CustomNoteConsume);
+    let secret = [Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)];
+    let custom_note = HashPreimageNote::new(secret, vec![fungible_asset.into()])
+        .build(
+            alice_account.id(),
+            NoteType::Public,
+            client.rng().draw_word(),
+        )
+        .unwrap();
+    let note_request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(custom_note.clone())])
+        .build()
+        .unwrap();
+    // Creating the note is not part of the workload
+    let tx_result = client
+        .new_transaction(alice_account.id(), note_request)
+        .await?;
+    client.submit_transaction(tx_result).await?;
+
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(custom_note, Some(secret))])
+        .build()
+        .unwrap();
+    let (workload_samples, _) = bench_transaction(
+        &mut client,
+        Workload::CustomNoteConsume,
+        bob_account.id(),
+        request,
+        &provers,
+        iterations,
+        DEFAULT_PROVER_TIMEOUT,
+    )
+    .await?;
+    samples.extend(workload_samples);
+
+    // -------------------------------------------------------------------------
+    // STEP 6: Write the reports
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 6] Writing reports to {output_dir}");
+    let report = ProvingBenchReport::new(label, iterations, samples);
+    fs::create_dir_all(&output_dir).unwrap();
+    fs::write(format!("{output_dir}/report.json"), report.to_json()).unwrap();
+    fs::write(format!("{output_dir}/samples.csv"), report.to_csv()).unwrap();
+
+    println!(
+        "{:<24} {:>8} {:>8} {:>12} {:>12} {:>8} {:>8}",
+        "workload", "cycles", "exec ms", "prove p50 ms", "proof bytes", "failed", "speedup"
+    );
+    for comparison in &report.workloads {
+        println!(
+            "{:<24} {:>8} {:>8.1}",
+            comparison.workload, comparison.cycles.total, comparison.execute_ms
+        );
+        for summary in &comparison.provers {
+            let proof_bytes = summary
+                .proof_bytes
+                .map(|bytes| bytes.to_string())
+                .unwrap_or("-".into());
+            let speedup = summary
+                .speedup
+                .map(|speedup| format!("{speedup:.2}x"))
+                .unwrap_or("-".into());
+            println!(
+                "  {:<40} {:>12.1} {:>12} {:>8} {:>8}",
+                summary.prover.to_string(),
+                summary.prove_ms.p50,
+                proof_bytes,
+                summary.failures,
+                speedup
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use miden_client::{
+    asset::FungibleAsset, note::NoteType, rpc::Endpoint, transaction::TransactionRequestBuilder,
+    ClientError,
+};
+use miden_client_tools::mint_from_faucet_for_account;
+use miden_objects::account::NetworkId;
+use rust_client::{
+    accounts::{create_basic_account, create_basic_faucet},
+    swap::SwapOffer,
+    workspace::Workspace,
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<ClientError>> {
+    // Initialize client & keystore
+    let endpoint = Endpoint::testnet();
+    let workspace = Workspace::current().unwrap();
+    let mut client = workspace.instantiate_client(endpoint, None).await.unwrap();
+
+    let keystore = workspace.open_keystore().unwrap();
+
+    let sync_summary = client.sync_state().await.unwrap();
+    println!("Latest block: {}", sync_summary.block_num);
+
+    //------------------------------------------------------------
+    // STEP 1: Deploy two faucets and fund Alice and Bob
+    //------------------------------------------------------------
+    println!("\n[STEP 1] Deploying faucets A and B, funding Alice with A and Bob with B");
+
+    let (alice, _) = create_basic_account(&mut client, keystore.clone()).await?;
+    let (bob, _) = create_basic_account(&mut client, keystore.clone()).await?;
+    let faucet_a = create_basic_faucet(&mut client, keystore.clone()).await?;
+    let faucet_b = create_basic_faucet(&mut client, keystore).await?;
+    println!("Alice: {}", alice.id().to_bech32(NetworkId::Testnet));
+    println!("Bob: {}", bob.id().to_bech32(NetworkId::Testnet));
+    println!("Faucet A: {}", faucet_a.id().to_bech32(NetworkId::Testnet));
+    println!("Faucet B: {}", faucet_b.id().to_bech32(NetworkId::Testnet));
+
+    client.sync_state().await?;
+    mint_from_faucet_for_account(&mut client, &alice, &faucet_a, 100, None).await?;
+    mint_from_faucet_for_account(&mut client, &bob, &faucet_b, 100, None).await?;
+
+    //------------------------------------------------------------
+    // STEP 2: Alice offers 50 A for 30 B
+    //------------------------------------------------------------
+    println!("\n[STEP 2] Alice creates a SWAP note offering 50 A for 30 B");
+
+    let offer = SwapOffer::new(
+        alice.id(),
+        FungibleAsset::new(faucet_a.id(), 50).unwrap(),
+        FungibleAsset::new(faucet_b.id(), 30).unwrap(),
+    );
+    let swap = offer.build(NoteType::Public, client.rng()).unwrap();
+
+    // The request also registers the payback note, so the client recognizes it once it lands
+    let tx_execution_result = client
+        .new_transaction(alice.id(), swap.request().unwrap())
+        .await?;
+    client.submit_transaction(tx_execution_result).await?;
+    println!("SWAP note: {}", swap.note.id().to_hex());
+
+    //------------------------------------------------------------
+    // STEP 3: Bob takes the offer
+    //------------------------------------------------------------
+    println!("\n[STEP 3] Bob consumes the SWAP note and pays 30 B back to Alice");
+
+    let payback_note = swap.payback_note(bob.id()).unwrap();
+    let transaction_request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(swap.note.clone(), None)])
+        .build()
+        .unwrap();
+    let tx_execution_result = client
+        .new_transaction(bob.id(), transaction_request)
+        .await?;
+
+    // The note script created the payback note inside Bob's transaction
+    let created = tx_execution_result.created_notes().get_note(0);
+    assert_eq!(created.id(), payback_note.id());
+    client.submit_transaction(tx_execution_result).await?;
+    println!("Payback note: {}", payback_note.id().to_hex());
+
+    //------------------------------------------------------------
+    // STEP 4: Alice claims the payback note
+    //------------------------------------------------------------
+    println!("\n[STEP 4] Alice consumes the payback note");
+
+    let transaction_request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(payback_note, None)])
+        .build()
+        .unwrap();
+    let tx_execution_result = client
+        .new_transaction(alice.id(), transaction_request)
+        .await?;
+    client.submit_transaction(tx_execution_result).await?;
+
+    client.sync_state().await?;
+    for (name, account_id) in [("Alice", alice.id()), ("Bob", bob.id())] {
+        let record = client.get_account(account_id).await?.unwrap();
+        let vault = record.account().vault();
+        println!(
+            "{name}: {} A, {} B",
+            vault.get_balance(faucet_a.id()).unwrap(),
+            vault.get_balance(faucet_b.id()).unwrap()
+        );
+    }
+
+    Ok(())
+}
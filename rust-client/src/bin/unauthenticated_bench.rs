@@ -0,0 +1,177 @@
+use std::{
+    fs,
+    sync::{Arc, Barrier},
+    thread,
+    time::{Duration, Instant},
+};
+
+use miden_client::{account::Account, rpc::Endpoint, Client, ClientError};
+use miden_client_tools::mint_from_faucet_for_account;
+use rust_client::{
+    accounts::{create_basic_account, create_basic_faucet},
+    bench::{record_inclusion, run_chain, BenchConfig, BenchReport, NoteMix, StepRecord},
+    workspace::Workspace,
+};
+
+const USAGE: &str =
+    "usage: unauthenticated_bench [chains] [chain_length] [private_ratio] [output_dir] [label]";
+
+struct Chain {
+    client: Client,
+    accounts: Vec<Account>,
+    faucet: Account,
+}
+
+// Every chain gets its own client and store, so chains never wait on each other's store
+async fn setup_chain(
+    index: usize,
+    config: &BenchConfig,
+    workspace: &Workspace,
+) -> Result<Chain, ClientError> {
+    let store_path = format!("bench_store_{index}.sqlite3");
+    // Errors are returned rather than unwrapped, a panic here would never release the start line
+    let mut client = workspace
+        .instantiate_client(Endpoint::testnet(), Some(&store_path))
+        .await?;
+    let keystore = workspace
+        .open_keystore()
+        .map_err(|err| ClientError::ClientInitializationError(err.to_string()))?;
+    client.sync_state().await?;
+
+    let mut accounts = Vec::with_capacity(config.chain_length + 1);
+    for _ in 0..=config.chain_length {
+        let (account, _) = create_basic_account(&mut client, keystore.clone()).await?;
+        accounts.push(account);
+    }
+    let faucet = create_basic_faucet(&mut client, keystore).await?;
+    client.sync_state().await?;
+
+    let funds = config.amount * config.chain_length as u64;
+    mint_from_faucet_for_account(&mut client, &accounts[0], &faucet, funds, None).await?;
+    println!("chain {index}: funded {} accounts", accounts.len());
+
+    Ok(Chain {
+        client,
+        accounts,
+        faucet,
+    })
+}
+
+/// Sets up a chain, runs it once every chain is set up, and waits for its transactions to land.
+///
+/// Returns the steps and when the chain finished, not counting the wait for inclusion.
+async fn bench_chain(
+    index: usize,
+    config: &BenchConfig,
+    workspace: &Workspace,
+    start_line: &Barrier,
+) -> Result<(Vec<StepRecord>, Instant), Box<ClientError>> {
+    let setup = setup_chain(index, config, workspace).await;
+    // A chain that failed to set up still has to release the others
+    start_line.wait();
+    let mut chain = setup?;
+
+    let faucet_id = chain.faucet.id();
+    let mut steps = run_chain(&mut chain.client, index, &chain.accounts, faucet_id, config).await?;
+    let finished = Instant::now();
+
+    record_inclusion(&mut chain.client, &mut steps, Duration::from_secs(3)).await?;
+    println!("chain {index}: all transactions committed");
+    Ok((steps, finished))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<ClientError>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "--help") {
+        println!("{USAGE}");
+        return Ok(());
+    }
+    let arg = |index: usize, default: &str| args.get(index).cloned().unwrap_or(default.into());
+
+    let config = BenchConfig {
+        chains: arg(0, "4").parse().unwrap(),
+        chain_length: arg(1, "5").parse().unwrap(),
+        note_mix: NoteMix::new(arg(2, "0.5").parse().unwrap()),
+        amount: 10,
+        label: arg(4, "miden-client 0.10"),
+    };
+    let output_dir = arg(3, "./bench");
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create and fund the accounts of every chain
+    // -------------------------------------------------------------------------
+    println!(
+        "\n[STEP 1] Setting up {} chains of length {}",
+        config.chains, config.chain_length
+    );
+    // The keystore is unlocked once, every chain's client shares the session
+    let workspace = Workspace::current().unwrap();
+    workspace.open_keystore().unwrap();
+
+    // Executing and proving is CPU-bound, so every chain runs on its own thread. The chains start
+    // together once all of them are set up.
+    let start_line = Arc::new(Barrier::new(config.chains + 1));
+    let handles: Vec<_> = (0..config.chains)
+        .map(|index| {
+            let config = config.clone();
+            let workspace = workspace.clone();
+            let start_line = Arc::clone(&start_line);
+            thread::spawn(move || {
+                tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .unwrap()
+                    .block_on(bench_chain(index, &config, &workspace, &start_line))
+            })
+        })
+        .collect();
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Run all chains concurrently
+    // -------------------------------------------------------------------------
+    start_line.wait();
+    println!("\n[STEP 2] Running unauthenticated note chains");
+    let start = Instant::now();
+    let mut steps: Vec<StepRecord> = Vec::new();
+    let mut finished = start;
+    for handle in handles {
+        let (chain_steps, chain_finished) = handle.join().unwrap()?;
+        steps.extend(chain_steps);
+        finished = finished.max(chain_finished);
+    }
+    let elapsed = finished - start;
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Write the reports
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Writing reports to {output_dir}");
+    let report = BenchReport::new(config, elapsed, steps);
+    fs::create_dir_all(&output_dir).unwrap();
+    fs::write(format!("{output_dir}/report.json"), report.to_json()).unwrap();
+    fs::write(format!("{output_dir}/steps.csv"), report.to_csv()).unwrap();
+
+    let summary = &report.summary;
+    println!("phase      p50 ms     p90 ms     p99 ms");
+    for (phase, percentiles) in [
+        ("build", &summary.build),
+        ("execute", &summary.execute),
+        ("prove", &summary.prove),
+        ("submit", &summary.submit),
+        ("total", &summary.total),
+    ] {
+        println!(
+            "{phase:<8} {:>8.1} {:>10.1} {:>10.1}",
+            percentiles.p50, percentiles.p90, percentiles.p99
+        );
+    }
+    println!(
+        "\n{} transfers in {:.1?} ({:.2} transfers/s)",
+        report.steps.len(),
+        elapsed,
+        report.transfers_per_second
+    );
+    println!("blocks: {:?}", report.landed_blocks);
+
+    Ok(())
+}
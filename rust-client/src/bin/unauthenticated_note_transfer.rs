@@ -1,42 +1,40 @@
 use rand::RngCore;
-use std::sync::Arc;
 use tokio::time::{Duration, Instant};
 
 use miden_client::{
     account::{
-        component::{BasicFungibleFaucet, BasicWallet, RpoFalcon512},
+        component::{BasicWallet, RpoFalcon512},
         AccountBuilder, AccountStorageMode, AccountType,
     },
-    asset::{FungibleAsset, TokenSymbol},
+    asset::FungibleAsset,
     auth::AuthSecretKey,
-    builder::ClientBuilder,
     crypto::SecretKey,
-    keystore::FilesystemKeyStore,
-    note::{create_p2id_note, Note, NoteType},
-    rpc::{Endpoint, TonicRpcClient},
+    note::{create_p2id_note, Note},
+    rpc::Endpoint,
     transaction::{OutputNote, TransactionRequestBuilder},
     utils::{Deserializable, Serializable},
     ClientError, Felt,
 };
 use miden_objects::account::NetworkId;
+use rust_client::{
+    address_book::AddressBook, faucet::FaucetSpec, note_policy::NotePolicy, workspace::Workspace,
+};
+
 #[tokio::main]
-async fn main() -> Result<(), ClientError> {
+async fn main() -> Result<(), Box<ClientError>> {
     // Initialize client & keystore
     let endpoint = Endpoint::testnet();
-    let timeout_ms = 10_000;
-    let rpc_api = Arc::new(TonicRpcClient::new(&endpoint, timeout_ms));
-
-    let mut client = ClientBuilder::new()
-        .rpc(rpc_api)
-        .filesystem_keystore("./keystore")
-        .in_debug_mode(true)
-        .build()
-        .await?;
+    let workspace = Workspace::current().unwrap();
+    let mut client = workspace.instantiate_client(endpoint, None).await.unwrap();
 
     let sync_summary = client.sync_state().await.unwrap();
     println!("Latest block: {}", sync_summary.block_num);
 
-    let keystore = FilesystemKeyStore::new("./keystore".into()).unwrap();
+    let keystore = workspace.open_keystore().unwrap();
+
+    // Public or private notes, set with MIDEN_NOTE_TYPE or a MIDEN_NOTE_POLICY file
+    let address_book = AddressBook::from_env(NetworkId::Testnet).unwrap();
+    let note_policy = NotePolicy::from_env(&address_book).unwrap();
 
     //------------------------------------------------------------
     // STEP 1: Deploy a fungible faucet
@@ -50,17 +48,13 @@ async fn main() -> Result<(), ClientError> {
     // Generate key pair
     let key_pair = SecretKey::with_rng(client.rng());
 
-    // Faucet parameters
-    let symbol = TokenSymbol::new("MID").unwrap();
-    let decimals = 8;
-    let max_supply = Felt::new(1_000_000);
+    // Faucet parameters, read from the file named by MIDEN_FAUCET_SPEC if set
+    let faucet_spec = FaucetSpec::from_env().unwrap();
 
     // Build the account
-    let builder = AccountBuilder::new(init_seed)
-        .account_type(AccountType::FungibleFaucet)
-        .storage_mode(AccountStorageMode::Public)
-        .with_auth_component(RpoFalcon512::new(key_pair.public_key()))
-        .with_component(BasicFungibleFaucet::new(symbol, decimals, max_supply).unwrap());
+    let builder = faucet_spec
+        .account_builder(init_seed, key_pair.public_key())
+        .unwrap();
 
     let (faucet_account, seed) = builder.build().unwrap();
 
@@ -129,7 +123,7 @@ async fn main() -> Result<(), ClientError> {
         .build_mint_fungible_asset(
             fungible_asset_mint_amount,
             alice.id(),
-            NoteType::Public,
+            note_policy.note_type_for(alice.id()),
             client.rng(),
         )
         .unwrap();
@@ -141,7 +135,7 @@ async fn main() -> Result<(), ClientError> {
         .submit_transaction(tx_execution_result.clone())
         .await?;
 
-    // The minted fungible asset is public so output is a `Full` note type
+    // The note was created by this client, so its details are known whether it is public or private
     let p2id_note: Note =
         if let OutputNote::Full(note) = tx_execution_result.created_notes().get_note(0) {
             note.clone()
@@ -180,12 +174,9 @@ async fn main() -> Result<(), ClientError> {
         let fungible_asset_send_amount =
             FungibleAsset::new(faucet_account.id(), send_amount).unwrap();
 
-        // for demo purposes, unauthenticated notes can be public or private
-        let note_type = if i % 2 == 0 {
-            NoteType::Private
-        } else {
-            NoteType::Public
-        };
+        // unauthenticated notes can be public or private, the receiver gets the details from the
+        // sender either way
+        let note_type = note_policy.note_type_for(accounts[i + 1].id());
 
         let p2id_note = create_p2id_note(
             accounts[i].id(),
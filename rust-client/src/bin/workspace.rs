@@ -0,0 +1,64 @@
+use rust_client::workspace::{Workspace, WORKSPACE_ENV};
+
+const USAGE: &str = "usage:
+  workspace create <name>             create an empty workspace in ./workspaces/<name>
+  workspace list                      list the workspaces and their snapshots
+  workspace snapshot <name> <label>   copy a workspace's stores, keystore and address book
+  workspace restore <name> <label>    replace a workspace's state with a snapshot
+  workspace reset <name>              delete a workspace's stores, keys and address book
+
+The workspace in the current directory is called default. Run a flow in a workspace with
+MIDEN_WORKSPACE=<name>.";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("create") if args.len() == 2 => {
+            let workspace = Workspace::create_in(".", &args[1]).unwrap();
+            println!(
+                "Created {} in {}",
+                workspace.name(),
+                workspace.root().display()
+            );
+            println!("Run flows in it with {WORKSPACE_ENV}={}", workspace.name());
+        }
+        Some("list") if args.len() == 1 => {
+            for name in Workspace::list_in(".").unwrap() {
+                let workspace = Workspace::open_in(".", &name).unwrap();
+                let snapshots = workspace.snapshots().unwrap();
+                let store = if workspace.store_path().exists() {
+                    "store"
+                } else {
+                    "empty"
+                };
+                println!(
+                    "{name:<20} {store:<6} {}",
+                    if snapshots.is_empty() {
+                        String::new()
+                    } else {
+                        format!("snapshots: {}", snapshots.join(", "))
+                    }
+                );
+            }
+        }
+        Some("snapshot") if args.len() == 3 => {
+            let workspace = Workspace::open_in(".", &args[1]).unwrap();
+            workspace.snapshot(&args[2]).unwrap();
+            println!("Saved {} as snapshot {}", workspace.name(), args[2]);
+        }
+        Some("restore") if args.len() == 3 => {
+            let workspace = Workspace::open_in(".", &args[1]).unwrap();
+            workspace.restore(&args[2]).unwrap();
+            println!("Restored {} from snapshot {}", workspace.name(), args[2]);
+        }
+        Some("reset") if args.len() == 2 => {
+            let workspace = Workspace::open_in(".", &args[1]).unwrap();
+            workspace.reset().unwrap();
+            println!("Reset {}, other workspaces are untouched", workspace.name());
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            std::process::exit(1);
+        }
+    }
+}
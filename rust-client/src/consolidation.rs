@@ -0,0 +1,169 @@
+//! Consolidates the notes waiting for an account into its vault.
+//!
+//! Every mint or payment an account receives is a separate note. A [`ConsolidationPolicy`]
+//! picks which of the consumable notes to consume, and [`consolidate`] consumes them in as few
+//! transactions as the kernel's input note limit allows.
+
+use std::collections::BTreeMap;
+
+use miden_client::{
+    account::AccountId,
+    asset::Asset,
+    note::{NoteAssets, NoteId, NoteRelevance},
+    transaction::{TransactionId, TransactionRequestBuilder},
+    Client, ClientError,
+};
+use miden_objects::MAX_INPUT_NOTES_PER_TX;
+
+/// Which consumable notes to consume, and how many per transaction.
+///
+/// The default consumes every note, as many per transaction as the kernel allows. The filters
+/// combine, e.g. all notes of one faucet worth at least 100 tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConsolidationPolicy {
+    max_notes_per_tx: usize,
+    faucet_id: Option<AccountId>,
+    min_amount: u64,
+}
+
+impl Default for ConsolidationPolicy {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl ConsolidationPolicy {
+    /// Consumes every consumable note.
+    pub fn all() -> Self {
+        Self {
+            max_notes_per_tx: MAX_INPUT_NOTES_PER_TX,
+            faucet_id: None,
+            min_amount: 0,
+        }
+    }
+
+    /// Consumes at most `max_notes_per_tx` notes per transaction, capped at the kernel limit.
+    pub fn with_max_notes_per_tx(mut self, max_notes_per_tx: usize) -> Self {
+        self.max_notes_per_tx = max_notes_per_tx.clamp(1, MAX_INPUT_NOTES_PER_TX);
+        self
+    }
+
+    /// Only consumes notes carrying tokens of `faucet_id`.
+    pub fn for_faucet(mut self, faucet_id: AccountId) -> Self {
+        self.faucet_id = Some(faucet_id);
+        self
+    }
+
+    /// Only consumes notes worth at least `min_amount` tokens, counting only the tokens of the
+    /// faucet set with [`Self::for_faucet`] if any.
+    pub fn with_min_amount(mut self, min_amount: u64) -> Self {
+        self.min_amount = min_amount;
+        self
+    }
+
+    pub fn max_notes_per_tx(&self) -> usize {
+        self.max_notes_per_tx
+    }
+
+    /// The fungible amount of `assets` the policy counts.
+    ///
+    /// Amounts of different faucets can add up to more than `u64::MAX`, the value saturates then.
+    pub fn note_value(&self, assets: &NoteAssets) -> u64 {
+        assets
+            .iter_fungible()
+            .filter(|asset| {
+                self.faucet_id
+                    .is_none_or(|faucet_id| asset.faucet_id() == faucet_id)
+            })
+            .fold(0, |value, asset| value.saturating_add(asset.amount()))
+    }
+
+    pub fn accepts(&self, assets: &NoteAssets) -> bool {
+        let has_faucet = self.faucet_id.is_none_or(|faucet_id| {
+            assets
+                .iter_fungible()
+                .any(|asset| asset.faucet_id() == faucet_id)
+        });
+        has_faucet && self.note_value(assets) >= self.min_amount
+    }
+
+    /// Splits the selected notes into the batches consumed by one transaction each.
+    pub fn batches<'a>(&self, note_ids: &'a [NoteId]) -> impl Iterator<Item = &'a [NoteId]> {
+        note_ids.chunks(self.max_notes_per_tx)
+    }
+}
+
+/// Returns the notes `account_id` can consume now that `policy` accepts.
+pub async fn select_notes(
+    client: &Client,
+    account_id: AccountId,
+    policy: &ConsolidationPolicy,
+) -> Result<Vec<NoteId>, ClientError> {
+    let consumable_notes = client.get_consumable_notes(Some(account_id)).await?;
+
+    Ok(consumable_notes
+        .into_iter()
+        .filter(|(note, relevances)| {
+            relevances.iter().any(|(consumer, relevance)| {
+                *consumer == account_id && *relevance == NoteRelevance::Now
+            }) && policy.accepts(note.assets())
+        })
+        .map(|(note, _)| note.id())
+        .collect())
+}
+
+/// What a consolidation consumed and the vault it left behind.
+#[derive(Debug, Clone, Default)]
+pub struct ConsolidationReport {
+    pub transactions: Vec<TransactionId>,
+    pub consumed: Vec<NoteId>,
+    /// Fungible balance of the account per faucet after the last transaction.
+    pub balances: BTreeMap<AccountId, u64>,
+}
+
+/// Consumes the notes selected by `policy` with `account_id`.
+pub async fn consolidate(
+    client: &mut Client,
+    account_id: AccountId,
+    policy: &ConsolidationPolicy,
+) -> Result<ConsolidationReport, ClientError> {
+    let note_ids = select_notes(client, account_id, policy).await?;
+
+    let mut report = ConsolidationReport::default();
+    for batch in policy.batches(&note_ids) {
+        let transaction_request =
+            TransactionRequestBuilder::new().build_consume_notes(batch.to_vec())?;
+        let tx_execution_result = client
+            .new_transaction(account_id, transaction_request)
+            .await?;
+        report
+            .transactions
+            .push(tx_execution_result.executed_transaction().id());
+        client.submit_transaction(tx_execution_result).await?;
+        report.consumed.extend_from_slice(batch);
+    }
+
+    report.balances = fungible_balances(client, account_id).await?;
+    Ok(report)
+}
+
+/// Fungible balance of every faucet in the vault of `account_id`.
+pub async fn fungible_balances(
+    client: &Client,
+    account_id: AccountId,
+) -> Result<BTreeMap<AccountId, u64>, ClientError> {
+    let account = client
+        .get_account(account_id)
+        .await?
+        .ok_or(ClientError::AccountDataNotFound(account_id))?;
+
+    Ok(account
+        .account()
+        .vault()
+        .assets()
+        .filter_map(|asset| match asset {
+            Asset::Fungible(asset) => Some((asset.faucet_id(), asset.amount())),
+            Asset::NonFungible(_) => None,
+        })
+        .collect())
+}
@@ -0,0 +1,332 @@
+//! An escrow account that holds a deposit until an arbiter releases it or it times out.
+//!
+//! The escrow is a contract account (`masm/accounts/escrow.masm`) created for one depositor,
+//! one beneficiary and one arbiter. Deposits arrive as ordinary P2ID notes. A payout moves an
+//! asset out of the vault into a P2ID note created by the contract itself:
+//!
+//! - `release` pays the beneficiary, but only with the arbiter's signature over the asset and
+//!   the note serial number. Each serial number can be released once.
+//! - `refund` pays the depositor back, but only from the timeout block on.
+//!
+//! The arbiter signs offline with [`sign_release`]; whoever submits the release passes the
+//! signature in the transaction's advice map.
+
+use std::fmt;
+
+use miden_client::{
+    account::{
+        Account, AccountBuilder, AccountDelta, AccountId, AccountStorageMode, AccountType,
+        StorageMap, StorageSlot,
+    },
+    asset::{AccountVaultDelta, Asset},
+    auth::{AuthSecretKey, BasicAuthenticator, TransactionAuthenticator},
+    crypto::{Digest, SecretKey},
+    note::{
+        build_p2id_recipient, BlockNumber, Note, NoteAssets, NoteExecutionHint, NoteInputs,
+        NoteMetadata, NoteRecipient, NoteTag, NoteType, WellKnownNote,
+    },
+    transaction::{
+        TransactionKernel, TransactionRequest, TransactionRequestBuilder, TransactionRequestError,
+        TransactionScript,
+    },
+    AuthenticationError, Felt, Word, ZERO,
+};
+use miden_client_tools::create_library;
+use miden_objects::{
+    account::{AccountComponent, AccountStorageDelta},
+    AccountError, Hasher, NoteError,
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::notes::word_to_masm;
+
+/// The escrow component's MASM source.
+pub const ESCROW_CODE: &str = include_str!("../../masm/accounts/escrow.masm");
+
+const NO_AUTH_CODE: &str = include_str!("../../masm/accounts/auth/no_auth.masm");
+
+/// The library path transaction scripts use to call the escrow procedures.
+pub const ESCROW_LIBRARY_PATH: &str = "escrow::escrow_contract";
+
+/// Domain separator of release messages, `RELEASE` in the MASM code.
+const RELEASE: u64 = 1;
+
+/// Errors that can occur when deploying or paying out an escrow.
+#[derive(Debug)]
+pub enum EscrowError {
+    Assembly(String),
+    Account(AccountError),
+    Note(NoteError),
+    Authentication(AuthenticationError),
+    Request(TransactionRequestError),
+}
+
+impl fmt::Display for EscrowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Assembly(err) => write!(f, "failed to assemble escrow code: {err}"),
+            Self::Account(err) => write!(f, "failed to build escrow account: {err}"),
+            Self::Note(err) => write!(f, "failed to build payout note: {err}"),
+            Self::Authentication(err) => write!(f, "failed to sign release: {err}"),
+            Self::Request(err) => write!(f, "failed to build escrow transaction: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for EscrowError {}
+
+impl From<AccountError> for EscrowError {
+    fn from(err: AccountError) -> Self {
+        Self::Account(err)
+    }
+}
+
+impl From<NoteError> for EscrowError {
+    fn from(err: NoteError) -> Self {
+        Self::Note(err)
+    }
+}
+
+impl From<AuthenticationError> for EscrowError {
+    fn from(err: AuthenticationError) -> Self {
+        Self::Authentication(err)
+    }
+}
+
+impl From<TransactionRequestError> for EscrowError {
+    fn from(err: TransactionRequestError) -> Self {
+        Self::Request(err)
+    }
+}
+
+/// Who the escrow pays and when the depositor can take the deposit back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EscrowTerms {
+    /// Falcon public key of the arbiter approving releases.
+    pub arbiter: Word,
+    pub depositor: AccountId,
+    pub beneficiary: AccountId,
+    /// First block from which the depositor can be refunded.
+    pub timeout: BlockNumber,
+    /// Type of the payout notes.
+    pub note_type: NoteType,
+}
+
+impl EscrowTerms {
+    /// Terms paying out in public notes.
+    pub fn new(
+        arbiter: Word,
+        depositor: AccountId,
+        beneficiary: AccountId,
+        timeout: BlockNumber,
+    ) -> Self {
+        Self {
+            arbiter,
+            depositor,
+            beneficiary,
+            timeout,
+            note_type: NoteType::Public,
+        }
+    }
+
+    pub fn with_note_type(mut self, note_type: NoteType) -> Self {
+        self.note_type = note_type;
+        self
+    }
+
+    /// The escrow component's storage, in the slot order of the MASM code.
+    pub fn storage_slots(&self) -> Result<Vec<StorageSlot>, NoteError> {
+        Ok(vec![
+            StorageSlot::Value(self.arbiter),
+            StorageSlot::Value([Felt::from(self.timeout.as_u32()), ZERO, ZERO, ZERO]),
+            StorageSlot::Value(WellKnownNote::P2ID.script_root().into()),
+            StorageSlot::Value(p2id_inputs(self.beneficiary)?.commitment().into()),
+            StorageSlot::Value(self.note_config(self.beneficiary)),
+            StorageSlot::Value(p2id_inputs(self.depositor)?.commitment().into()),
+            StorageSlot::Value(self.note_config(self.depositor)),
+            StorageSlot::Map(StorageMap::new()),
+        ])
+    }
+
+    /// A builder for a public escrow account with these terms.
+    ///
+    /// The account has no authentication of its own: the escrow procedures enforce who can move
+    /// the deposit, and anyone can consume deposit notes into it.
+    pub fn account_builder(&self, init_seed: [u8; 32]) -> Result<AccountBuilder, EscrowError> {
+        let assembler = TransactionKernel::assembler();
+        let no_auth_component = AccountComponent::compile(
+            NO_AUTH_CODE,
+            assembler.clone(),
+            vec![StorageSlot::empty_value()],
+        )?
+        .with_supports_all_types();
+        let escrow_component =
+            AccountComponent::compile(ESCROW_CODE, assembler, self.storage_slots()?)?
+                .with_supports_all_types();
+
+        Ok(AccountBuilder::new(init_seed)
+            .account_type(AccountType::RegularAccountImmutableCode)
+            .storage_mode(AccountStorageMode::Public)
+            .with_component(escrow_component)
+            .with_auth_component(no_auth_component))
+    }
+
+    /// Builds a new escrow account with these terms, returning it with its seed.
+    pub fn build_account(&self, init_seed: [u8; 32]) -> Result<(Account, Word), EscrowError> {
+        Ok(self.account_builder(init_seed)?.build()?)
+    }
+
+    /// The P2ID note the escrow `escrow_id` creates when it pays `payout` to `target`.
+    pub fn payout_note(
+        &self,
+        escrow_id: AccountId,
+        target: AccountId,
+        payout: &EscrowPayout,
+    ) -> Result<Note, NoteError> {
+        let metadata = NoteMetadata::new(
+            escrow_id,
+            self.note_type,
+            NoteTag::from_account_id(target),
+            NoteExecutionHint::always(),
+            ZERO,
+        )?;
+        Ok(Note::new(
+            NoteAssets::new(vec![payout.asset])?,
+            metadata,
+            payout_recipient(target, payout)?,
+        ))
+    }
+
+    /// The transaction releasing `payouts` to the beneficiary, approved by the arbiter's
+    /// `signatures` as returned by [`sign_release`].
+    pub fn release_request(
+        &self,
+        payouts: &[EscrowPayout],
+        signatures: Vec<(Digest, Vec<Felt>)>,
+    ) -> Result<TransactionRequest, EscrowError> {
+        let builder = payout_request_builder("release", self.beneficiary, payouts)?;
+        Ok(builder.extend_advice_map(signatures).build()?)
+    }
+
+    /// The transaction refunding `payouts` to the depositor. It only succeeds from the timeout
+    /// block on.
+    pub fn refund_request(
+        &self,
+        payouts: &[EscrowPayout],
+    ) -> Result<TransactionRequest, EscrowError> {
+        Ok(payout_request_builder("refund", self.depositor, payouts)?.build()?)
+    }
+
+    /// The `[execution_hint, note_type, aux, tag]` word of payout notes to `target`.
+    fn note_config(&self, target: AccountId) -> Word {
+        [
+            NoteExecutionHint::always().into(),
+            self.note_type.into(),
+            ZERO,
+            NoteTag::from_account_id(target).into(),
+        ]
+    }
+}
+
+/// One asset paid out of the escrow, in a note with serial number `serial_num`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EscrowPayout {
+    pub asset: Asset,
+    pub serial_num: Word,
+}
+
+impl EscrowPayout {
+    pub fn new(asset: Asset, serial_num: Word) -> Self {
+        Self { asset, serial_num }
+    }
+
+    /// The message the arbiter signs to release this payout from the escrow `escrow_id`,
+    /// computed the same way as the `release` procedure.
+    pub fn release_message(&self, escrow_id: AccountId) -> Word {
+        let payout = Hasher::merge(&[Word::from(self.asset).into(), self.serial_num.into()]);
+        let domain: Word = [
+            escrow_id.suffix(),
+            escrow_id.prefix().as_felt(),
+            ZERO,
+            Felt::new(RELEASE),
+        ];
+        Hasher::merge(&[payout, domain.into()]).into()
+    }
+}
+
+/// Signs the release of `payout` from the escrow `escrow_id` with the arbiter's key.
+///
+/// The signature stays valid until the payout is released, after which the escrow rejects its
+/// serial number.
+///
+/// Returns the advice map entry the `release` procedure reads the signature from.
+pub fn sign_release(
+    arbiter: &SecretKey,
+    escrow_id: AccountId,
+    payout: &EscrowPayout,
+) -> Result<(Digest, Vec<Felt>), AuthenticationError> {
+    let pub_key: Word = arbiter.public_key().into();
+    let message = payout.release_message(escrow_id);
+    let authenticator = BasicAuthenticator::new_with_rng(
+        &[(pub_key, AuthSecretKey::RpoFalcon512(arbiter.clone()))],
+        StdRng::from_os_rng(),
+    );
+    let no_changes = AccountDelta::new(
+        escrow_id,
+        AccountStorageDelta::default(),
+        AccountVaultDelta::default(),
+        ZERO,
+    )
+    .expect("an empty delta does not need a nonce increment");
+    let signature = authenticator.get_signature(pub_key, message, &no_changes)?;
+
+    Ok((Hasher::merge(&[pub_key.into(), message.into()]), signature))
+}
+
+/// Builds the library transaction scripts link against to call the escrow procedures.
+pub fn escrow_library() -> Result<miden_assembly::Library, EscrowError> {
+    create_library(ESCROW_CODE.to_string(), ESCROW_LIBRARY_PATH)
+        .map_err(|err| EscrowError::Assembly(err.to_string()))
+}
+
+fn p2id_inputs(target: AccountId) -> Result<NoteInputs, NoteError> {
+    NoteInputs::new(vec![target.suffix(), target.prefix().as_felt()])
+}
+
+fn payout_recipient(target: AccountId, payout: &EscrowPayout) -> Result<NoteRecipient, NoteError> {
+    build_p2id_recipient(target, payout.serial_num)
+}
+
+/// A request calling `procedure` once per payout, expecting the P2ID notes it creates for
+/// `target`.
+fn payout_request_builder(
+    procedure: &str,
+    target: AccountId,
+    payouts: &[EscrowPayout],
+) -> Result<TransactionRequestBuilder, EscrowError> {
+    let mut script = String::from("use.escrow::escrow_contract\n\nbegin\n");
+    for payout in payouts {
+        script.push_str(&format!(
+            "    push.{}\n    push.{}\n    call.escrow_contract::{procedure}\n    dropw dropw\n",
+            word_to_masm(Word::from(payout.asset)),
+            word_to_masm(payout.serial_num),
+        ));
+    }
+    script.push_str("end\n");
+
+    let library = escrow_library()?;
+    let assembler = TransactionKernel::assembler()
+        .with_library(&library)
+        .map_err(|err| EscrowError::Assembly(err.to_string()))?;
+    let tx_script = TransactionScript::compile(script, assembler)
+        .map_err(|err| EscrowError::Assembly(err.to_string()))?;
+
+    let recipients = payouts
+        .iter()
+        .map(|payout| payout_recipient(target, payout))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(TransactionRequestBuilder::new()
+        .custom_script(tx_script)
+        .expected_output_recipients(recipients))
+}
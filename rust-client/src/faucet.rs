@@ -0,0 +1,439 @@
+//! Fungible faucet deployment from a configurable spec, supply queries and token amounts.
+//!
+//! A [`FaucetSpec`] holds the token metadata of a new faucet. It is read from the JSON file
+//! named by `MIDEN_FAUCET_SPEC`:
+//!
+//! ```json
+//! { "symbol": "MID", "decimals": 8, "max_supply": 1000000, "storage_mode": "public" }
+//! ```
+//!
+//! `max_supply` is given in base units, like every amount stored on chain. [`parse_amount`]
+//! converts human-readable amounts like `12.5 MID` into base units using the faucet's decimals.
+//!
+//! [`reconcile_supply`] checks the supply a faucet reports as issued against the balances and
+//! notes in the local store.
+
+use std::{cmp::Ordering, collections::BTreeMap, env, fmt, fs, io, path::Path};
+
+use miden_client::{
+    account::{
+        component::{BasicFungibleFaucet, RpoFalcon512},
+        Account, AccountBuilder, AccountId, AccountStorageMode, AccountType,
+    },
+    asset::{Asset, FungibleAsset, TokenSymbol},
+    note::{NoteAssets, NoteId},
+    store::NoteFilter,
+    Client, ClientError, Felt, Word,
+};
+use miden_objects::crypto::dsa::rpo_falcon512::PublicKey;
+use serde::Deserialize;
+
+/// Environment variable holding the path of a JSON faucet spec.
+pub const FAUCET_SPEC_ENV: &str = "MIDEN_FAUCET_SPEC";
+
+/// Storage slot the kernel keeps the total issuance of a fungible faucet in.
+const FAUCET_DATA_SLOT: u8 = 0;
+
+/// Errors that can occur when loading a faucet spec, reading a faucet or handling amounts.
+#[derive(Debug)]
+pub enum FaucetError {
+    Io(io::Error),
+    InvalidSpec(String),
+    NotAFaucet(String),
+    InvalidAmount(String),
+    ExceedsSupply { requested: u64, remaining: u64 },
+    Client(Box<ClientError>),
+}
+
+impl fmt::Display for FaucetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to read faucet spec: {err}"),
+            Self::InvalidSpec(err) => write!(f, "invalid faucet spec: {err}"),
+            Self::NotAFaucet(err) => write!(f, "account is not a basic fungible faucet: {err}"),
+            Self::InvalidAmount(err) => write!(f, "invalid amount: {err}"),
+            Self::ExceedsSupply {
+                requested,
+                remaining,
+            } => write!(
+                f,
+                "cannot mint {requested} base units, the faucet can only issue {remaining} more"
+            ),
+            Self::Client(err) => write!(f, "client error: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for FaucetError {}
+
+impl From<io::Error> for FaucetError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<ClientError> for FaucetError {
+    fn from(err: ClientError) -> Self {
+        Self::Client(Box::new(err))
+    }
+}
+
+/// The JSON layout of a faucet spec file.
+#[derive(Debug, Deserialize)]
+struct SpecFile {
+    symbol: String,
+    decimals: u8,
+    max_supply: u64,
+    #[serde(default = "default_storage_mode")]
+    storage_mode: String,
+}
+
+fn default_storage_mode() -> String {
+    "public".to_string()
+}
+
+/// Token metadata and storage mode of a new basic fungible faucet.
+#[derive(Debug, Clone, Copy)]
+pub struct FaucetSpec {
+    pub symbol: TokenSymbol,
+    pub decimals: u8,
+    /// Maximum supply in base units.
+    pub max_supply: u64,
+    pub storage_mode: AccountStorageMode,
+}
+
+impl Default for FaucetSpec {
+    /// The `MID` token the tutorials deploy unless configured otherwise.
+    fn default() -> Self {
+        Self {
+            symbol: TokenSymbol::new("MID").expect("MID is a valid token symbol"),
+            decimals: 8,
+            max_supply: 1_000_000,
+            storage_mode: AccountStorageMode::Public,
+        }
+    }
+}
+
+impl FaucetSpec {
+    pub fn new(symbol: &str, decimals: u8, max_supply: u64) -> Result<Self, FaucetError> {
+        let spec = Self {
+            symbol: TokenSymbol::new(symbol)
+                .map_err(|err| FaucetError::InvalidSpec(err.to_string()))?,
+            decimals,
+            max_supply,
+            storage_mode: AccountStorageMode::Public,
+        };
+        spec.component()?;
+        Ok(spec)
+    }
+
+    pub fn with_storage_mode(mut self, storage_mode: AccountStorageMode) -> Self {
+        self.storage_mode = storage_mode;
+        self
+    }
+
+    /// Loads a spec from a JSON file, see the module documentation for the layout.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, FaucetError> {
+        let file: SpecFile = serde_json::from_slice(&fs::read(path)?)
+            .map_err(|err| FaucetError::InvalidSpec(err.to_string()))?;
+
+        let storage_mode = match file.storage_mode.trim().to_ascii_lowercase().as_str() {
+            "public" => AccountStorageMode::Public,
+            "private" => AccountStorageMode::Private,
+            other => {
+                return Err(FaucetError::InvalidSpec(format!(
+                    "unknown storage mode {other}, expected public or private"
+                )))
+            }
+        };
+        Ok(
+            Self::new(&file.symbol, file.decimals, file.max_supply)?
+                .with_storage_mode(storage_mode),
+        )
+    }
+
+    /// Loads the spec from the file named by [`FAUCET_SPEC_ENV`], or the default `MID` spec if
+    /// it is not set.
+    pub fn from_env() -> Result<Self, FaucetError> {
+        match env::var(FAUCET_SPEC_ENV) {
+            Ok(path) => Self::from_file(path),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    /// The basic fungible faucet component with this metadata.
+    pub fn component(&self) -> Result<BasicFungibleFaucet, FaucetError> {
+        BasicFungibleFaucet::new(self.symbol, self.decimals, Felt::new(self.max_supply))
+            .map_err(|err| FaucetError::InvalidSpec(err.to_string()))
+    }
+
+    /// A builder for a faucet with this spec, authenticated by `public_key`.
+    pub fn account_builder(
+        &self,
+        init_seed: [u8; 32],
+        public_key: PublicKey,
+    ) -> Result<AccountBuilder, FaucetError> {
+        Ok(AccountBuilder::new(init_seed)
+            .account_type(AccountType::FungibleFaucet)
+            .storage_mode(self.storage_mode)
+            .with_auth_component(RpoFalcon512::new(public_key))
+            .with_component(self.component()?))
+    }
+
+    /// Builds a new faucet with this spec, returning it with its seed.
+    pub fn build_account(
+        &self,
+        init_seed: [u8; 32],
+        public_key: PublicKey,
+    ) -> Result<(Account, Word), FaucetError> {
+        self.account_builder(init_seed, public_key)?
+            .build()
+            .map_err(|err| FaucetError::InvalidSpec(err.to_string()))
+    }
+}
+
+/// The token metadata and supply of a deployed basic fungible faucet.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FaucetInfo {
+    pub symbol: TokenSymbol,
+    pub decimals: u8,
+    /// Maximum supply in base units.
+    pub max_supply: u64,
+    /// Base units minted and not burned yet.
+    pub issued: u64,
+}
+
+impl FaucetInfo {
+    /// Reads the metadata and issued supply from the storage of `account`.
+    pub fn from_account(account: &Account) -> Result<Self, FaucetError> {
+        let faucet = BasicFungibleFaucet::try_from(account)
+            .map_err(|err| FaucetError::NotAFaucet(err.to_string()))?;
+        let issued = account
+            .storage()
+            .get_item(FAUCET_DATA_SLOT)
+            .map_err(|err| FaucetError::NotAFaucet(err.to_string()))?[3]
+            .as_int();
+
+        Ok(Self {
+            symbol: faucet.symbol(),
+            decimals: faucet.decimals(),
+            max_supply: faucet.max_supply().as_int(),
+            issued,
+        })
+    }
+
+    /// Base units the faucet can still mint.
+    pub fn remaining(&self) -> u64 {
+        self.max_supply.saturating_sub(self.issued)
+    }
+
+    pub fn symbol_name(&self) -> String {
+        self.symbol.to_string().unwrap_or_default()
+    }
+
+    /// Parses an amount of this faucet's token, see [`parse_amount`].
+    pub fn parse_amount(&self, input: &str) -> Result<u64, FaucetError> {
+        parse_amount(input, &self.symbol_name(), self.decimals)
+    }
+
+    /// Formats `amount` base units of this faucet's token, e.g. `12.5 MID`.
+    pub fn format_amount(&self, amount: u64) -> String {
+        format!(
+            "{} {}",
+            format_amount(amount, self.decimals),
+            self.symbol_name()
+        )
+    }
+
+    /// The asset minting `amount` base units creates, or an error if it would exceed the
+    /// remaining supply.
+    pub fn mint_asset(
+        &self,
+        faucet_id: AccountId,
+        amount: u64,
+    ) -> Result<FungibleAsset, FaucetError> {
+        if amount > self.remaining() {
+            return Err(FaucetError::ExceedsSupply {
+                requested: amount,
+                remaining: self.remaining(),
+            });
+        }
+        FungibleAsset::new(faucet_id, amount)
+            .map_err(|err| FaucetError::InvalidAmount(err.to_string()))
+    }
+}
+
+/// Where the issued supply of a faucet is, as far as the local store knows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SupplyReport {
+    pub faucet_id: AccountId,
+    /// Base units the faucet reports as issued.
+    pub issued: u64,
+    /// Non-zero balances of the tracked accounts.
+    pub balances: Vec<(AccountId, u64)>,
+    /// Base units in notes the store knows of and that are not consumed yet. This includes burn
+    /// notes the faucet has not consumed.
+    pub in_notes: u64,
+}
+
+/// A mismatch between the issued supply of a faucet and the tokens the store accounts for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SupplyDiscrepancy {
+    /// Base units issued but not found. They are held by accounts or notes the store does not
+    /// track.
+    Untracked(u64),
+    /// Base units found beyond the issued supply. The store holds stale account or note states,
+    /// or the faucet state is stale itself.
+    Excess(u64),
+}
+
+impl fmt::Display for SupplyDiscrepancy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Untracked(amount) => write!(f, "{amount} base units are not tracked locally"),
+            Self::Excess(amount) => write!(f, "{amount} base units more than issued were found"),
+        }
+    }
+}
+
+impl SupplyReport {
+    /// Base units found in tracked accounts and notes.
+    pub fn tracked(&self) -> u64 {
+        self.balances
+            .iter()
+            .map(|(_, balance)| balance)
+            .sum::<u64>()
+            + self.in_notes
+    }
+
+    /// The mismatch between the issued and tracked supply, or `None` if they match.
+    pub fn discrepancy(&self) -> Option<SupplyDiscrepancy> {
+        let tracked = self.tracked();
+        match tracked.cmp(&self.issued) {
+            Ordering::Equal => None,
+            Ordering::Less => Some(SupplyDiscrepancy::Untracked(self.issued - tracked)),
+            Ordering::Greater => Some(SupplyDiscrepancy::Excess(tracked - self.issued)),
+        }
+    }
+}
+
+/// Compares the supply the faucet `faucet_id` reports as issued with the balances of all accounts
+/// and the unconsumed notes in the local store.
+///
+/// The faucet must be tracked by the client, and the report is only as recent as the last sync.
+pub async fn reconcile_supply(
+    client: &Client,
+    faucet_id: AccountId,
+) -> Result<SupplyReport, FaucetError> {
+    let faucet = client
+        .get_account(faucet_id)
+        .await?
+        .ok_or_else(|| FaucetError::NotAFaucet(format!("{faucet_id} is not tracked")))?;
+    let issued = FaucetInfo::from_account(faucet.account())?.issued;
+
+    let mut balances = vec![];
+    for (header, _) in client.get_account_headers().await? {
+        let Some(record) = client.get_account(header.id()).await? else {
+            continue;
+        };
+        let balance = record
+            .account()
+            .vault()
+            .get_balance(faucet_id)
+            .map_err(|err| FaucetError::NotAFaucet(err.to_string()))?;
+        if balance > 0 {
+            balances.push((header.id(), balance));
+        }
+    }
+
+    // A note can be both an input and an output note of the store, so notes are counted by id,
+    // and a note consumed according to either table is not counted
+    let mut unspent: BTreeMap<NoteId, u64> = BTreeMap::new();
+    for note in client.get_input_notes(NoteFilter::Unspent).await? {
+        unspent.insert(note.id(), note_amount(note.assets(), faucet_id));
+    }
+    for note in client.get_output_notes(NoteFilter::Unspent).await? {
+        unspent.insert(note.id(), note_amount(note.assets(), faucet_id));
+    }
+    for note in client.get_input_notes(NoteFilter::Consumed).await? {
+        unspent.remove(&note.id());
+    }
+    for note in client.get_output_notes(NoteFilter::Consumed).await? {
+        unspent.remove(&note.id());
+    }
+
+    Ok(SupplyReport {
+        faucet_id,
+        issued,
+        balances,
+        in_notes: unspent.values().sum(),
+    })
+}
+
+/// Base units of the faucet `faucet_id` in `assets`.
+fn note_amount(assets: &NoteAssets, faucet_id: AccountId) -> u64 {
+    assets
+        .iter()
+        .filter_map(|asset| match asset {
+            Asset::Fungible(asset) if asset.faucet_id() == faucet_id => Some(asset.amount()),
+            _ => None,
+        })
+        .sum()
+}
+
+/// Parses a human-readable amount like `12.5 MID` into base units of a token with `decimals`
+/// decimals.
+///
+/// The symbol is optional, but must match `symbol` if given. Amounts with more fractional digits
+/// than the token has decimals are rejected rather than rounded.
+pub fn parse_amount(input: &str, symbol: &str, decimals: u8) -> Result<u64, FaucetError> {
+    let invalid = |reason: &str| FaucetError::InvalidAmount(format!("{input}: {reason}"));
+
+    let mut parts = input.split_whitespace();
+    let number = parts.next().ok_or_else(|| invalid("empty amount"))?;
+    match parts.next() {
+        Some(given) if !given.eq_ignore_ascii_case(symbol) => {
+            return Err(invalid(&format!("expected {symbol}, got {given}")))
+        }
+        _ => {}
+    }
+    if parts.next().is_some() {
+        return Err(invalid("expected a number and a token symbol"));
+    }
+
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return Err(invalid("missing digits"));
+    }
+    if !whole
+        .chars()
+        .chain(fraction.chars())
+        .all(|c| c.is_ascii_digit())
+    {
+        return Err(invalid("not a decimal number"));
+    }
+    if fraction.len() > decimals as usize {
+        return Err(invalid(&format!("the token only has {decimals} decimals")));
+    }
+
+    let digits = format!("{whole}{fraction:0<width$}", width = decimals as usize);
+    let amount: u64 = digits.parse().map_err(|_| invalid("too large"))?;
+    if amount > FungibleAsset::MAX_AMOUNT {
+        return Err(invalid("too large"));
+    }
+    Ok(amount)
+}
+
+/// Formats `amount` base units of a token with `decimals` decimals, without trailing zeros.
+pub fn format_amount(amount: u64, decimals: u8) -> String {
+    // From 20 decimals on, a single token is more than u64::MAX base units
+    let (whole, fraction) = match 10_u64.checked_pow(decimals as u32) {
+        Some(scale) => (amount / scale, amount % scale),
+        None => (0, amount),
+    };
+    if fraction == 0 {
+        return whole.to_string();
+    }
+    let fraction = format!("{fraction:0>width$}", width = decimals as usize);
+    format!("{whole}.{}", fraction.trim_end_matches('0'))
+}
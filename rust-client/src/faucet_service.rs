@@ -0,0 +1,668 @@
+//! A rate-limited HTTP faucet for testers.
+//!
+//! [`FaucetService`] wraps a faucet account tracked by a [`Client`], so it runs against the
+//! testnet, a local node or a mock RPC client alike. Testers request tokens with a JSON body:
+//!
+//! ```text
+//! POST /mint
+//! { "account_id": "mtst1qz...", "amount": "12.5 MID", "note_type": "private" }
+//! ```
+//!
+//! Every request mints one P2ID note to the account. `note_type` is optional and defaults to
+//! `public`. Private notes are returned in the text encoding of [`NoteExport`], since the
+//! receiver cannot find them on chain.
+//!
+//! Requests are limited per account and for the whole faucet over sliding windows. Every request
+//! is appended to a JSON lines log, which also restores the rate limits after a restart.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt, fs,
+    io::{self, BufRead, Write},
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use miden_client::{
+    account::AccountId,
+    note::NoteType,
+    transaction::{OutputNote, TransactionRequestBuilder},
+    Client, ClientError,
+};
+use miden_objects::account::NetworkId;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    sync::mpsc,
+};
+
+use crate::{
+    faucet::{FaucetError, FaucetInfo},
+    note_file::NoteExport,
+    note_policy::parse_note_type,
+};
+
+/// Address the service listens on unless told otherwise.
+pub const DEFAULT_FAUCET_ADDR: &str = "127.0.0.1:8080";
+
+/// Largest request body the service reads.
+const MAX_BODY_LEN: usize = 64 * 1024;
+
+/// Mint requests read from their connections but not handled yet, before reading waits.
+const MAX_QUEUED_REQUESTS: usize = 64;
+
+/// How long a client may take to send its request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Errors that can occur when handling or sending a mint request.
+#[derive(Debug)]
+pub enum ServiceError {
+    Io(io::Error),
+    InvalidRequest(String),
+    Faucet(FaucetError),
+    RateLimited {
+        retry_after: u64,
+    },
+    Client(Box<ClientError>),
+    /// The service answered a request with an error status.
+    Rejected {
+        status: u16,
+        message: String,
+    },
+}
+
+impl ServiceError {
+    /// The HTTP status the service answers with.
+    pub fn status(&self) -> u16 {
+        match self {
+            Self::InvalidRequest(_) | Self::Faucet(_) => 400,
+            Self::RateLimited { .. } => 429,
+            Self::Io(_) | Self::Client(_) => 500,
+            Self::Rejected { status, .. } => *status,
+        }
+    }
+}
+
+impl fmt::Display for ServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "faucet service I/O failed: {err}"),
+            Self::InvalidRequest(err) => write!(f, "invalid mint request: {err}"),
+            Self::Faucet(err) => write!(f, "{err}"),
+            Self::RateLimited { retry_after } => {
+                write!(f, "rate limit reached, retry in {retry_after} seconds")
+            }
+            Self::Client(err) => write!(f, "failed to mint: {err}"),
+            Self::Rejected { status, message } => {
+                write!(
+                    f,
+                    "faucet rejected the request with status {status}: {message}"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ServiceError {}
+
+impl From<io::Error> for ServiceError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<FaucetError> for ServiceError {
+    fn from(err: FaucetError) -> Self {
+        Self::Faucet(err)
+    }
+}
+
+impl From<ClientError> for ServiceError {
+    fn from(err: ClientError) -> Self {
+        Self::Client(Box::new(err))
+    }
+}
+
+/// The body of a `POST /mint` request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MintRequest {
+    /// The bech32 id of the account to mint to.
+    pub account_id: String,
+    /// An amount like `12.5` or `12.5 MID`, see [`crate::faucet::parse_amount`].
+    pub amount: String,
+    /// `public` or `private`, public if not given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note_type: Option<String>,
+}
+
+impl MintRequest {
+    pub fn new(account_id: AccountId, amount: impl Into<String>) -> Self {
+        Self {
+            account_id: account_id.to_bech32(NetworkId::Testnet),
+            amount: amount.into(),
+            note_type: None,
+        }
+    }
+
+    pub fn with_note_type(mut self, note_type: NoteType) -> Self {
+        let note_type = match note_type {
+            NoteType::Private => "private",
+            _ => "public",
+        };
+        self.note_type = Some(note_type.to_string());
+        self
+    }
+}
+
+/// The answer to a successful mint request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MintResponse {
+    pub tx_id: String,
+    pub note_id: String,
+    /// Minted amount in base units.
+    pub amount: u64,
+    /// The minted note in the text encoding of [`NoteExport`], only for private notes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// At most `max_requests` requests within any `window`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub max_requests: usize,
+    pub window: Duration,
+}
+
+impl RateLimit {
+    pub fn new(max_requests: usize, window: Duration) -> Self {
+        Self {
+            max_requests,
+            window,
+        }
+    }
+}
+
+/// Rate limits and request log location of a [`FaucetService`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FaucetServiceConfig {
+    pub per_account: RateLimit,
+    pub global: RateLimit,
+    pub log_path: PathBuf,
+}
+
+impl FaucetServiceConfig {
+    /// Three mints per account and a hundred mints overall per hour.
+    pub fn new(log_path: impl Into<PathBuf>) -> Self {
+        let hour = Duration::from_secs(60 * 60);
+        Self {
+            per_account: RateLimit::new(3, hour),
+            global: RateLimit::new(100, hour),
+            log_path: log_path.into(),
+        }
+    }
+
+    pub fn with_per_account_limit(mut self, limit: RateLimit) -> Self {
+        self.per_account = limit;
+        self
+    }
+
+    pub fn with_global_limit(mut self, limit: RateLimit) -> Self {
+        self.global = limit;
+        self
+    }
+}
+
+/// One line of the request log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogEntry {
+    /// Seconds since the Unix epoch.
+    pub time: u64,
+    pub request: MintRequest,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<MintResponse>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Reads every entry of the request log at `path`, or none if it does not exist yet.
+pub fn read_log(path: impl AsRef<Path>) -> Result<Vec<LogEntry>, ServiceError> {
+    let file = match fs::File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+
+    let mut entries = Vec::new();
+    for line in io::BufReader::new(file).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry = serde_json::from_str(&line)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// Appends `entry` to the request log at `path`.
+fn append_log(path: &Path, entry: &LogEntry) -> io::Result<()> {
+    let mut log = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    let line = serde_json::to_string(entry)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    writeln!(log, "{line}")
+}
+
+/// Timestamps of the mints within the current window, per account and overall.
+#[derive(Debug, Default)]
+struct RateLimiter {
+    accounts: HashMap<AccountId, VecDeque<u64>>,
+    global: VecDeque<u64>,
+}
+
+impl RateLimiter {
+    fn record(&mut self, account_id: AccountId, time: u64) {
+        self.accounts.entry(account_id).or_default().push_back(time);
+        self.global.push_back(time);
+    }
+
+    /// Seconds until `account_id` may mint again, or `None` if it may mint now.
+    fn retry_after(
+        &mut self,
+        config: &FaucetServiceConfig,
+        account_id: AccountId,
+        now: u64,
+    ) -> Option<u64> {
+        self.prune(config, now);
+        let no_mints = VecDeque::new();
+        let account = self.accounts.get(&account_id).unwrap_or(&no_mints);
+        let account_wait = wait_time(account, config.per_account, now);
+        let global_wait = wait_time(&self.global, config.global, now);
+        account_wait.max(global_wait)
+    }
+
+    /// Drops the timestamps that left their window, and the accounts without any left.
+    fn prune(&mut self, config: &FaucetServiceConfig, now: u64) {
+        self.accounts.retain(|_, times| {
+            expire(times, config.per_account, now);
+            !times.is_empty()
+        });
+        expire(&mut self.global, config.global, now);
+    }
+}
+
+/// Drops the timestamps that left the window of `limit`.
+fn expire(times: &mut VecDeque<u64>, limit: RateLimit, now: u64) {
+    let window = limit.window.as_secs();
+    while times.front().is_some_and(|&time| time + window <= now) {
+        times.pop_front();
+    }
+}
+
+/// How long until one more mint fits into `times`, which only holds timestamps in the window.
+fn wait_time(times: &VecDeque<u64>, limit: RateLimit, now: u64) -> Option<u64> {
+    if times.len() < limit.max_requests {
+        return None;
+    }
+    // The oldest mint that has to leave the window before another one fits.
+    let oldest = times
+        .get(times.len() - limit.max_requests)
+        .copied()
+        .unwrap_or(now);
+    Some((oldest + limit.window.as_secs()).saturating_sub(now).max(1))
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// A faucet account that mints P2ID notes on request.
+pub struct FaucetService {
+    client: Client,
+    faucet_id: AccountId,
+    config: FaucetServiceConfig,
+    limiter: RateLimiter,
+}
+
+impl FaucetService {
+    /// Wraps the faucet `faucet_id`, which `client` must track and hold the key of.
+    ///
+    /// The successful mints in the request log count towards the rate limits.
+    pub fn new(
+        client: Client,
+        faucet_id: AccountId,
+        config: FaucetServiceConfig,
+    ) -> Result<Self, ServiceError> {
+        let mut limiter = RateLimiter::default();
+        for entry in read_log(&config.log_path)? {
+            if entry.response.is_none() {
+                continue;
+            }
+            if let Ok((_, account_id)) = AccountId::from_bech32(&entry.request.account_id) {
+                limiter.record(account_id, entry.time);
+            }
+        }
+
+        Ok(Self {
+            client,
+            faucet_id,
+            config,
+            limiter,
+        })
+    }
+
+    pub fn faucet_id(&self) -> AccountId {
+        self.faucet_id
+    }
+
+    pub fn client(&mut self) -> &mut Client {
+        &mut self.client
+    }
+
+    /// Number of accounts the per-account limit tracks, those with mints in the current window
+    /// as of the last request.
+    pub fn rate_limited_accounts(&self) -> usize {
+        self.limiter.accounts.len()
+    }
+
+    /// Handles one mint request and appends it to the request log.
+    ///
+    /// A failure to write the log is only reported on stderr: the note is minted either way, so
+    /// the requester gets the response.
+    pub async fn mint(&mut self, request: MintRequest) -> Result<MintResponse, ServiceError> {
+        let time = unix_time();
+        let result = self.try_mint(&request, time).await;
+
+        let entry = LogEntry {
+            time,
+            request,
+            response: result.as_ref().ok().cloned(),
+            error: result.as_ref().err().map(ToString::to_string),
+        };
+        if let Err(err) = append_log(&self.config.log_path, &entry) {
+            eprintln!(
+                "failed to log the request for {} to {}: {err}",
+                entry.request.account_id,
+                self.config.log_path.display()
+            );
+        }
+
+        result
+    }
+
+    async fn try_mint(
+        &mut self,
+        request: &MintRequest,
+        time: u64,
+    ) -> Result<MintResponse, ServiceError> {
+        let (_, account_id) = AccountId::from_bech32(&request.account_id)
+            .map_err(|err| ServiceError::InvalidRequest(format!("invalid account id: {err}")))?;
+        let note_type = match &request.note_type {
+            Some(note_type) => parse_note_type(note_type)
+                .map_err(|err| ServiceError::InvalidRequest(err.to_string()))?,
+            None => NoteType::Public,
+        };
+
+        if let Some(retry_after) = self.limiter.retry_after(&self.config, account_id, time) {
+            return Err(ServiceError::RateLimited { retry_after });
+        }
+
+        self.client.sync_state().await?;
+        let faucet = self
+            .client
+            .get_account(self.faucet_id)
+            .await?
+            .ok_or(ClientError::AccountDataNotFound(self.faucet_id))?;
+
+        // Amounts beyond the remaining supply are rejected before a transaction is built
+        let info = FaucetInfo::from_account(faucet.account())?;
+        let amount = info.parse_amount(&request.amount)?;
+        let asset = info.mint_asset(self.faucet_id, amount)?;
+
+        let transaction_request = TransactionRequestBuilder::new()
+            .build_mint_fungible_asset(asset, account_id, note_type, self.client.rng())
+            .map_err(ClientError::TransactionRequestError)?;
+        let tx_execution_result = self
+            .client
+            .new_transaction(self.faucet_id, transaction_request)
+            .await?;
+        let tx_id = tx_execution_result.executed_transaction().id();
+        let note = match tx_execution_result.created_notes().get_note(0) {
+            OutputNote::Full(note) => note.clone(),
+            _ => unreachable!("mint requests create full notes"),
+        };
+        self.client.submit_transaction(tx_execution_result).await?;
+        self.limiter.record(account_id, time);
+
+        Ok(MintResponse {
+            tx_id: tx_id.to_hex(),
+            note_id: note.id().to_hex(),
+            amount,
+            note: (note_type == NoteType::Private).then(|| NoteExport::new(note).to_text()),
+        })
+    }
+
+    /// Answers requests until the listener fails.
+    ///
+    /// Every connection is read and answered on its own task, so a slow client does not hold up
+    /// the others. The mints themselves run one at a time, since they all update the same faucet
+    /// account.
+    pub async fn serve(mut self, listener: TcpListener) -> io::Result<()> {
+        let (sender, mut requests) = mpsc::channel(MAX_QUEUED_REQUESTS);
+        let mut acceptor = tokio::spawn(accept_connections(listener, sender));
+
+        loop {
+            tokio::select! {
+                result = &mut acceptor => return result?,
+                Some((mut stream, peer, request)) = requests.recv() => {
+                    let result = self.mint(request).await;
+                    tokio::spawn(async move {
+                        let written = match result {
+                            Ok(response) => {
+                                let body =
+                                    serde_json::to_string(&response).expect("responses serialize");
+                                write_response(&mut stream, 200, &[], &body).await
+                            }
+                            Err(err) => write_error(&mut stream, &err).await,
+                        };
+                        if let Err(err) = written {
+                            eprintln!("faucet connection from {peer} closed: {err}");
+                        }
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// A mint request waiting for the service, with the connection to answer it on.
+type QueuedRequest = (TcpStream, SocketAddr, MintRequest);
+
+/// Accepts connections until the listener fails, reading each one on its own task.
+async fn accept_connections(
+    listener: TcpListener,
+    sender: mpsc::Sender<QueuedRequest>,
+) -> io::Result<()> {
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let sender = sender.clone();
+        tokio::spawn(async move {
+            if let Err(err) = read_connection(stream, peer, sender).await {
+                eprintln!("faucet connection from {peer} closed: {err}");
+            }
+        });
+    }
+}
+
+/// Reads a mint request from `stream` and queues it, or answers right away if it is invalid.
+async fn read_connection(
+    mut stream: TcpStream,
+    peer: SocketAddr,
+    sender: mpsc::Sender<QueuedRequest>,
+) -> io::Result<()> {
+    let request = match tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream)).await {
+        Ok(request) => request?,
+        Err(_) => {
+            let err = ServiceError::InvalidRequest("request timed out".to_string());
+            return write_error(&mut stream, &err).await;
+        }
+    };
+
+    match (request.method.as_str(), request.path.as_str()) {
+        ("POST", "/mint") => {}
+        (_, "/mint") => {
+            let err = ServiceError::Rejected {
+                status: 405,
+                message: "use POST".to_string(),
+            };
+            return write_error(&mut stream, &err).await;
+        }
+        (_, path) => {
+            let err = ServiceError::Rejected {
+                status: 404,
+                message: format!("no such path {path}"),
+            };
+            return write_error(&mut stream, &err).await;
+        }
+    }
+
+    match serde_json::from_slice(&request.body) {
+        Ok(mint_request) => {
+            // The service only stops when the listener fails, so there is nobody left to answer
+            let _ = sender.send((stream, peer, mint_request)).await;
+            Ok(())
+        }
+        Err(err) => {
+            let err = ServiceError::InvalidRequest(err.to_string());
+            write_error(&mut stream, &err).await
+        }
+    }
+}
+
+struct HttpRequest {
+    method: String,
+    path: String,
+    body: Vec<u8>,
+}
+
+async fn read_request(stream: &mut TcpStream) -> io::Result<HttpRequest> {
+    let invalid = |reason: &str| io::Error::new(io::ErrorKind::InvalidData, reason.to_string());
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+        return Err(invalid("malformed request line"));
+    };
+    let (method, path) = (method.to_string(), path.to_string());
+
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).await? == 0 {
+            return Err(invalid("connection closed in the headers"));
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| invalid("invalid content length"))?;
+            }
+        }
+    }
+    if content_length > MAX_BODY_LEN {
+        return Err(invalid("request body too large"));
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).await?;
+    Ok(HttpRequest { method, path, body })
+}
+
+async fn write_error(stream: &mut TcpStream, err: &ServiceError) -> io::Result<()> {
+    let body = serde_json::json!({ "error": err.to_string() }).to_string();
+    let retry_after = match err {
+        ServiceError::RateLimited { retry_after } => Some(retry_after.to_string()),
+        _ => None,
+    };
+    let headers: Vec<(&str, &str)> = retry_after
+        .as_deref()
+        .map(|retry_after| ("Retry-After", retry_after))
+        .into_iter()
+        .collect();
+    write_response(stream, err.status(), &headers, &body).await
+}
+
+async fn write_response(
+    stream: &mut TcpStream,
+    status: u16,
+    headers: &[(&str, &str)],
+    body: &str,
+) -> io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        429 => "Too Many Requests",
+        _ => "Internal Server Error",
+    };
+    let mut response = format!(
+        "HTTP/1.1 {status} {reason}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+        body.len()
+    );
+    for (name, value) in headers {
+        response.push_str(&format!("{name}: {value}\r\n"));
+    }
+    response.push_str("\r\n");
+    response.push_str(body);
+
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Sends a mint request to the service at `addr`.
+pub async fn request_mint(
+    addr: impl ToSocketAddrs,
+    request: &MintRequest,
+) -> Result<MintResponse, ServiceError> {
+    let mut stream = TcpStream::connect(addr).await?;
+    let body = serde_json::to_string(request).expect("requests serialize");
+    let http_request = format!(
+        "POST /mint HTTP/1.1\r\nHost: faucet\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(http_request.as_bytes()).await?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await?;
+    let invalid = || ServiceError::InvalidRequest(format!("malformed response: {response}"));
+    let status: u16 = response
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse().ok())
+        .ok_or_else(invalid)?;
+    let (_, body) = response.split_once("\r\n\r\n").ok_or_else(invalid)?;
+
+    if status != 200 {
+        let message = serde_json::from_str::<serde_json::Value>(body)
+            .ok()
+            .and_then(|value| value["error"].as_str().map(ToString::to_string))
+            .unwrap_or_else(|| body.to_string());
+        return Err(ServiceError::Rejected { status, message });
+    }
+    serde_json::from_str(body).map_err(|_| invalid())
+}
@@ -0,0 +1,466 @@
+//! A keystore that encrypts secret keys at rest.
+//!
+//! [`EncryptedKeyStore`] has the same interface as the client's `FilesystemKeyStore`: keys are
+//! added with `add_key`, read back with `get_key`, and the store signs transactions as a
+//! [`TransactionAuthenticator`]. It also uses the same file per key, but the file holds the key
+//! encrypted with ChaCha20-Poly1305 instead of in plaintext.
+//!
+//! The encryption key is derived from a password with Argon2id. The salt and Argon2 parameters
+//! are kept in `keystore.json` in the keys directory, together with a check value that detects a
+//! wrong password. The key is derived once when the store is unlocked and kept in memory for the
+//! rest of the session, so signing does not ask for the password again.
+//!
+//! [`migrate_keystore`] encrypts an existing plaintext keystore in place. Workspace clients
+//! unlock a migrated keystore with the password, see
+//! [`Workspace::instantiate_client`](crate::workspace::Workspace::instantiate_client).
+
+use std::{
+    collections::hash_map::DefaultHasher,
+    fmt, fs,
+    hash::{Hash, Hasher},
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use miden_client::{
+    account::AccountDelta,
+    auth::{AuthSecretKey, BasicAuthenticator, TransactionAuthenticator},
+    crypto::Digest,
+    utils::{Deserializable, Serializable},
+    AuthenticationError, Felt, Word,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+/// Name of the file holding the key derivation parameters in an encrypted keys directory.
+pub const KEYSTORE_HEADER_FILE: &str = "keystore.json";
+
+/// Environment variable the tutorial binaries read the keystore password from.
+pub const KEYSTORE_PASSWORD_ENV: &str = "MIDEN_KEYSTORE_PASSWORD";
+
+/// Plaintext encrypted into the header's check value.
+const CHECK_PLAINTEXT: &[u8] = b"miden-tutorials-keystore";
+
+const NONCE_LEN: usize = 12;
+const SALT_LEN: usize = 16;
+
+/// Errors that can occur when creating, unlocking or using an encrypted keystore.
+#[derive(Debug)]
+pub enum KeystoreError {
+    Io(io::Error),
+    /// The keys directory has no `keystore.json`, so it is not an encrypted keystore.
+    NotEncrypted(PathBuf),
+    /// The keys directory already has a `keystore.json`.
+    AlreadyEncrypted(PathBuf),
+    /// The keys directory has files but no `keystore.json`, e.g. the keys of a plaintext
+    /// keystore.
+    NotEmpty(PathBuf),
+    WrongPassword,
+    /// A key file or the header could not be decrypted or decoded.
+    Corrupted(String),
+    Kdf(String),
+}
+
+impl fmt::Display for KeystoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "keystore I/O error: {err}"),
+            Self::NotEncrypted(dir) => write!(f, "{} is not an encrypted keystore", dir.display()),
+            Self::AlreadyEncrypted(dir) => {
+                write!(f, "{} is already an encrypted keystore", dir.display())
+            }
+            Self::NotEmpty(dir) => write!(
+                f,
+                "{} is not empty, plaintext keystores have to be migrated",
+                dir.display()
+            ),
+            Self::WrongPassword => write!(f, "wrong keystore password"),
+            Self::Corrupted(err) => write!(f, "corrupted keystore: {err}"),
+            Self::Kdf(err) => write!(f, "failed to derive the keystore key: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for KeystoreError {}
+
+impl From<io::Error> for KeystoreError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// The contents of `keystore.json`.
+#[derive(Debug, Serialize, Deserialize)]
+struct Header {
+    /// Argon2id memory cost in KiB.
+    m_cost: u32,
+    /// Argon2id number of iterations.
+    t_cost: u32,
+    /// Argon2id degree of parallelism.
+    p_cost: u32,
+    /// Hex-encoded salt.
+    salt: String,
+    /// Hex-encoded encryption of [`CHECK_PLAINTEXT`].
+    check: String,
+}
+
+/// A keystore holding Falcon secret keys encrypted under a password.
+///
+/// Cloning the store shares the unlocked session, so a clone can be handed to the client as its
+/// authenticator while the original is used to add keys.
+#[derive(Clone)]
+pub struct EncryptedKeyStore {
+    keys_directory: PathBuf,
+    key: Arc<Zeroizing<[u8; 32]>>,
+}
+
+impl fmt::Debug for EncryptedKeyStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncryptedKeyStore")
+            .field("keys_directory", &self.keys_directory)
+            .finish_non_exhaustive()
+    }
+}
+
+impl EncryptedKeyStore {
+    /// Creates an empty encrypted keystore in `keys_directory`, protected by `password`.
+    ///
+    /// Fails if the directory already holds an encrypted keystore, or any other files. A
+    /// plaintext keystore is encrypted with [`migrate_keystore`] instead.
+    pub fn create(
+        keys_directory: impl Into<PathBuf>,
+        password: &str,
+    ) -> Result<Self, KeystoreError> {
+        let keys_directory = keys_directory.into();
+        if keys_directory.join(KEYSTORE_HEADER_FILE).exists() {
+            return Err(KeystoreError::AlreadyEncrypted(keys_directory));
+        }
+        if keys_directory.exists() && fs::read_dir(&keys_directory)?.next().is_some() {
+            return Err(KeystoreError::NotEmpty(keys_directory));
+        }
+        Self::init(keys_directory, password)
+    }
+
+    /// Writes a new `keystore.json` to `keys_directory`.
+    fn init(keys_directory: PathBuf, password: &str) -> Result<Self, KeystoreError> {
+        let header_path = keys_directory.join(KEYSTORE_HEADER_FILE);
+        if header_path.exists() {
+            return Err(KeystoreError::AlreadyEncrypted(keys_directory));
+        }
+        fs::create_dir_all(&keys_directory)?;
+
+        let params = Params::default();
+        let salt: [u8; SALT_LEN] = rand::random();
+        let key = derive_key(password, &salt, params.clone())?;
+        let check = encrypt(&key, CHECK_PLAINTEXT, KEYSTORE_HEADER_FILE)?;
+        let header = Header {
+            m_cost: params.m_cost(),
+            t_cost: params.t_cost(),
+            p_cost: params.p_cost(),
+            salt: hex::encode(salt),
+            check: hex::encode(check),
+        };
+        let json = serde_json::to_vec_pretty(&header)
+            .map_err(|err| KeystoreError::Corrupted(err.to_string()))?;
+        fs::write(header_path, json)?;
+
+        Ok(Self {
+            keys_directory,
+            key: Arc::new(key),
+        })
+    }
+
+    /// Unlocks the encrypted keystore in `keys_directory` with `password`.
+    ///
+    /// This is the only time the password is needed: the derived key stays in memory until the
+    /// last clone of the store is dropped.
+    pub fn unlock(
+        keys_directory: impl Into<PathBuf>,
+        password: &str,
+    ) -> Result<Self, KeystoreError> {
+        let keys_directory = keys_directory.into();
+        let header_path = keys_directory.join(KEYSTORE_HEADER_FILE);
+        if !header_path.exists() {
+            return Err(KeystoreError::NotEncrypted(keys_directory));
+        }
+        let header: Header = serde_json::from_slice(&fs::read(header_path)?)
+            .map_err(|err| KeystoreError::Corrupted(err.to_string()))?;
+
+        let params = Params::new(header.m_cost, header.t_cost, header.p_cost, None)
+            .map_err(|err| KeystoreError::Kdf(err.to_string()))?;
+        let salt = decode_hex(&header.salt)?;
+        let key = derive_key(password, &salt, params)?;
+        let check = decode_hex(&header.check)?;
+        match decrypt(&key, &check, KEYSTORE_HEADER_FILE) {
+            Ok(plaintext) if plaintext.as_slice() == CHECK_PLAINTEXT => {}
+            _ => return Err(KeystoreError::WrongPassword),
+        }
+
+        Ok(Self {
+            keys_directory,
+            key: Arc::new(key),
+        })
+    }
+
+    /// Unlocks the keystore in `keys_directory`, creating it first if the directory does not
+    /// exist or is empty.
+    pub fn open(keys_directory: impl Into<PathBuf>, password: &str) -> Result<Self, KeystoreError> {
+        let keys_directory = keys_directory.into();
+        if keys_directory.join(KEYSTORE_HEADER_FILE).exists() {
+            Self::unlock(keys_directory, password)
+        } else {
+            Self::create(keys_directory, password)
+        }
+    }
+
+    pub fn keys_directory(&self) -> &Path {
+        &self.keys_directory
+    }
+
+    /// Encrypts `key` and adds it to the keystore.
+    pub fn add_key(&self, key: &AuthSecretKey) -> Result<(), KeystoreError> {
+        let AuthSecretKey::RpoFalcon512(secret_key) = key;
+        let filename = key_filename(secret_key.public_key().into());
+        let plaintext = Zeroizing::new(key.to_bytes());
+        let ciphertext = encrypt(&self.key, &plaintext, &filename)?;
+        fs::write(self.keys_directory.join(filename), hex::encode(ciphertext))?;
+        Ok(())
+    }
+
+    /// Decrypts the secret key of `pub_key`, or returns `None` if the keystore does not have it.
+    pub fn get_key(&self, pub_key: Word) -> Result<Option<AuthSecretKey>, KeystoreError> {
+        let filename = key_filename(pub_key);
+        let path = self.keys_directory.join(&filename);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let ciphertext = decode_hex(fs::read_to_string(path)?.trim())?;
+        let plaintext = decrypt(&self.key, &ciphertext, &filename)?;
+        let key = AuthSecretKey::read_from_bytes(&plaintext)
+            .map_err(|err| KeystoreError::Corrupted(format!("key file {filename}: {err}")))?;
+        Ok(Some(key))
+    }
+
+    /// The public keys of all keys in the keystore.
+    ///
+    /// Files that are not encrypted keys are skipped, like plaintext key files written by a
+    /// `FilesystemKeyStore` opened on the directory after it was encrypted.
+    pub fn public_keys(&self) -> Result<Vec<Word>, KeystoreError> {
+        let mut public_keys = vec![];
+        for entry in fs::read_dir(&self.keys_directory)? {
+            let path = entry?.path();
+            let Some(filename) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            if filename == KEYSTORE_HEADER_FILE || !path.is_file() {
+                continue;
+            }
+            let Ok(ciphertext) = hex::decode(fs::read_to_string(&path)?.trim()) else {
+                continue;
+            };
+            if AuthSecretKey::read_from_bytes(&ciphertext).is_ok() {
+                continue;
+            }
+            let plaintext = decrypt(&self.key, &ciphertext, filename)?;
+            let AuthSecretKey::RpoFalcon512(key) = AuthSecretKey::read_from_bytes(&plaintext)
+                .map_err(|err| KeystoreError::Corrupted(format!("key file {filename}: {err}")))?;
+            public_keys.push(key.public_key().into());
+        }
+        Ok(public_keys)
+    }
+}
+
+impl TransactionAuthenticator for EncryptedKeyStore {
+    fn get_signature(
+        &self,
+        pub_key: Word,
+        message: Word,
+        account_delta: &AccountDelta,
+    ) -> Result<Vec<Felt>, AuthenticationError> {
+        let key = self
+            .get_key(pub_key)
+            .map_err(|err| AuthenticationError::other(err.to_string()))?
+            .ok_or(AuthenticationError::UnknownPublicKey(
+                Digest::from(pub_key).into(),
+            ))?;
+        let authenticator =
+            BasicAuthenticator::new_with_rng(&[(pub_key, key)], StdRng::from_os_rng());
+        authenticator.get_signature(pub_key, message, account_delta)
+    }
+}
+
+/// Encrypts the plaintext keystore in `keys_directory` in place with `password`, returning the
+/// number of keys encrypted.
+///
+/// The keys are encrypted into a sibling `<dir>.migrating` directory, which replaces the keys
+/// directory only once every key was written. A migration that fails therefore leaves the
+/// plaintext keystore as it was, and can simply be run again. Files that are not key files of a
+/// `FilesystemKeyStore` are copied over unchanged.
+///
+/// The old directory is renamed to `<dir>.plaintext` while the two are swapped. If a migration
+/// was interrupted during the swap, the next one first rolls the swap back, or finishes it if the
+/// encrypted keystore is already in place.
+///
+/// Tools using the plaintext `FilesystemKeyStore` can no longer read the directory afterwards.
+pub fn migrate_keystore(
+    keys_directory: impl AsRef<Path>,
+    password: &str,
+) -> Result<usize, KeystoreError> {
+    let keys_directory = std::path::absolute(keys_directory)?;
+    recover_interrupted_swap(&keys_directory)?;
+    let keys_directory = fs::canonicalize(keys_directory)?;
+    if keys_directory.join(KEYSTORE_HEADER_FILE).exists() {
+        return Err(KeystoreError::AlreadyEncrypted(keys_directory));
+    }
+
+    // Read every file first, so nothing is written if the directory cannot be migrated
+    let mut keys = vec![];
+    let mut other_files = vec![];
+    for entry in fs::read_dir(&keys_directory)? {
+        let path = entry?.path();
+        if !path.is_file() {
+            return Err(KeystoreError::Io(io::Error::other(format!(
+                "{} is a directory, keystores only hold files",
+                path.display()
+            ))));
+        }
+        match hex::decode(fs::read_to_string(&path)?.trim()) {
+            Ok(bytes) => match AuthSecretKey::read_from_bytes(&bytes) {
+                Ok(key) => keys.push(key),
+                Err(_) => other_files.push(path),
+            },
+            Err(_) => other_files.push(path),
+        }
+    }
+
+    let staging = sibling(&keys_directory, "migrating");
+    if staging.exists() {
+        // Left behind by a migration that failed, the plaintext keys are still in place
+        fs::remove_dir_all(&staging)?;
+    }
+    let store = EncryptedKeyStore::init(staging.clone(), password)?;
+    for key in &keys {
+        store.add_key(key)?;
+    }
+    for path in &other_files {
+        let filename = path
+            .file_name()
+            .expect("directory entries have a file name");
+        fs::copy(path, staging.join(filename))?;
+    }
+
+    let plaintext = sibling(&keys_directory, "plaintext");
+    fs::rename(&keys_directory, &plaintext)?;
+    if let Err(err) = fs::rename(&staging, &keys_directory) {
+        fs::rename(&plaintext, &keys_directory)?;
+        return Err(err.into());
+    }
+    fs::remove_dir_all(plaintext)?;
+
+    Ok(keys.len())
+}
+
+/// Cleans up after a migration that stopped between renaming `keys_directory` to
+/// `<dir>.plaintext` and removing that directory.
+fn recover_interrupted_swap(keys_directory: &Path) -> io::Result<()> {
+    let plaintext = sibling(keys_directory, "plaintext");
+    if !plaintext.exists() {
+        return Ok(());
+    }
+    if keys_directory.join(KEYSTORE_HEADER_FILE).exists() {
+        // The encrypted keystore is in place, only the plaintext keys were not removed yet
+        return fs::remove_dir_all(plaintext);
+    }
+    // The keys directory is missing, or is an empty directory a tool created in its place. The
+    // staging directory is discarded by the migration, which then starts over
+    if keys_directory.exists() {
+        fs::remove_dir(keys_directory)?;
+    }
+    fs::rename(plaintext, keys_directory)
+}
+
+/// Reads the keystore password from [`KEYSTORE_PASSWORD_ENV`], or asks for it on the terminal
+/// without echoing it.
+pub fn read_password() -> Result<String, KeystoreError> {
+    if let Ok(password) = std::env::var(KEYSTORE_PASSWORD_ENV) {
+        return Ok(password);
+    }
+    Ok(rpassword::prompt_password("Keystore password: ")?)
+}
+
+/// `dir` with `.suffix` appended to its name.
+fn sibling(dir: &Path, suffix: &str) -> PathBuf {
+    let mut name = dir.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{suffix}"));
+    dir.with_file_name(name)
+}
+
+/// The key file name of `pub_key`, the same as the client's `FilesystemKeyStore` uses.
+fn key_filename(pub_key: Word) -> String {
+    let pub_key = Digest::from(pub_key).to_hex();
+    let mut hasher = DefaultHasher::new();
+    pub_key.hash(&mut hasher);
+    hasher.finish().to_string()
+}
+
+fn derive_key(
+    password: &str,
+    salt: &[u8],
+    params: Params,
+) -> Result<Zeroizing<[u8; 32]>, KeystoreError> {
+    let mut key = Zeroizing::new([0_u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(password.as_bytes(), salt, key.as_mut())
+        .map_err(|err| KeystoreError::Kdf(err.to_string()))?;
+    Ok(key)
+}
+
+/// Encrypts `plaintext` as `nonce || ciphertext`. The file name is authenticated with it, so an
+/// encrypted key cannot be moved to the file of another key.
+fn encrypt(key: &[u8; 32], plaintext: &[u8], filename: &str) -> Result<Vec<u8>, KeystoreError> {
+    let nonce: [u8; NONCE_LEN] = rand::random();
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+    let ciphertext = cipher
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext,
+                aad: filename.as_bytes(),
+            },
+        )
+        .map_err(|_| KeystoreError::Corrupted("encryption failed".to_string()))?;
+    Ok([nonce.as_slice(), &ciphertext].concat())
+}
+
+fn decrypt(
+    key: &[u8; 32],
+    data: &[u8],
+    filename: &str,
+) -> Result<Zeroizing<Vec<u8>>, KeystoreError> {
+    if data.len() < NONCE_LEN {
+        return Err(KeystoreError::Corrupted(format!("{filename} is truncated")));
+    }
+    let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+    let plaintext = cipher
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: filename.as_bytes(),
+            },
+        )
+        .map_err(|_| KeystoreError::Corrupted(format!("{filename} failed authentication")))?;
+    Ok(Zeroizing::new(plaintext))
+}
+
+fn decode_hex(data: &str) -> Result<Vec<u8>, KeystoreError> {
+    hex::decode(data).map_err(|err| KeystoreError::Corrupted(err.to_string()))
+}
@@ -0,0 +1,27 @@
+//! Reusable building blocks for the Miden tutorials.
+//!
+//! The binaries in `src/bin` walk through each flow step by step. The pieces that more than one
+//! tutorial (or a test) needs live here.
+
+pub mod account_bundle;
+pub mod accounts;
+pub mod address_book;
+pub mod bench;
+pub mod consolidation;
+pub mod escrow;
+pub mod faucet;
+pub mod faucet_service;
+pub mod keystore;
+pub mod mnemonic;
+pub mod multisig;
+pub mod nft;
+pub mod note_file;
+pub mod note_policy;
+pub mod notes;
+pub mod payout;
+pub mod prover;
+pub mod proving_bench;
+pub mod reclaim;
+pub mod relay;
+pub mod swap;
+pub mod workspace;
@@ -0,0 +1,248 @@
+//! Deterministic account derivation from a BIP39 mnemonic.
+//!
+//! The tutorials otherwise draw every account's init seed and Falcon key pair from the client's
+//! RNG, so an account is lost together with `./keystore` or the sqlite store. An
+//! [`AccountDeriver`] derives both from a mnemonic instead: the mnemonic and an optional
+//! passphrase are stretched into a BIP39 seed, and the init seed and key pair of the account at
+//! a given index are hashed from that seed, the account type and the index. Writing down the
+//! mnemonic is then enough to rebuild every account.
+//!
+//! [`recover_accounts`] re-derives the accounts of a template one index after the other and
+//! re-imports the public ones from the network by id, stopping after a number of consecutive
+//! indices that were never deployed.
+
+use std::fmt;
+
+use bip39::Mnemonic;
+use miden_client::{
+    account::{
+        component::{BasicWallet, RpoFalcon512},
+        Account, AccountBuilder, AccountStorageMode, AccountType,
+    },
+    crypto::SecretKey,
+    rpc::RpcError,
+    Client, ClientError, Word,
+};
+use miden_objects::Hasher;
+use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+use zeroize::Zeroizing;
+
+use crate::faucet::FaucetSpec;
+
+/// Environment variable the tutorial binaries read the mnemonic from.
+pub const MNEMONIC_ENV: &str = "MIDEN_MNEMONIC";
+
+/// Environment variable holding the optional BIP39 passphrase.
+pub const MNEMONIC_PASSPHRASE_ENV: &str = "MIDEN_MNEMONIC_PASSPHRASE";
+
+/// Number of consecutive missing accounts after which [`recover_accounts`] stops by default.
+pub const DEFAULT_GAP_LIMIT: u32 = 5;
+
+/// Domain separators, so that an init seed is never reused as a key seed.
+const INIT_SEED_DOMAIN: &[u8] = b"miden-tutorials/init-seed";
+const SECRET_KEY_DOMAIN: &[u8] = b"miden-tutorials/falcon512";
+
+/// Start of the text of a gRPC `NotFound` status.
+const NOT_FOUND_STATUS: &str = "status: NotFound,";
+
+/// Errors that can occur when deriving or recovering accounts.
+#[derive(Debug)]
+pub enum MnemonicError {
+    InvalidMnemonic(String),
+    Account(String),
+    Client(Box<ClientError>),
+}
+
+impl fmt::Display for MnemonicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidMnemonic(err) => write!(f, "invalid mnemonic: {err}"),
+            Self::Account(err) => write!(f, "failed to build derived account: {err}"),
+            Self::Client(err) => write!(f, "client error: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for MnemonicError {}
+
+impl From<ClientError> for MnemonicError {
+    fn from(err: ClientError) -> Self {
+        Self::Client(Box::new(err))
+    }
+}
+
+/// Generates a new 24-word English mnemonic from 256 bits of OS entropy.
+pub fn generate_mnemonic() -> String {
+    let entropy = Zeroizing::new(rand::random::<[u8; 32]>());
+    Mnemonic::from_entropy(entropy.as_slice())
+        .expect("32 bytes is a valid BIP39 entropy length")
+        .to_string()
+}
+
+/// The kind of account derived at each index.
+#[derive(Debug, Clone, Copy)]
+pub enum AccountTemplate {
+    /// A basic wallet with Falcon authentication.
+    Wallet(AccountStorageMode),
+    /// A basic fungible faucet with the given spec.
+    Faucet(FaucetSpec),
+}
+
+impl AccountTemplate {
+    pub fn account_type(&self) -> AccountType {
+        match self {
+            Self::Wallet(_) => AccountType::RegularAccountUpdatableCode,
+            Self::Faucet(_) => AccountType::FungibleFaucet,
+        }
+    }
+
+    fn build(&self, init_seed: [u8; 32], key: &SecretKey) -> Result<(Account, Word), String> {
+        match self {
+            Self::Wallet(storage_mode) => AccountBuilder::new(init_seed)
+                .account_type(AccountType::RegularAccountUpdatableCode)
+                .storage_mode(*storage_mode)
+                .with_auth_component(RpoFalcon512::new(key.public_key()))
+                .with_component(BasicWallet)
+                .build()
+                .map_err(|err| err.to_string()),
+            Self::Faucet(spec) => spec
+                .build_account(init_seed, key.public_key())
+                .map_err(|err| err.to_string()),
+        }
+    }
+}
+
+/// An account re-derived from a mnemonic, with the seed and key needed to use it.
+#[derive(Debug, Clone)]
+pub struct DerivedAccount {
+    pub index: u32,
+    pub account: Account,
+    pub seed: Word,
+    pub secret_key: SecretKey,
+}
+
+/// Derives account init seeds and Falcon key pairs from a mnemonic.
+#[derive(Clone)]
+pub struct AccountDeriver {
+    seed: Zeroizing<[u8; 64]>,
+}
+
+impl fmt::Debug for AccountDeriver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AccountDeriver").finish_non_exhaustive()
+    }
+}
+
+impl AccountDeriver {
+    /// Parses an English mnemonic and stretches it with `passphrase` into the BIP39 seed.
+    pub fn from_mnemonic(phrase: &str, passphrase: &str) -> Result<Self, MnemonicError> {
+        let mnemonic = Mnemonic::parse(phrase)
+            .map_err(|err| MnemonicError::InvalidMnemonic(err.to_string()))?;
+        Ok(Self {
+            seed: Zeroizing::new(mnemonic.to_seed(passphrase)),
+        })
+    }
+
+    /// Reads the mnemonic from [`MNEMONIC_ENV`] and the passphrase from
+    /// [`MNEMONIC_PASSPHRASE_ENV`], which may be unset.
+    pub fn from_env() -> Result<Self, MnemonicError> {
+        let phrase = std::env::var(MNEMONIC_ENV)
+            .map_err(|_| MnemonicError::InvalidMnemonic(format!("{MNEMONIC_ENV} is not set")))?;
+        let passphrase = Zeroizing::new(std::env::var(MNEMONIC_PASSPHRASE_ENV).unwrap_or_default());
+        Self::from_mnemonic(&phrase, &passphrase)
+    }
+
+    /// The init seed of the account of `account_type` at `index`.
+    pub fn init_seed(&self, account_type: AccountType, index: u32) -> [u8; 32] {
+        self.derive(INIT_SEED_DOMAIN, account_type, index)
+    }
+
+    /// The Falcon key pair of the account of `account_type` at `index`.
+    pub fn secret_key(&self, account_type: AccountType, index: u32) -> SecretKey {
+        let key_seed = Zeroizing::new(self.derive(SECRET_KEY_DOMAIN, account_type, index));
+        SecretKey::with_rng(&mut ChaCha20Rng::from_seed(*key_seed))
+    }
+
+    /// Builds the account of `template` at `index`.
+    pub fn account(
+        &self,
+        template: &AccountTemplate,
+        index: u32,
+    ) -> Result<DerivedAccount, MnemonicError> {
+        let account_type = template.account_type();
+        let secret_key = self.secret_key(account_type, index);
+        let (account, seed) = template
+            .build(self.init_seed(account_type, index), &secret_key)
+            .map_err(MnemonicError::Account)?;
+        Ok(DerivedAccount {
+            index,
+            account,
+            seed,
+            secret_key,
+        })
+    }
+
+    fn derive(&self, domain: &[u8], account_type: AccountType, index: u32) -> [u8; 32] {
+        let mut data = Zeroizing::new(Vec::with_capacity(domain.len() + 64 + 5));
+        data.extend_from_slice(domain);
+        data.extend_from_slice(self.seed.as_slice());
+        data.push(account_type as u8);
+        data.extend_from_slice(&index.to_le_bytes());
+        Hasher::hash(&data).as_bytes()
+    }
+}
+
+/// The accounts found by [`recover_accounts`].
+#[derive(Debug, Default)]
+pub struct Recovery {
+    /// Public accounts that were imported into the client with their on-chain state.
+    pub imported: Vec<DerivedAccount>,
+    /// Private accounts that exist on chain. Their keys are recovered, but their state has to be
+    /// restored from a backup of the store.
+    pub private: Vec<DerivedAccount>,
+}
+
+/// Re-derives the accounts of `template` from index 0 and imports the public ones by id.
+///
+/// An index whose account id is unknown to the node counts towards the gap; the scan stops once
+/// `gap_limit` consecutive indices were not found. Any other failure, like a timeout or an
+/// unavailable node, stops the scan with an error, since it says nothing about the account. The
+/// caller adds the recovered secret keys to its keystore.
+pub async fn recover_accounts(
+    client: &mut Client,
+    deriver: &AccountDeriver,
+    template: &AccountTemplate,
+    gap_limit: u32,
+) -> Result<Recovery, MnemonicError> {
+    let mut recovery = Recovery::default();
+    let mut gap = 0;
+    let mut index = 0;
+    while gap < gap_limit {
+        let derived = deriver.account(template, index)?;
+        match client.import_account_by_id(derived.account.id()).await {
+            Ok(()) => {
+                recovery.imported.push(derived);
+                gap = 0;
+            }
+            Err(ClientError::AccountIsPrivate(_)) => {
+                recovery.private.push(derived);
+                gap = 0;
+            }
+            Err(err) if is_account_not_found(&err) => gap += 1,
+            Err(err) => return Err(err.into()),
+        }
+        index += 1;
+    }
+    Ok(recovery)
+}
+
+/// Whether `err` is the node's answer to a request for an account id it does not know.
+///
+/// The RPC client only keeps the text of the gRPC status, which starts with its code.
+fn is_account_not_found(err: &ClientError) -> bool {
+    matches!(
+        err,
+        ClientError::RpcError(RpcError::RequestError(_, status))
+            if status.starts_with(NOT_FOUND_STATUS)
+    )
+}
@@ -0,0 +1,300 @@
+//! M-of-N Falcon multisig authentication for shared accounts.
+//!
+//! The `masm/accounts/auth/multisig.masm` component replaces `no_auth.masm` on accounts several
+//! parties control. It stores the signers' public keys in a map and the number of signatures a
+//! transaction needs in a value slot.
+//!
+//! Signers approve a [`TransactionSummary`], which commits to the account, its nonce and the
+//! notes the transaction consumes and creates. A [`SigningCoordinator`] gathers their signatures
+//! from the keystores holding their keys and turns them into the advice the component reads.
+
+use std::{collections::BTreeSet, fmt};
+
+use miden_client::{
+    account::{AccountDelta, AccountId, StorageMap, StorageSlot},
+    asset::AccountVaultDelta,
+    auth::TransactionAuthenticator,
+    crypto::Digest,
+    keystore::{FilesystemKeyStore, KeyStoreError},
+    transaction::{InputNote, InputNotes, OutputNotes, TransactionKernel},
+    AuthenticationError, Felt, Word, ZERO,
+};
+use miden_objects::{
+    account::{AccountComponent, AccountStorageDelta},
+    AccountError, Hasher,
+};
+use rand::rngs::StdRng;
+
+/// The multisig authentication component's MASM source.
+pub const MULTISIG_AUTH_CODE: &str = include_str!("../../masm/accounts/auth/multisig.masm");
+
+/// Errors that can occur when configuring a multisig account or collecting its signatures.
+#[derive(Debug)]
+pub enum MultisigError {
+    InvalidThreshold { threshold: usize, signers: usize },
+    DuplicateSigner(Word),
+    ThresholdNotMet { threshold: usize, collected: usize },
+    KeyStore(KeyStoreError),
+    Authentication(AuthenticationError),
+    Account(AccountError),
+}
+
+impl fmt::Display for MultisigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidThreshold { threshold, signers } => write!(
+                f,
+                "threshold must be between 1 and the number of signers ({signers}), got {threshold}"
+            ),
+            Self::DuplicateSigner(pub_key) => {
+                write!(f, "signer {} is listed more than once", Digest::from(*pub_key))
+            }
+            Self::ThresholdNotMet {
+                threshold,
+                collected,
+            } => write!(
+                f,
+                "the transaction needs {threshold} signatures, but only {collected} signers could sign"
+            ),
+            Self::KeyStore(err) => write!(f, "failed to read signer key: {err}"),
+            Self::Authentication(err) => write!(f, "failed to sign transaction: {err}"),
+            Self::Account(err) => write!(f, "failed to build multisig component: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for MultisigError {}
+
+impl From<KeyStoreError> for MultisigError {
+    fn from(err: KeyStoreError) -> Self {
+        Self::KeyStore(err)
+    }
+}
+
+impl From<AuthenticationError> for MultisigError {
+    fn from(err: AuthenticationError) -> Self {
+        Self::Authentication(err)
+    }
+}
+
+impl From<AccountError> for MultisigError {
+    fn from(err: AccountError) -> Self {
+        Self::Account(err)
+    }
+}
+
+/// The signers of a multisig account and how many of them must approve a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultisigConfig {
+    threshold: usize,
+    signers: Vec<Word>,
+}
+
+impl MultisigConfig {
+    /// Requires `threshold` of the Falcon public keys in `signers` to sign every transaction.
+    ///
+    /// Signers are identified by their position in `signers`, so every key may appear only once.
+    pub fn new(threshold: usize, signers: Vec<Word>) -> Result<Self, MultisigError> {
+        if threshold == 0 || threshold > signers.len() {
+            return Err(MultisigError::InvalidThreshold {
+                threshold,
+                signers: signers.len(),
+            });
+        }
+
+        let mut seen = BTreeSet::new();
+        for pub_key in &signers {
+            if !seen.insert(Digest::from(*pub_key)) {
+                return Err(MultisigError::DuplicateSigner(*pub_key));
+            }
+        }
+
+        Ok(Self { threshold, signers })
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    pub fn signers(&self) -> &[Word] {
+        &self.signers
+    }
+
+    /// The component's storage: the threshold, then the map from signer index to public key.
+    pub fn storage_slots(&self) -> Vec<StorageSlot> {
+        let signers = StorageMap::with_entries(
+            self.signers
+                .iter()
+                .enumerate()
+                .map(|(index, pub_key)| (signer_key(index).into(), *pub_key)),
+        )
+        .expect("signer indices are unique");
+
+        vec![
+            StorageSlot::Value([Felt::new(self.threshold as u64), ZERO, ZERO, ZERO]),
+            StorageSlot::Map(signers),
+        ]
+    }
+
+    /// Compiles the multisig authentication component for these signers.
+    pub fn component(&self) -> Result<AccountComponent, MultisigError> {
+        Ok(AccountComponent::compile(
+            MULTISIG_AUTH_CODE,
+            TransactionKernel::assembler(),
+            self.storage_slots(),
+        )?
+        .with_supports_all_types())
+    }
+
+    /// The advice that approves the transaction described by `summary` with `signatures`, given
+    /// as signer index and signature.
+    ///
+    /// The signatures are passed on in the given order. The component only accepts signers in
+    /// increasing index order, each of them once.
+    pub fn approval_advice(
+        &self,
+        summary: &TransactionSummary,
+        signatures: Vec<(usize, Vec<Felt>)>,
+    ) -> Vec<(Digest, Vec<Felt>)> {
+        let message = summary.message();
+        let indices = signatures
+            .iter()
+            .map(|(index, _)| Felt::new(*index as u64))
+            .collect();
+
+        let mut advice = vec![(Digest::from(message), indices)];
+        advice.extend(signatures.into_iter().map(|(index, signature)| {
+            let pub_key = self.signers.get(index).copied().unwrap_or_default();
+            (Hasher::merge(&[pub_key.into(), message.into()]), signature)
+        }));
+        advice
+    }
+}
+
+/// What the signers of a multisig account approve: the account, its nonce before the
+/// transaction and the notes the transaction consumes and creates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransactionSummary {
+    pub account_id: AccountId,
+    pub nonce: Felt,
+    pub input_notes_commitment: Digest,
+    pub output_notes_commitment: Digest,
+}
+
+impl TransactionSummary {
+    pub fn new(
+        account_id: AccountId,
+        nonce: Felt,
+        input_notes: &InputNotes<InputNote>,
+        output_notes: &OutputNotes,
+    ) -> Self {
+        Self {
+            account_id,
+            nonce,
+            input_notes_commitment: input_notes.commitment(),
+            output_notes_commitment: output_notes.commitment(),
+        }
+    }
+
+    /// The message every signer signs, computed the same way as the multisig component.
+    pub fn message(&self) -> Word {
+        let account: Word = [
+            self.account_id.suffix(),
+            self.account_id.prefix().as_felt(),
+            ZERO,
+            ZERO,
+        ];
+        let nonce: Word = [self.nonce, ZERO, ZERO, ZERO];
+
+        let account_and_nonce = Hasher::merge(&[nonce.into(), account.into()]);
+        let with_input_notes = Hasher::merge(&[self.input_notes_commitment, account_and_nonce]);
+        Hasher::merge(&[self.output_notes_commitment, with_input_notes]).into()
+    }
+}
+
+/// Collects the signatures of a multisig account's signers from the keystores holding their
+/// keys.
+pub struct SigningCoordinator {
+    config: MultisigConfig,
+    keystores: Vec<FilesystemKeyStore<StdRng>>,
+}
+
+impl SigningCoordinator {
+    pub fn new(config: MultisigConfig) -> Self {
+        Self {
+            config,
+            keystores: Vec::new(),
+        }
+    }
+
+    /// Adds a keystore holding the keys of one or more signers.
+    pub fn with_keystore(mut self, keystore: FilesystemKeyStore<StdRng>) -> Self {
+        self.keystores.push(keystore);
+        self
+    }
+
+    pub fn config(&self) -> &MultisigConfig {
+        &self.config
+    }
+
+    /// Signs `summary` as the signer at `index`, or returns `None` if no keystore holds its key.
+    pub fn sign(
+        &self,
+        summary: &TransactionSummary,
+        index: usize,
+    ) -> Result<Option<Vec<Felt>>, MultisigError> {
+        let Some(&pub_key) = self.config.signers.get(index) else {
+            return Ok(None);
+        };
+
+        for keystore in &self.keystores {
+            if keystore.get_key(pub_key)?.is_some() {
+                let signature =
+                    keystore.get_signature(pub_key, summary.message(), &no_changes(summary))?;
+                return Ok(Some(signature));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Signs `summary` with the first signers whose keys are available until the threshold is
+    /// met, and returns the advice approving the transaction.
+    pub fn collect(
+        &self,
+        summary: &TransactionSummary,
+    ) -> Result<Vec<(Digest, Vec<Felt>)>, MultisigError> {
+        let mut signatures = Vec::new();
+        for index in 0..self.config.signers.len() {
+            if signatures.len() == self.config.threshold {
+                break;
+            }
+            if let Some(signature) = self.sign(summary, index)? {
+                signatures.push((index, signature));
+            }
+        }
+
+        if signatures.len() < self.config.threshold {
+            return Err(MultisigError::ThresholdNotMet {
+                threshold: self.config.threshold,
+                collected: signatures.len(),
+            });
+        }
+        Ok(self.config.approval_advice(summary, signatures))
+    }
+}
+
+/// The storage map key of the signer at `index`.
+fn signer_key(index: usize) -> Word {
+    [Felt::new(index as u64), ZERO, ZERO, ZERO]
+}
+
+/// Keystores inspect the account delta before signing; signers approve the summary alone.
+fn no_changes(summary: &TransactionSummary) -> AccountDelta {
+    AccountDelta::new(
+        summary.account_id,
+        AccountStorageDelta::default(),
+        AccountVaultDelta::default(),
+        ZERO,
+    )
+    .expect("an empty delta does not need a nonce increment")
+}
@@ -0,0 +1,280 @@
+//! A non-fungible faucet minting NFTs with metadata commitments.
+//!
+//! The faucet is a non-fungible faucet account with the `masm/accounts/nft_faucet.masm`
+//! component. Every NFT is a [`NonFungibleAsset`] derived from the faucet and a token id. The
+//! metadata describing it lives off chain as [`NftMetadata`], and the faucet stores a commitment
+//! to it in a map keyed by the asset when it mints the NFT.
+//!
+//! Minted NFTs are sent in P2ID notes created by the faucet. Once in a wallet, an NFT moves like
+//! any other asset, and [`owned_nfts`] lists the NFTs in an account's vault.
+
+use std::fmt;
+
+use miden_client::{
+    account::{
+        component::RpoFalcon512, Account, AccountBuilder, AccountId, AccountStorageMode,
+        AccountType, StorageMap, StorageSlot,
+    },
+    asset::{Asset, NonFungibleAsset},
+    note::{
+        build_p2id_recipient, Note, NoteAssets, NoteExecutionHint, NoteMetadata, NoteRecipient,
+        NoteTag, NoteType,
+    },
+    transaction::{
+        TransactionKernel, TransactionRequest, TransactionRequestBuilder, TransactionRequestError,
+        TransactionScript,
+    },
+    Word, ZERO,
+};
+use miden_client_tools::create_library;
+use miden_objects::{
+    account::AccountComponent, asset::NonFungibleAssetDetails,
+    crypto::dsa::rpo_falcon512::PublicKey, AccountError, AssetError, Hasher, NoteError,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::notes::word_to_masm;
+
+/// The NFT faucet component's MASM source.
+pub const NFT_FAUCET_CODE: &str = include_str!("../../masm/accounts/nft_faucet.masm");
+
+/// The library path transaction scripts use to call the NFT faucet procedures.
+pub const NFT_FAUCET_LIBRARY_PATH: &str = "nft::nft_faucet";
+
+/// Account storage slot of the metadata map. Slot 0 is the kernel's record of issued NFTs, and
+/// slot 1 holds the public key of the Falcon authentication component.
+const METADATA_SLOT: u8 = 2;
+
+/// Errors that can occur when deploying an NFT faucet or minting NFTs.
+#[derive(Debug)]
+pub enum NftError {
+    Assembly(String),
+    Account(AccountError),
+    Asset(AssetError),
+    Note(NoteError),
+    Request(TransactionRequestError),
+}
+
+impl fmt::Display for NftError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Assembly(err) => write!(f, "failed to assemble NFT faucet code: {err}"),
+            Self::Account(err) => write!(f, "failed to build NFT faucet: {err}"),
+            Self::Asset(err) => write!(f, "invalid NFT: {err}"),
+            Self::Note(err) => write!(f, "failed to build NFT note: {err}"),
+            Self::Request(err) => write!(f, "failed to build mint transaction: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for NftError {}
+
+impl From<AccountError> for NftError {
+    fn from(err: AccountError) -> Self {
+        Self::Account(err)
+    }
+}
+
+impl From<AssetError> for NftError {
+    fn from(err: AssetError) -> Self {
+        Self::Asset(err)
+    }
+}
+
+impl From<NoteError> for NftError {
+    fn from(err: NoteError) -> Self {
+        Self::Note(err)
+    }
+}
+
+impl From<TransactionRequestError> for NftError {
+    fn from(err: TransactionRequestError) -> Self {
+        Self::Request(err)
+    }
+}
+
+/// The off-chain description of an NFT.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NftMetadata {
+    pub name: String,
+    pub description: String,
+    /// Where the NFT's content is found, e.g. an `ipfs://` URI.
+    pub uri: String,
+}
+
+impl NftMetadata {
+    pub fn new(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        uri: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            uri: uri.into(),
+        }
+    }
+
+    /// The commitment the faucet stores: the hash of the metadata's JSON encoding.
+    pub fn commitment(&self) -> Word {
+        let json = serde_json::to_vec(self).expect("metadata serializes to JSON");
+        Hasher::hash(&json).into()
+    }
+}
+
+/// Compiles the NFT faucet component, starting with an empty metadata map.
+pub fn nft_faucet_component() -> Result<AccountComponent, NftError> {
+    Ok(AccountComponent::compile(
+        NFT_FAUCET_CODE,
+        TransactionKernel::assembler(),
+        vec![StorageSlot::Map(StorageMap::new())],
+    )?
+    .with_supported_type(AccountType::NonFungibleFaucet))
+}
+
+/// A builder for a public NFT faucet, authenticated by `public_key`.
+pub fn nft_faucet_builder(
+    init_seed: [u8; 32],
+    public_key: PublicKey,
+) -> Result<AccountBuilder, NftError> {
+    Ok(AccountBuilder::new(init_seed)
+        .account_type(AccountType::NonFungibleFaucet)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(RpoFalcon512::new(public_key))
+        .with_component(nft_faucet_component()?))
+}
+
+/// Builds a new NFT faucet, returning it with its seed.
+pub fn build_nft_faucet(
+    init_seed: [u8; 32],
+    public_key: PublicKey,
+) -> Result<(Account, Word), NftError> {
+    Ok(nft_faucet_builder(init_seed, public_key)?.build()?)
+}
+
+/// The NFT with `token_id` in the collection of the faucet `faucet_id`.
+pub fn nft_asset(faucet_id: AccountId, token_id: u64) -> Result<NonFungibleAsset, NftError> {
+    let details =
+        NonFungibleAssetDetails::new(faucet_id.prefix(), token_id.to_le_bytes().to_vec())?;
+    Ok(NonFungibleAsset::new(&details)?)
+}
+
+/// One NFT to mint and the P2ID note it is sent to `target` in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NftMint {
+    pub asset: NonFungibleAsset,
+    pub metadata: NftMetadata,
+    pub target: AccountId,
+    pub serial_num: Word,
+    pub note_type: NoteType,
+}
+
+impl NftMint {
+    /// Mints `asset` to `target` in a public note.
+    pub fn new(
+        asset: NonFungibleAsset,
+        metadata: NftMetadata,
+        target: AccountId,
+        serial_num: Word,
+    ) -> Self {
+        Self {
+            asset,
+            metadata,
+            target,
+            serial_num,
+            note_type: NoteType::Public,
+        }
+    }
+
+    pub fn with_note_type(mut self, note_type: NoteType) -> Self {
+        self.note_type = note_type;
+        self
+    }
+
+    /// The note the faucet `faucet_id` creates for this mint.
+    pub fn note(&self, faucet_id: AccountId) -> Result<Note, NoteError> {
+        let metadata = NoteMetadata::new(
+            faucet_id,
+            self.note_type,
+            NoteTag::from_account_id(self.target),
+            NoteExecutionHint::always(),
+            ZERO,
+        )?;
+        Ok(Note::new(
+            NoteAssets::new(vec![self.asset.into()])?,
+            metadata,
+            self.recipient()?,
+        ))
+    }
+
+    /// The `[execution_hint, note_type, aux, tag]` word of the note.
+    fn note_config(&self) -> Word {
+        [
+            NoteExecutionHint::always().into(),
+            self.note_type.into(),
+            ZERO,
+            NoteTag::from_account_id(self.target).into(),
+        ]
+    }
+
+    fn recipient(&self) -> Result<NoteRecipient, NoteError> {
+        build_p2id_recipient(self.target, self.serial_num)
+    }
+}
+
+/// The transaction minting `mints`, to be executed against the NFT faucet.
+pub fn mint_request(mints: &[NftMint]) -> Result<TransactionRequest, NftError> {
+    let mut script = String::from("use.nft::nft_faucet\n\nbegin\n");
+    for mint in mints {
+        script.push_str(&format!(
+            "    push.{}\n    push.{}\n    push.{}\n    push.{}\n    call.nft_faucet::mint\n    dropw dropw dropw dropw\n",
+            word_to_masm(mint.recipient()?.digest().into()),
+            word_to_masm(mint.note_config()),
+            word_to_masm(mint.metadata.commitment()),
+            word_to_masm(mint.asset.into()),
+        ));
+    }
+    script.push_str("end\n");
+
+    let library = create_library(NFT_FAUCET_CODE.to_string(), NFT_FAUCET_LIBRARY_PATH)
+        .map_err(|err| NftError::Assembly(err.to_string()))?;
+    let assembler = TransactionKernel::assembler()
+        .with_library(&library)
+        .map_err(|err| NftError::Assembly(err.to_string()))?;
+    let tx_script = TransactionScript::compile(script, assembler)
+        .map_err(|err| NftError::Assembly(err.to_string()))?;
+
+    let recipients = mints
+        .iter()
+        .map(NftMint::recipient)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(TransactionRequestBuilder::new()
+        .custom_script(tx_script)
+        .expected_output_recipients(recipients)
+        .build()?)
+}
+
+/// The NFTs in the vault of `account`.
+pub fn owned_nfts(account: &Account) -> Vec<NonFungibleAsset> {
+    account
+        .vault()
+        .assets()
+        .filter_map(|asset| match asset {
+            Asset::NonFungible(nft) => Some(nft),
+            Asset::Fungible(_) => None,
+        })
+        .collect()
+}
+
+/// The metadata commitment the NFT faucet `faucet` stored when it minted `asset`, or `None` if it
+/// has not minted it.
+pub fn metadata_commitment(
+    faucet: &Account,
+    asset: &NonFungibleAsset,
+) -> Result<Option<Word>, NftError> {
+    let commitment = faucet
+        .storage()
+        .get_map_item(METADATA_SLOT, Word::from(*asset))?;
+    Ok((commitment != Word::default()).then_some(commitment))
+}
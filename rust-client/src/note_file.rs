@@ -0,0 +1,225 @@
+//! A versioned file format for handing notes to counterparties out of band.
+//!
+//! Private notes never reach the chain in full, so the receiver needs the note details from the
+//! sender, for example over email or chat. A note file carries the note and an optional note
+//! argument, such as the secret of a hash-preimage note.
+//!
+//! The binary encoding is `MAGIC | version | payload | checksum`, where the checksum is the RPO
+//! hash of everything before it. The text encoding wraps the binary encoding in a bech32-style
+//! string with the `mnote` prefix, so it survives being pasted into a message.
+
+use std::{fmt, fs, io, path::Path};
+
+use bech32::{primitives::decode::CheckedHrpstring, Hrp, NoChecksum};
+use miden_client::{
+    note::{Note, NoteFile, NoteId},
+    Client, ClientError, Word,
+};
+use miden_objects::{
+    block::BlockNumber,
+    note::NoteDetails,
+    utils::{ByteReader, Deserializable, DeserializationError, Serializable, SliceReader},
+    Hasher,
+};
+
+/// Magic bytes at the start of every binary note file.
+const MAGIC: &[u8; 4] = b"MNTF";
+
+/// Current version of the note file format.
+pub const VERSION: u8 = 1;
+
+/// Human-readable prefix of the text encoding.
+pub const HRP: &str = "mnote";
+
+const CHECKSUM_LEN: usize = 32;
+
+/// How a note file is written to disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoteFileFormat {
+    Binary,
+    Text,
+}
+
+/// Errors that can occur when reading a note file.
+#[derive(Debug)]
+pub enum NoteFileError {
+    Io(io::Error),
+    InvalidMagic,
+    /// The file ends before the header and checksum.
+    Truncated,
+    UnsupportedVersion(u8),
+    ChecksumMismatch,
+    InvalidText(String),
+    Deserialization(DeserializationError),
+}
+
+impl fmt::Display for NoteFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to access note file: {err}"),
+            Self::InvalidMagic => write!(f, "not a note file"),
+            Self::Truncated => write!(f, "note file is truncated"),
+            Self::UnsupportedVersion(version) => {
+                write!(
+                    f,
+                    "unsupported note file version {version}, expected {VERSION}"
+                )
+            }
+            Self::ChecksumMismatch => write!(f, "note file checksum does not match its contents"),
+            Self::InvalidText(err) => write!(f, "invalid note file text encoding: {err}"),
+            Self::Deserialization(err) => write!(f, "failed to deserialize note file: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for NoteFileError {}
+
+impl From<io::Error> for NoteFileError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<DeserializationError> for NoteFileError {
+    fn from(err: DeserializationError) -> Self {
+        Self::Deserialization(err)
+    }
+}
+
+/// A note together with the note argument the receiver has to provide when consuming it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoteExport {
+    pub note: Note,
+    pub note_args: Option<Word>,
+}
+
+impl NoteExport {
+    pub fn new(note: Note) -> Self {
+        Self {
+            note,
+            note_args: None,
+        }
+    }
+
+    /// Attaches the note argument, e.g. the secret of a hash-preimage note.
+    pub fn with_note_args(mut self, note_args: Word) -> Self {
+        self.note_args = Some(note_args);
+        self
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION);
+        self.note.write_into(&mut bytes);
+        self.note_args.write_into(&mut bytes);
+
+        let checksum = Hasher::hash(&bytes);
+        bytes.extend_from_slice(&checksum.as_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, NoteFileError> {
+        let magic_len = bytes.len().min(MAGIC.len());
+        if bytes[..magic_len] != MAGIC[..magic_len] {
+            return Err(NoteFileError::InvalidMagic);
+        }
+        let Some(&version) = bytes.get(MAGIC.len()) else {
+            return Err(NoteFileError::Truncated);
+        };
+        if version != VERSION {
+            return Err(NoteFileError::UnsupportedVersion(version));
+        }
+        if bytes.len() < MAGIC.len() + 1 + CHECKSUM_LEN {
+            return Err(NoteFileError::Truncated);
+        }
+
+        let (contents, checksum) = bytes.split_at(bytes.len() - CHECKSUM_LEN);
+        if Hasher::hash(contents).as_bytes() != checksum {
+            return Err(NoteFileError::ChecksumMismatch);
+        }
+
+        let mut reader = SliceReader::new(&contents[MAGIC.len() + 1..]);
+        let note = Note::read_from(&mut reader)?;
+        let note_args = Option::<Word>::read_from(&mut reader)?;
+        if reader.has_more_bytes() {
+            return Err(DeserializationError::UnconsumedBytes.into());
+        }
+
+        Ok(Self { note, note_args })
+    }
+
+    /// Encodes the note file as an `mnote1...` string.
+    pub fn to_text(&self) -> String {
+        let hrp = Hrp::parse(HRP).expect("note file prefix is a valid human-readable part");
+        // The binary encoding carries its own checksum, which is not limited in length like the
+        // bech32 checksums are.
+        bech32::encode::<NoChecksum>(hrp, &self.to_bytes())
+            .expect("encoding without a checksum has no length limit")
+    }
+
+    pub fn from_text(text: &str) -> Result<Self, NoteFileError> {
+        let parsed = CheckedHrpstring::new::<NoChecksum>(text.trim())
+            .map_err(|err| NoteFileError::InvalidText(err.to_string()))?;
+        if parsed.hrp().as_str() != HRP {
+            return Err(NoteFileError::InvalidText(format!(
+                "expected prefix {HRP}, found {}",
+                parsed.hrp()
+            )));
+        }
+
+        Self::from_bytes(&parsed.byte_iter().collect::<Vec<_>>())
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>, format: NoteFileFormat) -> io::Result<()> {
+        match format {
+            NoteFileFormat::Binary => fs::write(path, self.to_bytes()),
+            NoteFileFormat::Text => fs::write(path, self.to_text()),
+        }
+    }
+
+    /// Reads a note file in either encoding.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, NoteFileError> {
+        let bytes = fs::read(path)?;
+        // Anything that does not look like the text encoding is read as binary, so a truncated
+        // binary file is reported as such
+        let prefix = bytes.trim_ascii_start().get(..HRP.len());
+        if !prefix.is_some_and(|prefix| prefix.eq_ignore_ascii_case(HRP.as_bytes())) {
+            return Self::from_bytes(&bytes);
+        }
+
+        let text = String::from_utf8(bytes)
+            .map_err(|_| NoteFileError::InvalidText("file is neither binary nor text".into()))?;
+        Self::from_text(&text)
+    }
+
+    /// Registers the note with `client`, which then looks for it on chain from
+    /// `after_block_num` on when syncing.
+    pub async fn import(
+        &self,
+        client: &mut Client,
+        after_block_num: BlockNumber,
+    ) -> Result<NoteId, ClientError> {
+        client
+            .import_note(NoteFile::NoteDetails {
+                details: NoteDetails::from(self.note.clone()),
+                after_block_num,
+                tag: Some(self.note.metadata().tag()),
+            })
+            .await
+    }
+}
+
+/// Looks up a note `client` created or received, with all the details needed to export it.
+///
+/// Returns `None` if the client does not know the note, or only knows its commitment.
+pub async fn find_note(client: &Client, note_id: NoteId) -> Result<Option<Note>, ClientError> {
+    if let Some(record) = client.get_output_note(note_id).await? {
+        if let Ok(note) = Note::try_from(record) {
+            return Ok(Some(note));
+        }
+    }
+    Ok(client
+        .get_input_note(note_id)
+        .await?
+        .and_then(|record| record.try_into().ok()))
+}
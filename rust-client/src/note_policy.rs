@@ -0,0 +1,189 @@
+//! Chooses the [`NoteType`] of the notes a flow creates and delivers private notes.
+//!
+//! Public notes are stored on chain in full, so the receiver finds them by syncing. Private notes
+//! only put their commitment on chain, and the receiver needs the note details from the sender.
+//! [`NoteDelivery`] either exports them as a note file or registers them directly with the
+//! receiver's client.
+
+use std::{collections::BTreeMap, env, fmt, fs, io, path::Path};
+
+use miden_client::{
+    account::AccountId,
+    note::{Note, NoteId, NoteType},
+    Client, ClientError,
+};
+use miden_objects::block::BlockNumber;
+use serde::Deserialize;
+
+use crate::{
+    address_book::AddressBook,
+    note_file::{NoteExport, NoteFileFormat},
+};
+
+/// Environment variable holding the path of a JSON note policy file.
+pub const NOTE_POLICY_ENV: &str = "MIDEN_NOTE_POLICY";
+
+/// Environment variable holding the default note type, `public` or `private`.
+pub const NOTE_TYPE_ENV: &str = "MIDEN_NOTE_TYPE";
+
+/// Errors that can occur when loading a note policy or delivering a note.
+#[derive(Debug)]
+pub enum NotePolicyError {
+    Io(io::Error),
+    InvalidConfig(String),
+    Client(Box<ClientError>),
+}
+
+impl fmt::Display for NotePolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to access note file: {err}"),
+            Self::InvalidConfig(err) => write!(f, "invalid note policy: {err}"),
+            Self::Client(err) => write!(f, "failed to import note: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for NotePolicyError {}
+
+impl From<io::Error> for NotePolicyError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<ClientError> for NotePolicyError {
+    fn from(err: ClientError) -> Self {
+        Self::Client(Box::new(err))
+    }
+}
+
+/// Parses `public` or `private`, ignoring case.
+pub fn parse_note_type(value: &str) -> Result<NoteType, NotePolicyError> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "public" => Ok(NoteType::Public),
+        "private" => Ok(NoteType::Private),
+        other => Err(NotePolicyError::InvalidConfig(format!(
+            "unknown note type {other}, expected public or private"
+        ))),
+    }
+}
+
+/// The JSON layout of a note policy file. Recipients are address book names or account ids.
+///
+/// ```json
+/// { "default": "private", "recipients": { "bob": "public", "mtst1...": "public" } }
+/// ```
+#[derive(Debug, Deserialize)]
+struct PolicyFile {
+    default: String,
+    #[serde(default)]
+    recipients: BTreeMap<String, String>,
+}
+
+/// The note type to use for every recipient, with optional per-recipient overrides.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotePolicy {
+    default: NoteType,
+    recipients: BTreeMap<AccountId, NoteType>,
+}
+
+impl Default for NotePolicy {
+    /// Public notes for everyone, which is what the tutorials use unless configured otherwise.
+    fn default() -> Self {
+        Self::new(NoteType::Public)
+    }
+}
+
+impl NotePolicy {
+    pub fn new(default: NoteType) -> Self {
+        Self {
+            default,
+            recipients: BTreeMap::new(),
+        }
+    }
+
+    /// Overrides the note type for notes sent to `recipient`.
+    pub fn with_recipient(mut self, recipient: AccountId, note_type: NoteType) -> Self {
+        self.recipients.insert(recipient, note_type);
+        self
+    }
+
+    pub fn note_type_for(&self, recipient: AccountId) -> NoteType {
+        self.recipients
+            .get(&recipient)
+            .copied()
+            .unwrap_or(self.default)
+    }
+
+    /// Loads a policy from a JSON file, see [`NOTE_POLICY_ENV`] for the layout.
+    ///
+    /// Recipients are resolved through `address_book`, so names work and account ids of another
+    /// network are rejected.
+    pub fn from_file(
+        path: impl AsRef<Path>,
+        address_book: &AddressBook,
+    ) -> Result<Self, NotePolicyError> {
+        let file: PolicyFile = serde_json::from_slice(&fs::read(path)?)
+            .map_err(|err| NotePolicyError::InvalidConfig(err.to_string()))?;
+
+        let mut policy = Self::new(parse_note_type(&file.default)?);
+        for (recipient, note_type) in file.recipients {
+            let account_id = address_book.resolve(&recipient).map_err(|err| {
+                NotePolicyError::InvalidConfig(format!("invalid recipient {recipient}: {err}"))
+            })?;
+            policy = policy.with_recipient(account_id, parse_note_type(&note_type)?);
+        }
+        Ok(policy)
+    }
+
+    /// Loads the policy file named by `MIDEN_NOTE_POLICY` if set, otherwise uses the note type in
+    /// `MIDEN_NOTE_TYPE` for everyone. Falls back to public notes.
+    pub fn from_env(address_book: &AddressBook) -> Result<Self, NotePolicyError> {
+        if let Ok(path) = env::var(NOTE_POLICY_ENV) {
+            return Self::from_file(path, address_book);
+        }
+        match env::var(NOTE_TYPE_ENV) {
+            Ok(note_type) => Ok(Self::new(parse_note_type(&note_type)?)),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+}
+
+/// How the details of a private note reach its receiver.
+pub enum NoteDelivery<'a> {
+    /// Writes `<note_id>.mnote` into the directory, to be handed over out of band.
+    Export(&'a Path),
+    /// Registers the note with the receiver's client, which then finds it on chain when syncing.
+    Import {
+        receiver: &'a mut Client,
+        /// The sender's chain tip when the note was created.
+        after_block_num: BlockNumber,
+    },
+}
+
+impl NoteDelivery<'_> {
+    /// Delivers `note` if it is private and returns its id. Public notes need no delivery.
+    pub async fn deliver(self, note: &Note) -> Result<Option<NoteId>, NotePolicyError> {
+        if note.metadata().note_type() != NoteType::Private {
+            return Ok(None);
+        }
+
+        match self {
+            Self::Export(dir) => {
+                fs::create_dir_all(dir)?;
+                let path = dir.join(format!("{}.mnote", note.id().to_hex()));
+                NoteExport::new(note.clone()).write(path, NoteFileFormat::Binary)?;
+            }
+            Self::Import {
+                receiver,
+                after_block_num,
+            } => {
+                NoteExport::new(note.clone())
+                    .import(receiver, after_block_num)
+                    .await?;
+            }
+        }
+        Ok(Some(note.id()))
+    }
+}
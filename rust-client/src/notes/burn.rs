@@ -0,0 +1,64 @@
+use miden_client::{
+    account::AccountId,
+    asset::FungibleAsset,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+        NoteTag, NoteType,
+    },
+    Felt, Word,
+};
+use miden_objects::NoteError;
+
+use super::compile_note_script;
+
+const BURN_NOTE: &str = include_str!("../../../masm/notes/burn_note.masm");
+
+/// A note returning tokens to the faucet that issued them.
+///
+/// The faucet burns the tokens when it consumes the note, which reduces its issued supply by the
+/// same amount. No other account can consume the note.
+#[derive(Debug, Clone)]
+pub struct BurnNote {
+    asset: FungibleAsset,
+}
+
+impl BurnNote {
+    pub fn new(asset: FungibleAsset) -> Self {
+        Self { asset }
+    }
+
+    /// Compiles `burn_note.masm`.
+    pub fn script() -> Result<NoteScript, NoteError> {
+        compile_note_script(BURN_NOTE)
+    }
+
+    pub fn asset(&self) -> FungibleAsset {
+        self.asset
+    }
+
+    /// The faucet that burns the tokens.
+    pub fn faucet_id(&self) -> AccountId {
+        self.asset.faucet_id()
+    }
+
+    /// Builds the note sent by `sender`.
+    ///
+    /// The note is tagged for the faucet, so it is picked up by the faucet's client.
+    pub fn build(
+        &self,
+        sender: AccountId,
+        note_type: NoteType,
+        serial_num: Word,
+    ) -> Result<Note, NoteError> {
+        let recipient = NoteRecipient::new(serial_num, Self::script()?, NoteInputs::default());
+        let metadata = NoteMetadata::new(
+            sender,
+            note_type,
+            NoteTag::from_account_id(self.faucet_id()),
+            NoteExecutionHint::always(),
+            Felt::new(0),
+        )?;
+        let vault = NoteAssets::new(vec![self.asset.into()])?;
+        Ok(Note::new(vault, metadata, recipient))
+    }
+}
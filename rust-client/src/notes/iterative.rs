@@ -0,0 +1,188 @@
+use miden_client::{
+    account::AccountId,
+    asset::{Asset, FungibleAsset},
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+        NoteTag, NoteType,
+    },
+    Felt, Word,
+};
+use miden_objects::{note::NoteDetails, NoteError};
+
+use super::{compile_note_script, RecursiveNote};
+
+const ITERATIVE_OUTPUT_NOTE: &str = include_str!("../../../masm/notes/iterative_output_note.masm");
+
+/// Number of times the note re-creates itself unless set with
+/// [`IterativeOutputNote::with_depth`].
+pub const DEFAULT_DEPTH: u32 = 8;
+
+/// A note that, when consumed, re-creates itself with half of every fungible asset it held.
+///
+/// The consumer receives all assets first and then moves half of each fungible asset into the
+/// new note, whose serial number is incremented by one. An asset is only split while its half is
+/// non-zero and at least `min_amount`; anything else, including non-fungible assets, is kept by
+/// the consumer. The chain ends once `depth` notes have been created or nothing is left to split,
+/// so the last consumer keeps the remainder.
+#[derive(Debug, Clone)]
+pub struct IterativeOutputNote {
+    creator: AccountId,
+    tag: NoteTag,
+    assets: Vec<Asset>,
+    depth: u32,
+    min_amount: u64,
+}
+
+/// A note the iterative chain will create, as predicted by [`IterativeOutputNote::simulate`].
+#[derive(Debug, Clone)]
+pub struct FutureNote {
+    /// The state of the chain at this note.
+    pub note: IterativeOutputNote,
+    /// Serial number of the note, incremented by one per step.
+    pub serial_num: Word,
+    pub details: NoteDetails,
+}
+
+impl IterativeOutputNote {
+    /// `tag` is stored in the note inputs and reused for every note in the chain, which is
+    /// always public.
+    pub fn new(creator: AccountId, tag: NoteTag, assets: Vec<Asset>) -> Self {
+        Self {
+            creator,
+            tag,
+            assets,
+            depth: DEFAULT_DEPTH,
+            min_amount: 1,
+        }
+    }
+
+    /// Sets how many more notes the chain creates after this one.
+    pub fn with_depth(mut self, depth: u32) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Sets the smallest amount moved into the next note. Halves below it stay with the consumer.
+    pub fn with_min_amount(mut self, min_amount: u64) -> Self {
+        self.min_amount = min_amount;
+        self
+    }
+
+    /// Compiles `iterative_output_note.masm`.
+    pub fn script() -> Result<NoteScript, NoteError> {
+        compile_note_script(ITERATIVE_OUTPUT_NOTE)
+    }
+
+    pub fn tag(&self) -> NoteTag {
+        self.tag
+    }
+
+    pub fn assets(&self) -> &[Asset] {
+        &self.assets
+    }
+
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    pub fn min_amount(&self) -> u64 {
+        self.min_amount
+    }
+
+    /// Note inputs laid out as `[creator_id_prefix, creator_id_suffix, tag, depth, min_amount]`.
+    pub fn inputs(&self) -> Result<NoteInputs, NoteError> {
+        NoteInputs::new(vec![
+            self.creator.prefix().as_felt(),
+            self.creator.suffix(),
+            self.tag.into(),
+            Felt::from(self.depth),
+            Felt::new(self.min_amount),
+        ])
+    }
+
+    pub fn recipient(&self, serial_num: Word) -> Result<NoteRecipient, NoteError> {
+        Ok(NoteRecipient::new(
+            serial_num,
+            Self::script()?,
+            self.inputs()?,
+        ))
+    }
+
+    pub fn details(&self, serial_num: Word) -> Result<NoteDetails, NoteError> {
+        let vault = NoteAssets::new(self.assets.clone())?;
+        Ok(NoteDetails::new(vault, self.recipient(serial_num)?))
+    }
+
+    /// Builds the note sent by `sender`.
+    ///
+    /// For the first note in the chain `sender` is the creator; for every later note it is the
+    /// account that consumed the previous one.
+    pub fn build(&self, sender: AccountId, serial_num: Word) -> Result<Note, NoteError> {
+        let metadata = NoteMetadata::new(
+            sender,
+            NoteType::Public,
+            self.tag,
+            NoteExecutionHint::always(),
+            Felt::new(0),
+        )?;
+        let vault = NoteAssets::new(self.assets.clone())?;
+        Ok(Note::new(vault, metadata, self.recipient(serial_num)?))
+    }
+
+    /// Returns the note created when this one is consumed, or `None` if the chain ends here.
+    ///
+    /// Mirrors the termination rules of the note script.
+    pub fn next(&self) -> Option<Self> {
+        if self.depth == 0 {
+            return None;
+        }
+
+        let assets: Vec<Asset> = self
+            .assets
+            .iter()
+            .filter_map(|asset| match asset {
+                Asset::Fungible(asset) => {
+                    let half = asset.amount() / 2;
+                    (half != 0 && half >= self.min_amount).then(|| {
+                        FungibleAsset::new(asset.faucet_id(), half)
+                            .expect("half of a valid amount is a valid amount")
+                            .into()
+                    })
+                }
+                Asset::NonFungible(_) => None,
+            })
+            .collect();
+        if assets.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            assets,
+            depth: self.depth - 1,
+            ..self.clone()
+        })
+    }
+
+    /// Predicts every note the chain creates after this one, in the order they are created.
+    ///
+    /// `serial_num` is the serial number of this note. The details and tags can be passed to
+    /// `TransactionRequestBuilder::expected_future_notes`.
+    pub fn simulate(&self, serial_num: Word) -> Result<Vec<FutureNote>, NoteError> {
+        let script = Self::script()?;
+        let mut future_notes = Vec::new();
+        let mut current = self.clone();
+        let mut serial_num = serial_num;
+        while let Some(note) = current.next() {
+            let recursive = RecursiveNote::new(script.clone(), note.inputs()?.values().to_vec());
+            let details = recursive.details(serial_num, note.assets.clone())?;
+            serial_num = details.serial_num();
+            future_notes.push(FutureNote {
+                details,
+                note: note.clone(),
+                serial_num,
+            });
+            current = note;
+        }
+        Ok(future_notes)
+    }
+}
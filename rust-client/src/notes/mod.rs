@@ -0,0 +1,31 @@
+//! Builders for the custom notes found in `masm/notes`.
+
+use miden_client::{note::NoteScript, transaction::TransactionKernel, Word};
+use miden_objects::NoteError;
+
+mod burn;
+mod iterative;
+mod preimage;
+mod recursive;
+
+pub use burn::BurnNote;
+pub use iterative::{FutureNote, IterativeOutputNote, DEFAULT_DEPTH};
+pub use preimage::{secret_digest, HashPreimageNote, PreimageNote};
+pub use recursive::{
+    build_recipient_hash, inputs_commitment, pad_inputs, RecursiveNote, SerialTransform,
+};
+
+/// Compiles a note script with the same assembler settings the tutorials use.
+fn compile_note_script(source: &str) -> Result<NoteScript, NoteError> {
+    let assembler = TransactionKernel::assembler().with_debug_mode(true);
+    NoteScript::compile(source, assembler)
+}
+
+/// Formats `word` as the dot-separated elements of a `push` instruction, for scripts that are
+/// generated with constants baked in.
+pub fn word_to_masm(word: Word) -> String {
+    word.iter()
+        .map(|felt| felt.as_int().to_string())
+        .collect::<Vec<_>>()
+        .join(".")
+}
@@ -0,0 +1,145 @@
+use miden_client::{
+    account::AccountId,
+    asset::Asset,
+    crypto::Digest,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+        NoteTag, NoteType,
+    },
+    Felt, Word,
+};
+use miden_objects::{Hasher, NoteError};
+
+use super::compile_note_script;
+
+const HASH_PREIMAGE_NOTE: &str = include_str!("../../../masm/notes/hash_preimage_note.masm");
+const BOUND_HASH_PREIMAGE_NOTE: &str =
+    include_str!("../../../masm/notes/bound_hash_preimage_note.masm");
+
+/// Hashes a secret the same way `hperm` does inside the preimage note scripts.
+///
+/// `hperm` permutes a state whose first rate word is empty, so the secret is prepended with
+/// `Word::default()` before hashing.
+pub fn secret_digest(secret: Word) -> Digest {
+    let mut elements = Word::default().to_vec();
+    elements.extend_from_slice(&secret);
+    Hasher::hash_elements(&elements)
+}
+
+/// A hash-preimage note that anyone who knows the secret can consume.
+///
+/// Every asset in the note, fungible or not, is moved into the consuming account.
+#[derive(Debug, Clone)]
+pub struct HashPreimageNote {
+    secret: Word,
+    assets: Vec<Asset>,
+}
+
+impl HashPreimageNote {
+    pub fn new(secret: Word, assets: Vec<Asset>) -> Self {
+        Self { secret, assets }
+    }
+
+    /// Compiles `hash_preimage_note.masm`.
+    pub fn script() -> Result<NoteScript, NoteError> {
+        compile_note_script(HASH_PREIMAGE_NOTE)
+    }
+
+    /// The secret the consumer passes as note args.
+    pub fn secret(&self) -> Word {
+        self.secret
+    }
+
+    pub fn digest(&self) -> Digest {
+        secret_digest(self.secret)
+    }
+
+    /// Builds the note sent by `sender`.
+    pub fn build(
+        &self,
+        sender: AccountId,
+        note_type: NoteType,
+        serial_num: Word,
+    ) -> Result<Note, NoteError> {
+        let inputs = NoteInputs::new(self.digest().to_vec())?;
+        let recipient = NoteRecipient::new(serial_num, Self::script()?, inputs);
+        let metadata = NoteMetadata::new(
+            sender,
+            note_type,
+            NoteTag::for_local_use_case(0, 0)?,
+            NoteExecutionHint::always(),
+            Felt::new(0),
+        )?;
+        let vault = NoteAssets::new(self.assets.clone())?;
+        Ok(Note::new(vault, metadata, recipient))
+    }
+}
+
+/// A hash-preimage note that can only be consumed by one account.
+///
+/// Knowing the secret is not enough to consume the note: the script also checks that the
+/// native account of the consuming transaction is `consumer`, so a pending transaction which
+/// leaks the secret cannot be front-run.
+#[derive(Debug, Clone)]
+pub struct PreimageNote {
+    secret: Word,
+    consumer: AccountId,
+    assets: Vec<Asset>,
+}
+
+impl PreimageNote {
+    pub fn new(secret: Word, consumer: AccountId, assets: Vec<Asset>) -> Self {
+        Self {
+            secret,
+            consumer,
+            assets,
+        }
+    }
+
+    /// Compiles `bound_hash_preimage_note.masm`.
+    pub fn script() -> Result<NoteScript, NoteError> {
+        compile_note_script(BOUND_HASH_PREIMAGE_NOTE)
+    }
+
+    /// The secret the consumer passes as note args.
+    pub fn secret(&self) -> Word {
+        self.secret
+    }
+
+    pub fn consumer(&self) -> AccountId {
+        self.consumer
+    }
+
+    pub fn digest(&self) -> Digest {
+        secret_digest(self.secret)
+    }
+
+    /// Note inputs laid out as `[DIGEST, consumer_id_suffix, consumer_id_prefix]`.
+    pub fn inputs(&self) -> Result<NoteInputs, NoteError> {
+        let mut values = self.digest().to_vec();
+        values.extend([self.consumer.suffix(), self.consumer.prefix().as_felt()]);
+        NoteInputs::new(values)
+    }
+
+    /// Builds the note sent by `sender`.
+    ///
+    /// The note is tagged for the consumer account, so it is picked up by the consumer's client
+    /// regardless of `note_type`.
+    pub fn build(
+        &self,
+        sender: AccountId,
+        note_type: NoteType,
+        serial_num: Word,
+    ) -> Result<Note, NoteError> {
+        let recipient = NoteRecipient::new(serial_num, Self::script()?, self.inputs()?);
+        let metadata = NoteMetadata::new(
+            sender,
+            note_type,
+            NoteTag::from_account_id(self.consumer),
+            NoteExecutionHint::always(),
+            Felt::new(0),
+        )?;
+        let vault = NoteAssets::new(self.assets.clone())?;
+        Ok(Note::new(vault, metadata, recipient))
+    }
+}
@@ -0,0 +1,124 @@
+use miden_client::{
+    asset::Asset,
+    crypto::Digest,
+    note::{NoteAssets, NoteInputs, NoteRecipient, NoteScript},
+    Felt, Word,
+};
+use miden_objects::{note::NoteDetails, Hasher, NoteError};
+
+/// How a note script derives the serial number of a note it creates from its own serial number.
+#[derive(Debug, Clone, Copy)]
+pub enum SerialTransform {
+    /// Reuses the serial number unchanged.
+    Keep,
+    /// Adds one to the last element, like `exec.note::get_serial_number push.1 add`.
+    Increment,
+    /// Any other derivation the script implements.
+    Custom(fn(Word) -> Word),
+}
+
+impl SerialTransform {
+    pub fn apply(self, serial_num: Word) -> Word {
+        match self {
+            Self::Keep => serial_num,
+            Self::Increment => [
+                serial_num[0],
+                serial_num[1],
+                serial_num[2],
+                serial_num[3] + Felt::new(1),
+            ],
+            Self::Custom(transform) => transform(serial_num),
+        }
+    }
+}
+
+/// Pads note inputs with zeros to a multiple of 8 elements.
+///
+/// `rpo::hash_memory` hashes exactly the number of elements it is given, so the note scripts
+/// hash the zero-padded memory region to match the commitment of [`NoteInputs`].
+pub fn pad_inputs(inputs: &[Felt]) -> Vec<Felt> {
+    let mut padded = inputs.to_vec();
+    padded.resize(inputs.len().next_multiple_of(8), Felt::new(0));
+    padded
+}
+
+/// Computes the inputs commitment a note script gets from `rpo::hash_memory` over the padded
+/// inputs.
+pub fn inputs_commitment(inputs: &[Felt]) -> Digest {
+    Hasher::hash_elements(&pad_inputs(inputs))
+}
+
+/// Mirrors `tx::build_recipient_hash`:
+/// `hash(hash(hash(serial_num, [0; 4]), script_root), inputs_commitment)`.
+pub fn build_recipient_hash(
+    serial_num: Word,
+    script_root: Digest,
+    inputs_commitment: Digest,
+) -> Digest {
+    let serial_num_hash = Hasher::merge(&[serial_num.into(), Digest::default()]);
+    let merge_script = Hasher::merge(&[serial_num_hash, script_root]);
+    Hasher::merge(&[merge_script, inputs_commitment])
+}
+
+/// Describes a note that a note script creates while it is consumed.
+///
+/// Given the serial number of the note being consumed, it derives the recipient and details of
+/// the created note, so they can be passed to `expected_future_notes` and
+/// `expected_output_recipients` instead of being rebuilt by hand.
+#[derive(Debug, Clone)]
+pub struct RecursiveNote {
+    script: NoteScript,
+    inputs: Vec<Felt>,
+    serial_transform: SerialTransform,
+}
+
+impl RecursiveNote {
+    /// `script` and `inputs` are those of the created note. The serial number is incremented by
+    /// default.
+    pub fn new(script: NoteScript, inputs: Vec<Felt>) -> Self {
+        Self {
+            script,
+            inputs,
+            serial_transform: SerialTransform::Increment,
+        }
+    }
+
+    pub fn with_serial_transform(mut self, serial_transform: SerialTransform) -> Self {
+        self.serial_transform = serial_transform;
+        self
+    }
+
+    /// The serial number of the created note.
+    pub fn serial_num(&self, parent_serial_num: Word) -> Word {
+        self.serial_transform.apply(parent_serial_num)
+    }
+
+    /// The recipient hash the script computes with `tx::build_recipient_hash`.
+    pub fn recipient_digest(&self, parent_serial_num: Word) -> Digest {
+        build_recipient_hash(
+            self.serial_num(parent_serial_num),
+            self.script.root(),
+            inputs_commitment(&self.inputs),
+        )
+    }
+
+    pub fn recipient(&self, parent_serial_num: Word) -> Result<NoteRecipient, NoteError> {
+        Ok(NoteRecipient::new(
+            self.serial_num(parent_serial_num),
+            self.script.clone(),
+            NoteInputs::new(self.inputs.clone())?,
+        ))
+    }
+
+    /// The details of the created note, which holds `assets`.
+    pub fn details(
+        &self,
+        parent_serial_num: Word,
+        assets: Vec<Asset>,
+    ) -> Result<NoteDetails, NoteError> {
+        Ok(NoteDetails::new(
+            NoteAssets::new(assets)?,
+            self.recipient(parent_serial_num)?,
+        ))
+    }
+}
@@ -0,0 +1,299 @@
+//! Batch payouts read from a CSV file.
+//!
+//! Every CSV row pays `amount` tokens of `faucet` to `recipient`, both given as bech32 account
+//! ids or as names from the address book:
+//!
+//! ```text
+//! recipient,faucet,amount
+//! mtst1qz...,mtst1qr...,100
+//! alice,mid-faucet,250
+//! ```
+//!
+//! The header row is optional and lines starting with `#` are ignored. Each payout becomes one
+//! P2ID note, and the notes are sent in as few transactions as the per-transaction output note
+//! limit allows.
+
+use std::{collections::BTreeMap, fmt, fs, io, path::Path, sync::Arc};
+
+use miden_client::{
+    account::AccountId,
+    asset::FungibleAsset,
+    note::{create_p2id_note, Note},
+    transaction::{OutputNote, TransactionId, TransactionProver, TransactionRequestBuilder},
+    Client, ClientError, Felt,
+};
+use miden_objects::{account::NetworkId, MAX_OUTPUT_NOTES_PER_TX};
+use serde::Serialize;
+
+use crate::{address_book::AddressBook, note_policy::NotePolicy};
+
+/// Errors that can occur when reading or sending a payout.
+#[derive(Debug)]
+pub enum PayoutError {
+    Io(io::Error),
+    InvalidRow {
+        line: usize,
+        reason: String,
+    },
+    UnknownAccount(AccountId),
+    InsufficientBalance {
+        faucet_id: AccountId,
+        required: u64,
+        available: u64,
+    },
+    /// The payouts of one faucet add up to more than `u64::MAX` tokens.
+    TotalOverflow(AccountId),
+    Client(Box<ClientError>),
+    /// A batch failed after earlier batches were sent. `report` holds the sent batches, so a
+    /// rerun can leave out the payouts that already went out.
+    Interrupted {
+        report: Box<PayoutReport>,
+        cause: Box<PayoutError>,
+    },
+}
+
+impl fmt::Display for PayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to read payout file: {err}"),
+            Self::InvalidRow { line, reason } => write!(f, "invalid payout on line {line}: {reason}"),
+            Self::UnknownAccount(account_id) => {
+                write!(f, "account {} is not tracked by the client", account_id.to_hex())
+            }
+            Self::InsufficientBalance {
+                faucet_id,
+                required,
+                available,
+            } => write!(
+                f,
+                "payouts need {required} tokens of faucet {}, but the sender only holds {available}",
+                faucet_id.to_hex()
+            ),
+            Self::TotalOverflow(faucet_id) => write!(
+                f,
+                "payouts of faucet {} add up to more than {} tokens",
+                faucet_id.to_hex(),
+                u64::MAX
+            ),
+            Self::Client(err) => write!(f, "failed to send payout: {err}"),
+            Self::Interrupted { report, cause } => write!(
+                f,
+                "payouts stopped after {} transaction(s): {cause}",
+                report.transactions.len()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PayoutError {}
+
+impl From<io::Error> for PayoutError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<ClientError> for PayoutError {
+    fn from(err: ClientError) -> Self {
+        Self::Client(Box::new(err))
+    }
+}
+
+/// One row of the payout file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Payout {
+    pub recipient: AccountId,
+    pub faucet_id: AccountId,
+    pub amount: u64,
+}
+
+/// Parses the rows of a payout file, see the module docs for the layout. Names are resolved with
+/// `address_book`.
+pub fn parse_payouts(csv: &str, address_book: &AddressBook) -> Result<Vec<Payout>, PayoutError> {
+    let mut payouts = Vec::new();
+
+    for (index, row) in csv.lines().enumerate() {
+        let line = index + 1;
+        let row = row.trim();
+        if row.is_empty() || row.starts_with('#') {
+            continue;
+        }
+
+        let invalid = |reason: String| PayoutError::InvalidRow { line, reason };
+        let fields: Vec<&str> = row.split(',').map(str::trim).collect();
+        if fields.len() != 3 {
+            return Err(invalid(format!(
+                "expected 3 fields, found {}",
+                fields.len()
+            )));
+        }
+        if index == 0 && fields[0].eq_ignore_ascii_case("recipient") {
+            continue;
+        }
+
+        let parse_id = |field: &str| {
+            address_book
+                .resolve(field)
+                .map_err(|err| invalid(format!("invalid account {field}: {err}")))
+        };
+        let payout = Payout {
+            recipient: parse_id(fields[0])?,
+            faucet_id: parse_id(fields[1])?,
+            amount: fields[2]
+                .parse()
+                .map_err(|err| invalid(format!("invalid amount {}: {err}", fields[2])))?,
+        };
+        // Checks the faucet type and the amount limits
+        FungibleAsset::new(payout.faucet_id, payout.amount)
+            .map_err(|err| invalid(err.to_string()))?;
+        payouts.push(payout);
+    }
+
+    Ok(payouts)
+}
+
+pub fn read_payouts(
+    path: impl AsRef<Path>,
+    address_book: &AddressBook,
+) -> Result<Vec<Payout>, PayoutError> {
+    parse_payouts(&fs::read_to_string(path)?, address_book)
+}
+
+/// Total amount paid out per faucet.
+pub fn totals(payouts: &[Payout]) -> Result<BTreeMap<AccountId, u64>, PayoutError> {
+    let mut totals = BTreeMap::new();
+    for payout in payouts {
+        let total = totals.entry(payout.faucet_id).or_insert(0u64);
+        *total = total
+            .checked_add(payout.amount)
+            .ok_or(PayoutError::TotalOverflow(payout.faucet_id))?;
+    }
+    Ok(totals)
+}
+
+/// Checks that the sender's vault covers the payouts of every faucet.
+pub async fn validate_balances(
+    client: &Client,
+    sender: AccountId,
+    payouts: &[Payout],
+) -> Result<(), PayoutError> {
+    let account = client
+        .get_account(sender)
+        .await?
+        .ok_or(PayoutError::UnknownAccount(sender))?;
+
+    for (faucet_id, required) in totals(payouts)? {
+        let available = account
+            .account()
+            .vault()
+            .get_balance(faucet_id)
+            .unwrap_or(0);
+        if available < required {
+            return Err(PayoutError::InsufficientBalance {
+                faucet_id,
+                required,
+                available,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Note ids per recipient and the transactions that created them.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PayoutReport {
+    pub transactions: Vec<String>,
+    /// Keyed by the bech32 recipient id.
+    pub recipients: BTreeMap<String, Vec<String>>,
+    /// The created notes, e.g. to hand private notes to their recipients.
+    #[serde(skip)]
+    pub notes: Vec<Note>,
+}
+
+impl PayoutReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("report only contains serializable values")
+    }
+}
+
+/// Sends the payouts from `sender`, at most `max_notes_per_tx` notes per transaction.
+///
+/// Balances are validated before the first transaction. Transactions are proven with `prover`
+/// if given, e.g. a delegated prover, and with the client's prover otherwise. If a batch fails,
+/// the error is [`PayoutError::Interrupted`] with the report of the batches sent before it.
+pub async fn send_payouts(
+    client: &mut Client,
+    sender: AccountId,
+    payouts: &[Payout],
+    note_policy: &NotePolicy,
+    max_notes_per_tx: usize,
+    prover: Option<Arc<dyn TransactionProver>>,
+) -> Result<PayoutReport, PayoutError> {
+    validate_balances(client, sender, payouts).await?;
+
+    let mut report = PayoutReport::default();
+    for batch in payouts.chunks(max_notes_per_tx.clamp(1, MAX_OUTPUT_NOTES_PER_TX)) {
+        let notes = match send_batch(client, sender, batch, note_policy, prover.as_ref()).await {
+            Ok((tx_id, notes)) => {
+                report.transactions.push(tx_id.to_hex());
+                notes
+            }
+            Err(err) => {
+                return Err(PayoutError::Interrupted {
+                    report: Box::new(report),
+                    cause: Box::new(err.into()),
+                })
+            }
+        };
+
+        for (payout, note) in batch.iter().zip(&notes) {
+            report
+                .recipients
+                .entry(payout.recipient.to_bech32(NetworkId::Testnet))
+                .or_default()
+                .push(note.id().to_hex());
+        }
+        report.notes.extend(notes);
+    }
+
+    Ok(report)
+}
+
+/// Sends one P2ID note per payout in a single transaction.
+async fn send_batch(
+    client: &mut Client,
+    sender: AccountId,
+    batch: &[Payout],
+    note_policy: &NotePolicy,
+    prover: Option<&Arc<dyn TransactionProver>>,
+) -> Result<(TransactionId, Vec<Note>), ClientError> {
+    let mut notes = Vec::with_capacity(batch.len());
+    for payout in batch {
+        let asset = FungibleAsset::new(payout.faucet_id, payout.amount)?;
+        let note = create_p2id_note(
+            sender,
+            payout.recipient,
+            vec![asset.into()],
+            note_policy.note_type_for(payout.recipient),
+            Felt::new(0),
+            client.rng(),
+        )?;
+        notes.push(note);
+    }
+
+    let transaction_request = TransactionRequestBuilder::new()
+        .own_output_notes(notes.iter().cloned().map(OutputNote::Full))
+        .build()?;
+    let tx_execution_result = client.new_transaction(sender, transaction_request).await?;
+    let tx_id = tx_execution_result.executed_transaction().id();
+    match prover {
+        Some(prover) => {
+            client
+                .submit_transaction_with_prover(tx_execution_result, prover.clone())
+                .await?
+        }
+        None => client.submit_transaction(tx_execution_result).await?,
+    }
+
+    Ok((tx_id, notes))
+}
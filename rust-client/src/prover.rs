@@ -0,0 +1,198 @@
+//! Delegated proving that falls back to proving locally.
+//!
+//! [`ProverSelector`] tries a list of remote provers in order, each under a timeout, and proves
+//! the transaction locally if none of them returns a proof. It implements [`TransactionProver`],
+//! so it is passed to `Client::submit_transaction_with_prover` in place of a single prover. Keep
+//! an `Arc` of it to read [`ProverSelector::records`] afterwards.
+//!
+//! Every proof is recorded with the prover that produced it and the remote provers that failed
+//! before it, so a flow can report when it ran without its delegated prover.
+
+use std::{
+    env, fmt,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use miden_client::{
+    transaction::{
+        LocalTransactionProver, ProvenTransaction, TransactionId, TransactionProver,
+        TransactionProverError,
+    },
+    RemoteTransactionProver,
+};
+use miden_objects::transaction::TransactionWitness;
+use serde::Serialize;
+
+/// Environment variable holding a comma-separated list of remote prover endpoints.
+pub const PROVERS_ENV: &str = "MIDEN_PROVERS";
+
+/// The testnet delegated prover, used unless [`PROVERS_ENV`] is set.
+pub const DEFAULT_PROVER_URL: &str = "https://tx-prover.testnet.miden.io";
+
+/// How long a remote prover has to return a proof before the next one is tried.
+pub const DEFAULT_PROVER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The prover that produced a proof.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProofSource {
+    /// The remote prover at the given endpoint.
+    Remote(String),
+    Local,
+}
+
+impl fmt::Display for ProofSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Remote(endpoint) => write!(f, "remote prover at {endpoint}"),
+            Self::Local => write!(f, "local prover"),
+        }
+    }
+}
+
+/// A remote prover that did not return a proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProverFailure {
+    pub endpoint: String,
+    pub reason: String,
+}
+
+/// How one transaction was proven.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofRecord {
+    pub transaction_id: TransactionId,
+    pub source: ProofSource,
+    /// Time the prover in `source` took to return the proof.
+    pub proving_time: Duration,
+    /// The remote provers tried before `source`, in order.
+    pub failures: Vec<ProverFailure>,
+}
+
+/// A [`TransactionProver`] that tries remote provers in order and falls back to local proving.
+pub struct ProverSelector {
+    remotes: Vec<(String, RemoteTransactionProver)>,
+    timeout: Duration,
+    local: LocalTransactionProver,
+    records: Mutex<Vec<ProofRecord>>,
+}
+
+impl ProverSelector {
+    /// A selector trying the remote provers at `endpoints` in order, giving each `timeout` to
+    /// return a proof. With no endpoints every transaction is proven locally.
+    pub fn new<I, S>(endpoints: I, timeout: Duration) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let remotes = endpoints
+            .into_iter()
+            .map(|endpoint| {
+                let endpoint = endpoint.into();
+                (endpoint.clone(), RemoteTransactionProver::new(endpoint))
+            })
+            .collect();
+        Self {
+            remotes,
+            timeout,
+            local: LocalTransactionProver::default(),
+            records: Mutex::new(vec![]),
+        }
+    }
+
+    /// A selector trying the endpoints from [`endpoints_from_env`] with
+    /// [`DEFAULT_PROVER_TIMEOUT`].
+    pub fn from_env() -> Self {
+        Self::new(endpoints_from_env(), DEFAULT_PROVER_TIMEOUT)
+    }
+
+    /// The remote endpoints, in the order they are tried.
+    pub fn endpoints(&self) -> impl Iterator<Item = &str> {
+        self.remotes.iter().map(|(endpoint, _)| endpoint.as_str())
+    }
+
+    /// How every transaction proven so far was proven, oldest first.
+    pub fn records(&self) -> Vec<ProofRecord> {
+        self.records
+            .lock()
+            .expect("records lock is not poisoned")
+            .clone()
+    }
+
+    fn record(
+        &self,
+        proven: &ProvenTransaction,
+        source: ProofSource,
+        started: Instant,
+        failures: Vec<ProverFailure>,
+    ) {
+        self.records
+            .lock()
+            .expect("records lock is not poisoned")
+            .push(ProofRecord {
+                transaction_id: proven.id(),
+                source,
+                proving_time: started.elapsed(),
+                failures,
+            });
+    }
+}
+
+#[async_trait(?Send)]
+impl TransactionProver for ProverSelector {
+    async fn prove(
+        &self,
+        tx_witness: TransactionWitness,
+    ) -> Result<ProvenTransaction, TransactionProverError> {
+        let mut failures = vec![];
+        for (endpoint, remote) in &self.remotes {
+            let started = Instant::now();
+            let reason =
+                match tokio::time::timeout(self.timeout, remote.prove(tx_witness.clone())).await {
+                    Ok(Ok(proven)) => {
+                        let source = ProofSource::Remote(endpoint.clone());
+                        self.record(&proven, source, started, failures);
+                        return Ok(proven);
+                    }
+                    Ok(Err(err)) => error_chain(&err),
+                    Err(_) => format!("no proof after {:?}", self.timeout),
+                };
+            failures.push(ProverFailure {
+                endpoint: endpoint.clone(),
+                reason,
+            });
+        }
+
+        let started = Instant::now();
+        let proven = self.local.prove(tx_witness).await?;
+        self.record(&proven, ProofSource::Local, started, failures);
+        Ok(proven)
+    }
+}
+
+/// The endpoints in [`PROVERS_ENV`], or the testnet delegated prover if it is not set. An empty
+/// [`PROVERS_ENV`] gives no endpoints, i.e. local proving only.
+pub fn endpoints_from_env() -> Vec<String> {
+    match env::var(PROVERS_ENV) {
+        Ok(endpoints) => endpoints
+            .split(',')
+            .map(str::trim)
+            .filter(|endpoint| !endpoint.is_empty())
+            .map(String::from)
+            .collect(),
+        Err(_) => vec![DEFAULT_PROVER_URL.into()],
+    }
+}
+
+/// The error and its sources, since the remote prover errors keep the cause in the source.
+pub fn error_chain(err: &dyn std::error::Error) -> String {
+    let mut message = err.to_string();
+    let mut source = err.source();
+    while let Some(err) = source {
+        message.push_str(": ");
+        message.push_str(&err.to_string());
+        source = err.source();
+    }
+    message
+}
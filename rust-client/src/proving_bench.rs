@@ -0,0 +1,361 @@
+//! Benchmark comparing local and delegated proving on representative transactions.
+//!
+//! Every workload is executed once and its witness is proven by each prover under test, so local
+//! and remote proving are compared on the same transaction. Every proof is recorded with the
+//! execution time, the proving time, the size of the proof and of the proven transaction, and the
+//! cycle counts of the execution. One of the proofs is then submitted, so workloads that build on
+//! each other run in order on one client.
+//!
+//! [`ProvingBenchReport`] puts the provers side by side for every workload. Like the reports in
+//! [`crate::bench`], it is written as JSON (comparison and raw samples) and CSV (raw samples).
+
+use std::{
+    fmt::{self, Write as _},
+    time::{Duration, Instant},
+};
+
+use miden_client::{
+    account::AccountId,
+    transaction::{
+        ExecutedTransaction, ProvenTransaction, TransactionId, TransactionProver,
+        TransactionProverError, TransactionRequest,
+    },
+    utils::Serializable,
+    Client, ClientError,
+};
+use miden_objects::transaction::{TransactionMeasurements, TransactionWitness};
+use serde::Serialize;
+
+use crate::{
+    bench::{millis, Percentiles},
+    prover::{error_chain, ProofSource},
+};
+
+/// A representative transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Workload {
+    /// A wallet creating ten P2ID notes, as in `delegated_prover`.
+    P2idBatch,
+    /// A script calling `increment_count` on a counter contract.
+    CounterIncrement,
+    /// A contract reading the counter with a foreign procedure invocation.
+    ForeignProcedureCall,
+    /// A wallet consuming a hash-preimage note.
+    CustomNoteConsume,
+}
+
+impl Workload {
+    pub const ALL: [Self; 4] = [
+        Self::P2idBatch,
+        Self::CounterIncrement,
+        Self::ForeignProcedureCall,
+        Self::CustomNoteConsume,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::P2idBatch => "p2id_batch",
+            Self::CounterIncrement => "counter_increment",
+            Self::ForeignProcedureCall => "foreign_procedure_call",
+            Self::CustomNoteConsume => "custom_note_consume",
+        }
+    }
+}
+
+impl fmt::Display for Workload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+/// VM cycles spent in each stage of executing a transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CycleCounts {
+    pub prologue: usize,
+    pub notes_processing: usize,
+    pub tx_script_processing: usize,
+    pub epilogue: usize,
+    pub total: usize,
+    /// The total rounded up to a power of two. Proving time grows with this, not the total.
+    pub trace_length: usize,
+}
+
+impl From<&TransactionMeasurements> for CycleCounts {
+    fn from(measurements: &TransactionMeasurements) -> Self {
+        Self {
+            prologue: measurements.prologue,
+            notes_processing: measurements.notes_processing,
+            tx_script_processing: measurements.tx_script_processing,
+            epilogue: measurements.epilogue,
+            total: measurements.total_cycles(),
+            trace_length: measurements.trace_length(),
+        }
+    }
+}
+
+/// One proof of one workload by one prover.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProvingSample {
+    pub workload: Workload,
+    pub prover: ProofSource,
+    pub iteration: usize,
+    pub execute_ms: f64,
+    /// The proving fields are `None` if the prover failed, see `error`.
+    pub prove_ms: Option<f64>,
+    pub proof_bytes: Option<usize>,
+    pub proven_tx_bytes: Option<usize>,
+    pub cycles: CycleCounts,
+    pub error: Option<String>,
+}
+
+/// Proves `executed` `iterations` times with each prover, in order.
+///
+/// A remote prover that returns no proof within `remote_timeout` is recorded as a failure, like
+/// `ProverSelector` gives up on it. The local prover has no timeout.
+///
+/// Returns the samples and the first proof produced, or `None` if every prover failed.
+pub async fn measure_proving(
+    workload: Workload,
+    executed: &ExecutedTransaction,
+    execute_time: Duration,
+    provers: &[(ProofSource, &dyn TransactionProver)],
+    iterations: usize,
+    remote_timeout: Duration,
+) -> (Vec<ProvingSample>, Option<ProvenTransaction>) {
+    let witness = TransactionWitness::from(executed.clone());
+    let cycles = CycleCounts::from(executed.measurements());
+    let mut samples = Vec::with_capacity(provers.len() * iterations);
+    let mut first_proof = None;
+
+    for (source, prover) in provers {
+        for iteration in 0..iterations {
+            let mut sample = ProvingSample {
+                workload,
+                prover: source.clone(),
+                iteration,
+                execute_ms: millis(execute_time),
+                prove_ms: None,
+                proof_bytes: None,
+                proven_tx_bytes: None,
+                cycles: cycles.clone(),
+                error: None,
+            };
+
+            let start = Instant::now();
+            let result = match source {
+                ProofSource::Local => Ok(prover.prove(witness.clone()).await),
+                ProofSource::Remote(_) => {
+                    tokio::time::timeout(remote_timeout, prover.prove(witness.clone())).await
+                }
+            };
+            match result {
+                Ok(Ok(proven)) => {
+                    sample.prove_ms = Some(millis(start.elapsed()));
+                    sample.proof_bytes = Some(proven.proof().to_bytes().len());
+                    sample.proven_tx_bytes = Some(proven.to_bytes().len());
+                    first_proof.get_or_insert(proven);
+                }
+                Ok(Err(err)) => sample.error = Some(error_chain(&err)),
+                Err(_) => sample.error = Some(format!("no proof after {remote_timeout:?}")),
+            }
+            samples.push(sample);
+        }
+    }
+
+    (samples, first_proof)
+}
+
+/// Executes `request` on `account_id`, proves it with every prover and submits the first proof,
+/// so later workloads see the new state. Remote provers get `remote_timeout` for every proof, see
+/// [`measure_proving`].
+///
+/// Returns the samples and the id of the transaction.
+pub async fn bench_transaction(
+    client: &mut Client,
+    workload: Workload,
+    account_id: AccountId,
+    request: TransactionRequest,
+    provers: &[(ProofSource, &dyn TransactionProver)],
+    iterations: usize,
+    remote_timeout: Duration,
+) -> Result<(Vec<ProvingSample>, TransactionId), ClientError> {
+    let start = Instant::now();
+    let tx_result = client.new_transaction(account_id, request).await?;
+    let execute_time = start.elapsed();
+
+    let (samples, proven) = measure_proving(
+        workload,
+        tx_result.executed_transaction(),
+        execute_time,
+        provers,
+        iterations,
+        remote_timeout,
+    )
+    .await;
+    let proven = proven.ok_or_else(|| {
+        TransactionProverError::other(format!("no prover could prove the {workload} workload"))
+    })?;
+
+    let transaction_id = proven.id();
+    client.testing_submit_proven_transaction(proven).await?;
+    client.testing_apply_transaction(tx_result).await?;
+    Ok((samples, transaction_id))
+}
+
+/// How one prover did on one workload.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProverSummary {
+    pub prover: ProofSource,
+    /// Over the successful proofs only.
+    pub prove_ms: Percentiles,
+    pub proof_bytes: Option<usize>,
+    pub failures: usize,
+    /// Median local proving time divided by this prover's, above 1 when this prover is faster.
+    /// `None` without successful local and remote proofs.
+    pub speedup: Option<f64>,
+}
+
+/// All provers side by side on one workload.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkloadComparison {
+    pub workload: Workload,
+    pub execute_ms: f64,
+    pub cycles: CycleCounts,
+    pub provers: Vec<ProverSummary>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProvingBenchReport {
+    /// Free-form label for the run, e.g. the machine proving locally.
+    pub label: String,
+    pub iterations: usize,
+    pub workloads: Vec<WorkloadComparison>,
+    pub samples: Vec<ProvingSample>,
+}
+
+impl ProvingBenchReport {
+    /// Groups the samples by workload and prover, both in the order they were first measured.
+    pub fn new(label: String, iterations: usize, samples: Vec<ProvingSample>) -> Self {
+        let mut workloads: Vec<Workload> = vec![];
+        for sample in &samples {
+            if !workloads.contains(&sample.workload) {
+                workloads.push(sample.workload);
+            }
+        }
+
+        let workloads = workloads
+            .into_iter()
+            .map(|workload| {
+                let samples: Vec<&ProvingSample> = samples
+                    .iter()
+                    .filter(|sample| sample.workload == workload)
+                    .collect();
+                compare(workload, &samples)
+            })
+            .collect();
+
+        Self {
+            label,
+            iterations,
+            workloads,
+            samples,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("report only contains serializable values")
+    }
+
+    /// One row per sample. The prover is `local` or the endpoint of the remote prover.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "workload,prover,iteration,execute_ms,prove_ms,proof_bytes,proven_tx_bytes,\
+             total_cycles,trace_length,error\n",
+        );
+        for sample in &self.samples {
+            let prover = match &sample.prover {
+                ProofSource::Local => "local",
+                ProofSource::Remote(endpoint) => endpoint,
+            };
+            writeln!(
+                csv,
+                "{},{},{},{:.3},{},{},{},{},{},{}",
+                sample.workload,
+                prover,
+                sample.iteration,
+                sample.execute_ms,
+                sample
+                    .prove_ms
+                    .map(|ms| format!("{ms:.3}"))
+                    .unwrap_or_default(),
+                optional(sample.proof_bytes),
+                optional(sample.proven_tx_bytes),
+                sample.cycles.total,
+                sample.cycles.trace_length,
+                // Errors are free text, so they are quoted
+                sample
+                    .error
+                    .as_ref()
+                    .map(|err| format!("\"{}\"", err.replace('"', "\"\"")))
+                    .unwrap_or_default()
+            )
+            .expect("writing to a string cannot fail");
+        }
+        csv
+    }
+}
+
+fn compare(workload: Workload, samples: &[&ProvingSample]) -> WorkloadComparison {
+    let mut provers: Vec<ProofSource> = vec![];
+    for sample in samples {
+        if !provers.contains(&sample.prover) {
+            provers.push(sample.prover.clone());
+        }
+    }
+
+    let prove_times = |prover: &ProofSource| -> Vec<f64> {
+        samples
+            .iter()
+            .filter(|sample| &sample.prover == prover)
+            .filter_map(|sample| sample.prove_ms)
+            .collect()
+    };
+    let local_p50 = Some(prove_times(&ProofSource::Local))
+        .filter(|times| !times.is_empty())
+        .map(|times| Percentiles::from_samples(times).p50);
+
+    let provers = provers
+        .into_iter()
+        .map(|prover| {
+            let times = prove_times(&prover);
+            let prove_ms = Percentiles::from_samples(times.iter().copied());
+            let speedup = match (&prover, local_p50) {
+                (ProofSource::Remote(_), Some(local)) if !times.is_empty() => {
+                    Some(local / prove_ms.p50.max(f64::EPSILON))
+                }
+                _ => None,
+            };
+            let of_prover = samples.iter().filter(|sample| sample.prover == prover);
+            ProverSummary {
+                proof_bytes: of_prover.clone().find_map(|sample| sample.proof_bytes),
+                failures: of_prover.filter(|sample| sample.error.is_some()).count(),
+                prover,
+                prove_ms,
+                speedup,
+            }
+        })
+        .collect();
+
+    let first = samples[0];
+    WorkloadComparison {
+        workload,
+        execute_ms: first.execute_ms,
+        cycles: first.cycles.clone(),
+        provers,
+    }
+}
+
+fn optional(value: Option<usize>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
@@ -0,0 +1,66 @@
+//! Reclaimable payments and recovering the ones nobody claimed.
+//!
+//! A payment with a reclaim height is sent as a P2IDE note. The target can consume it at any
+//! time, and once the chain passes the reclaim height the sender can consume it as well, which
+//! returns the assets to the sender's vault.
+
+use miden_client::{
+    account::AccountId,
+    note::{BlockNumber, NoteId, NoteRelevance, WellKnownNote},
+    transaction::{PaymentNoteDescription, TransactionId, TransactionRequestBuilder},
+    Client, ClientError,
+};
+
+/// Makes `payment` reclaimable by its sender `blocks` blocks after `current_block`.
+pub fn reclaimable_after(
+    payment: PaymentNoteDescription,
+    current_block: BlockNumber,
+    blocks: u32,
+) -> PaymentNoteDescription {
+    payment.with_reclaim_height(current_block + blocks)
+}
+
+/// Returns the unclaimed P2IDE notes `sender` created that it can reclaim at the client's
+/// current sync height.
+pub async fn reclaimable_notes(
+    client: &Client,
+    sender: AccountId,
+) -> Result<Vec<NoteId>, ClientError> {
+    let p2ide_root = WellKnownNote::P2IDE.script_root();
+    let consumable_notes = client.get_consumable_notes(Some(sender)).await?;
+
+    Ok(consumable_notes
+        .into_iter()
+        .filter(|(note, relevances)| {
+            note.details().script().root() == p2ide_root
+                && note
+                    .metadata()
+                    .is_some_and(|metadata| metadata.sender() == sender)
+                && relevances.iter().any(|(account_id, relevance)| {
+                    *account_id == sender && *relevance == NoteRelevance::Now
+                })
+        })
+        .map(|(note, _)| note.id())
+        .collect())
+}
+
+/// Consumes every note [`reclaimable_notes`] finds in a single transaction with `sender`.
+///
+/// Returns `None` if there was nothing to reclaim.
+pub async fn reclaim(
+    client: &mut Client,
+    sender: AccountId,
+) -> Result<Option<(TransactionId, Vec<NoteId>)>, ClientError> {
+    let note_ids = reclaimable_notes(client, sender).await?;
+    if note_ids.is_empty() {
+        return Ok(None);
+    }
+
+    let transaction_request =
+        TransactionRequestBuilder::new().build_consume_notes(note_ids.clone())?;
+    let tx_execution_result = client.new_transaction(sender, transaction_request).await?;
+    let tx_id = tx_execution_result.executed_transaction().id();
+    client.submit_transaction(tx_execution_result).await?;
+
+    Ok(Some((tx_id, note_ids)))
+}
@@ -0,0 +1,411 @@
+//! A small note relay for handing unauthenticated notes between client processes.
+//!
+//! Senders post notes addressed by [`NoteTag`] and receivers subscribe to a tag. The relay keeps
+//! posted notes in a per-tag mailbox, so a receiver that subscribes late still gets the notes
+//! posted before it connected. Mailboxes keep the most recent notes up to an age and count limit,
+//! see [`NoteRelay::with_retention`]. A subscriber that falls behind the live feed is caught up
+//! from its mailbox, and is disconnected if the notes it missed were already pruned.
+//!
+//! The protocol is newline-delimited JSON over TCP. Notes travel in the text encoding of
+//! [`NoteExport`], so the note argument is relayed together with the note.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt, io,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use miden_client::note::NoteTag;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines},
+    net::{tcp::OwnedReadHalf, TcpListener, TcpStream, ToSocketAddrs},
+    sync::broadcast,
+};
+
+use crate::note_file::{NoteExport, NoteFileError};
+
+/// Address the relay listens on unless told otherwise.
+pub const DEFAULT_RELAY_ADDR: &str = "127.0.0.1:7878";
+
+/// How many notes a slow subscriber can fall behind before it is caught up from its mailbox.
+const CHANNEL_CAPACITY: usize = 256;
+
+/// How many notes a mailbox keeps unless told otherwise.
+pub const DEFAULT_MAILBOX_NOTES: usize = 1024;
+
+/// How long a mailbox keeps a note unless told otherwise.
+pub const DEFAULT_MAILBOX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Message {
+    /// Sent by a sender to store and forward a note.
+    Post { tag: u32, note: String },
+    /// Sent by a receiver to start receiving notes with `tag`.
+    Subscribe { tag: u32 },
+    /// Sent by the relay for every note matching a subscription.
+    Note { tag: u32, note: String },
+}
+
+/// Errors that can occur when talking to the relay.
+#[derive(Debug)]
+pub enum RelayError {
+    Io(io::Error),
+    InvalidMessage(serde_json::Error),
+    UnexpectedMessage(String),
+    NoteFile(NoteFileError),
+    /// A subscriber fell behind by more notes than its mailbox still holds.
+    SubscriberLagged,
+}
+
+impl fmt::Display for RelayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "relay connection failed: {err}"),
+            Self::InvalidMessage(err) => write!(f, "invalid relay message: {err}"),
+            Self::UnexpectedMessage(message) => write!(f, "unexpected relay message: {message}"),
+            Self::NoteFile(err) => write!(f, "invalid relayed note: {err}"),
+            Self::SubscriberLagged => {
+                write!(f, "subscriber fell behind notes that were already pruned")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RelayError {}
+
+impl From<io::Error> for RelayError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for RelayError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::InvalidMessage(err)
+    }
+}
+
+impl From<NoteFileError> for RelayError {
+    fn from(err: NoteFileError) -> Self {
+        Self::NoteFile(err)
+    }
+}
+
+/// A note kept in a mailbox, numbered in posting order across all tags.
+#[derive(Debug, Clone)]
+struct StoredNote {
+    seq: u64,
+    posted: Instant,
+    note: String,
+}
+
+#[derive(Debug, Default)]
+struct Mailbox {
+    notes: VecDeque<StoredNote>,
+    /// The number of the last note pruned from this mailbox.
+    pruned_through: Option<u64>,
+    /// Subscribers that may still have to catch up from this mailbox.
+    subscribers: usize,
+}
+
+impl Mailbox {
+    fn prune_front(&mut self) {
+        if let Some(note) = self.notes.pop_front() {
+            self.pruned_through = Some(note.seq);
+        }
+    }
+
+    fn is_unused(&self) -> bool {
+        self.notes.is_empty() && self.subscribers == 0
+    }
+}
+
+/// Keeps the mailbox of a subscribed tag until the subscriber is gone.
+struct Subscriber<'a> {
+    state: &'a RelayState,
+    tag: u32,
+}
+
+impl Drop for Subscriber<'_> {
+    fn drop(&mut self) {
+        let mut mailboxes = self
+            .state
+            .mailboxes
+            .lock()
+            .expect("relay state lock poisoned");
+        if let Some(mailbox) = mailboxes.by_tag.get_mut(&self.tag) {
+            mailbox.subscribers -= 1;
+            if mailbox.is_unused() {
+                mailboxes.by_tag.remove(&self.tag);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Mailboxes {
+    next_seq: u64,
+    by_tag: HashMap<u32, Mailbox>,
+}
+
+#[derive(Debug)]
+struct RelayState {
+    mailboxes: Mutex<Mailboxes>,
+    live: broadcast::Sender<(u32, StoredNote)>,
+    max_notes: usize,
+    max_age: Duration,
+}
+
+impl RelayState {
+    fn post(&self, tag: u32, note: String) {
+        // Holding the lock while broadcasting keeps a concurrent subscriber from either missing
+        // the note or receiving it twice.
+        let mut mailboxes = self.mailboxes.lock().expect("relay state lock poisoned");
+        let now = Instant::now();
+        let stored = StoredNote {
+            seq: mailboxes.next_seq,
+            posted: now,
+            note,
+        };
+        mailboxes.next_seq += 1;
+        let mailbox = mailboxes.by_tag.entry(tag).or_default();
+        mailbox.notes.push_back(stored.clone());
+        while mailbox.notes.len() > self.max_notes {
+            mailbox.prune_front();
+        }
+
+        // Expired notes are pruned from every mailbox. Mailboxes left empty are dropped once
+        // nobody is subscribed, a lagging subscriber needs them to tell it missed notes.
+        for mailbox in mailboxes.by_tag.values_mut() {
+            while mailbox
+                .notes
+                .front()
+                .is_some_and(|note| now.duration_since(note.posted) > self.max_age)
+            {
+                mailbox.prune_front();
+            }
+        }
+        mailboxes
+            .by_tag
+            .retain(|&mailbox_tag, mailbox| mailbox_tag == tag || !mailbox.is_unused());
+
+        // Nobody listening is fine, the note stays in the mailbox.
+        let _ = self.live.send((tag, stored));
+    }
+
+    /// The notes in the mailbox for `tag`, the number the next posted note gets, and the live
+    /// feed from that note on. The mailbox is kept while the returned [`Subscriber`] lives.
+    fn subscribe(
+        &self,
+        tag: u32,
+    ) -> (
+        Subscriber<'_>,
+        Vec<StoredNote>,
+        u64,
+        broadcast::Receiver<(u32, StoredNote)>,
+    ) {
+        let mut mailboxes = self.mailboxes.lock().expect("relay state lock poisoned");
+        let next_seq = mailboxes.next_seq;
+        let mailbox = mailboxes.by_tag.entry(tag).or_default();
+        mailbox.subscribers += 1;
+        let pending = mailbox.notes.iter().cloned().collect();
+        (
+            Subscriber { state: self, tag },
+            pending,
+            next_seq,
+            self.live.subscribe(),
+        )
+    }
+
+    /// The notes with `tag` numbered `from` or later, for a subscriber that lagged behind.
+    fn catch_up(&self, tag: u32, from: u64) -> Result<Vec<StoredNote>, RelayError> {
+        let mailboxes = self.mailboxes.lock().expect("relay state lock poisoned");
+        let Some(mailbox) = mailboxes.by_tag.get(&tag) else {
+            return Ok(Vec::new());
+        };
+        if mailbox.pruned_through.is_some_and(|pruned| pruned >= from) {
+            return Err(RelayError::SubscriberLagged);
+        }
+        Ok(mailbox
+            .notes
+            .iter()
+            .filter(|note| note.seq >= from)
+            .cloned()
+            .collect())
+    }
+}
+
+/// The relay server.
+#[derive(Debug)]
+pub struct NoteRelay {
+    listener: TcpListener,
+    channel_capacity: usize,
+    max_notes: usize,
+    max_age: Duration,
+}
+
+impl NoteRelay {
+    pub async fn bind(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        Ok(Self {
+            listener: TcpListener::bind(addr).await?,
+            channel_capacity: CHANNEL_CAPACITY,
+            max_notes: DEFAULT_MAILBOX_NOTES,
+            max_age: DEFAULT_MAILBOX_AGE,
+        })
+    }
+
+    /// Keeps at most `max_notes` notes per tag, each for at most `max_age`.
+    pub fn with_retention(mut self, max_notes: usize, max_age: Duration) -> Self {
+        self.max_notes = max_notes;
+        self.max_age = max_age;
+        self
+    }
+
+    /// Sets how many live notes a subscriber can fall behind before it is caught up from its
+    /// mailbox.
+    pub fn with_channel_capacity(mut self, capacity: usize) -> Self {
+        self.channel_capacity = capacity;
+        self
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Accepts connections until the listener fails.
+    pub async fn run(self) -> io::Result<()> {
+        let (live, _) = broadcast::channel(self.channel_capacity);
+        let state = Arc::new(RelayState {
+            mailboxes: Mutex::default(),
+            live,
+            max_notes: self.max_notes,
+            max_age: self.max_age,
+        });
+        loop {
+            let (stream, peer) = self.listener.accept().await?;
+            let state = Arc::clone(&state);
+            tokio::spawn(async move {
+                if let Err(err) = handle_connection(stream, state).await {
+                    eprintln!("relay connection from {peer} closed: {err}");
+                }
+            });
+        }
+    }
+}
+
+async fn handle_connection(stream: TcpStream, state: Arc<RelayState>) -> Result<(), RelayError> {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Some(line) = lines.next_line().await? {
+        match serde_json::from_str(&line)? {
+            Message::Post { tag, note } => state.post(tag, note),
+            Message::Subscribe { tag } => {
+                let (_subscriber, pending, mut next, mut live) = state.subscribe(tag);
+                for stored in pending {
+                    write_note(&mut writer, tag, stored.note).await?;
+                }
+                // Notes numbered below `next` were sent already, either from the mailbox or live
+                loop {
+                    match live.recv().await {
+                        Ok((note_tag, stored)) if note_tag == tag && stored.seq >= next => {
+                            next = stored.seq + 1;
+                            write_note(&mut writer, tag, stored.note).await?;
+                        }
+                        Ok(_) => {}
+                        Err(broadcast::error::RecvError::Lagged(_)) => {
+                            for stored in state.catch_up(tag, next)? {
+                                next = stored.seq + 1;
+                                write_note(&mut writer, tag, stored.note).await?;
+                            }
+                        }
+                        Err(broadcast::error::RecvError::Closed) => return Ok(()),
+                    }
+                }
+            }
+            message @ Message::Note { .. } => {
+                return Err(RelayError::UnexpectedMessage(format!("{message:?}")));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn write_note(
+    writer: &mut (impl AsyncWriteExt + Unpin),
+    tag: u32,
+    note: String,
+) -> Result<(), RelayError> {
+    write_message(writer, &Message::Note { tag, note }).await
+}
+
+async fn write_message(
+    writer: &mut (impl AsyncWriteExt + Unpin),
+    message: &Message,
+) -> Result<(), RelayError> {
+    let mut line = serde_json::to_string(message)?;
+    line.push('\n');
+    writer.write_all(line.as_bytes()).await?;
+    Ok(())
+}
+
+/// Posts a note to the relay, addressed to everyone subscribed to `tag`.
+pub async fn post_note(
+    addr: impl ToSocketAddrs,
+    tag: NoteTag,
+    note: &NoteExport,
+) -> Result<(), RelayError> {
+    post_notes(addr, tag, std::slice::from_ref(note)).await
+}
+
+/// Posts several notes with the same tag over one connection.
+pub async fn post_notes(
+    addr: impl ToSocketAddrs,
+    tag: NoteTag,
+    notes: &[NoteExport],
+) -> Result<(), RelayError> {
+    let mut stream = TcpStream::connect(addr).await?;
+    for note in notes {
+        let message = Message::Post {
+            tag: tag.into(),
+            note: note.to_text(),
+        };
+        write_message(&mut stream, &message).await?;
+    }
+    stream.shutdown().await?;
+    Ok(())
+}
+
+/// A receiver's subscription to the notes posted with one tag.
+#[derive(Debug)]
+pub struct RelaySubscription {
+    lines: Lines<BufReader<OwnedReadHalf>>,
+}
+
+impl RelaySubscription {
+    pub async fn connect(addr: impl ToSocketAddrs, tag: NoteTag) -> Result<Self, RelayError> {
+        let (reader, mut writer) = TcpStream::connect(addr).await?.into_split();
+        write_message(&mut writer, &Message::Subscribe { tag: tag.into() }).await?;
+        // The relay only reads the subscription, the write half can be closed.
+        writer.shutdown().await?;
+        Ok(Self {
+            lines: BufReader::new(reader).lines(),
+        })
+    }
+
+    /// Waits for the next note, or returns `None` once the relay closes the connection.
+    pub async fn next_note(&mut self) -> Result<Option<NoteExport>, RelayError> {
+        let Some(line) = self.lines.next_line().await? else {
+            return Ok(None);
+        };
+        match serde_json::from_str(&line)? {
+            Message::Note { note, .. } => Ok(Some(NoteExport::from_text(&note)?)),
+            message => Err(RelayError::UnexpectedMessage(format!("{message:?}"))),
+        }
+    }
+}
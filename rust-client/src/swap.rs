@@ -0,0 +1,117 @@
+//! Atomic swaps between two fungible faucets with the standard SWAP note.
+//!
+//! The creator locks the offered asset in a SWAP note. Whoever consumes it receives the offered
+//! asset and, in the same transaction, the note script creates a private P2ID note paying the
+//! requested asset back to the creator. Either both legs happen or neither does.
+
+use miden_client::{
+    account::AccountId,
+    asset::{Asset, FungibleAsset},
+    crypto::FeltRng,
+    note::{
+        create_swap_note, Note, NoteExecutionHint, NoteMetadata, NoteTag, NoteType, WellKnownNote,
+    },
+    transaction::{
+        OutputNote, TransactionRequest, TransactionRequestBuilder, TransactionRequestError,
+    },
+    Felt, Word,
+};
+use miden_objects::{note::NoteDetails, NoteError};
+
+/// The terms of a swap: `creator` gives `offered` in exchange for `requested`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwapOffer {
+    pub creator: AccountId,
+    pub offered: FungibleAsset,
+    pub requested: FungibleAsset,
+}
+
+impl SwapOffer {
+    pub fn new(creator: AccountId, offered: FungibleAsset, requested: FungibleAsset) -> Self {
+        Self {
+            creator,
+            offered,
+            requested,
+        }
+    }
+
+    /// Builds the SWAP note, drawing its serial number and the payback note's from `rng`.
+    pub fn build<R: FeltRng>(
+        &self,
+        note_type: NoteType,
+        rng: &mut R,
+    ) -> Result<SwapNote, NoteError> {
+        let (note, payback) = create_swap_note(
+            self.creator,
+            self.offered.into(),
+            self.requested.into(),
+            note_type,
+            Felt::new(0),
+            rng,
+        )?;
+        Ok(SwapNote {
+            creator: self.creator,
+            note,
+            payback,
+        })
+    }
+}
+
+/// A SWAP note together with the details of the note that pays the creator back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapNote {
+    pub creator: AccountId,
+    pub note: Note,
+    pub payback: NoteDetails,
+}
+
+impl SwapNote {
+    /// The tag of the payback note, which routes it to the creator.
+    pub fn payback_tag(&self) -> NoteTag {
+        NoteTag::from_account_id(self.creator)
+    }
+
+    /// The payback note exactly as the SWAP script creates it when `consumer` consumes the
+    /// SWAP note: private, sent by the consumer and without aux data.
+    pub fn payback_note(&self, consumer: AccountId) -> Result<Note, NoteError> {
+        let metadata = NoteMetadata::new(
+            consumer,
+            NoteType::Private,
+            self.payback_tag(),
+            NoteExecutionHint::always(),
+            Felt::new(0),
+        )?;
+        Ok(Note::new(
+            self.payback.assets().clone(),
+            metadata,
+            self.payback.recipient().clone(),
+        ))
+    }
+
+    /// The creator's transaction, which outputs the SWAP note and tells the client to expect
+    /// the payback note.
+    pub fn request(&self) -> Result<TransactionRequest, TransactionRequestError> {
+        TransactionRequestBuilder::new()
+            .own_output_notes(vec![OutputNote::Full(self.note.clone())])
+            .expected_future_notes(vec![(self.payback.clone(), self.payback_tag())])
+            .build()
+    }
+}
+
+/// Reads the asset a SWAP note asks for from its inputs, or `None` if `note` is not a SWAP note.
+pub fn requested_asset(note: &Note) -> Option<Asset> {
+    if note.script().root() != WellKnownNote::SWAP.script_root() {
+        return None;
+    }
+    let inputs = note.inputs().values();
+    let word: Word = inputs.get(4..8)?.try_into().ok()?;
+    Asset::try_from(word).ok()
+}
+
+/// The asset a SWAP note offers, or `None` if `note` is not a SWAP note.
+pub fn offered_asset(note: &Note) -> Option<Asset> {
+    if note.script().root() != WellKnownNote::SWAP.script_root() {
+        return None;
+    }
+    note.assets().iter().next().copied()
+}
@@ -0,0 +1,444 @@
+//! Named workspaces, so repeated tutorial runs do not share or delete each other's state.
+//!
+//! A workspace is a directory with its own sqlite store, keystore and address book. The default
+//! workspace is the current directory, which keeps the layout the tutorials always used:
+//! `./store.sqlite3`, `./keystore` and `./address_book.json`. Named workspaces live in
+//! `./workspaces/<name>/` with the same layout inside.
+//!
+//! Flows run in the workspace named by `MIDEN_WORKSPACE`, see [`Workspace::current`]. A workspace
+//! can be snapshotted and restored, or reset to an empty state, without touching any other
+//! workspace.
+//!
+//! Flows add keys through [`Workspace::open_keystore`], which works whether or not the keystore
+//! was encrypted with `keystore migrate`.
+
+use std::{
+    env, fmt, fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock},
+};
+
+use miden_client::{
+    account::AccountDelta,
+    auth::{AuthSecretKey, TransactionAuthenticator},
+    builder::ClientBuilder,
+    keystore::{FilesystemKeyStore, KeyStoreError},
+    rpc::{Endpoint, TonicRpcClient},
+    AuthenticationError, Client, ClientError, Felt, Word,
+};
+use rand::rngs::StdRng;
+
+use crate::keystore::{read_password, EncryptedKeyStore, KEYSTORE_HEADER_FILE};
+
+/// Environment variable naming the workspace flows run in.
+pub const WORKSPACE_ENV: &str = "MIDEN_WORKSPACE";
+
+/// Name of the workspace in the current directory.
+pub const DEFAULT_WORKSPACE: &str = "default";
+
+/// Directory named workspaces are created in.
+pub const WORKSPACES_DIR: &str = "workspaces";
+
+/// Directory in [`WORKSPACES_DIR`] that holds the snapshots of every workspace.
+const SNAPSHOTS_DIR: &str = ".snapshots";
+
+const STORE_FILE: &str = "store.sqlite3";
+const KEYSTORE_DIR: &str = "keystore";
+pub const ADDRESS_BOOK_FILE: &str = "address_book.json";
+
+/// Suffixes of the sqlite stores in a workspace, including the files sqlite keeps next to them.
+const STORE_SUFFIXES: [&str; 3] = [".sqlite3", ".sqlite3-wal", ".sqlite3-shm"];
+
+/// Errors that can occur when creating, opening or managing a workspace.
+#[derive(Debug)]
+pub enum WorkspaceError {
+    Io(io::Error),
+    /// Names are lowercase ASCII letters, digits, `-` and `_`, starting with a letter.
+    InvalidName(String),
+    NotFound(String),
+    AlreadyExists(String),
+    SnapshotNotFound(String),
+    SnapshotExists(String),
+    Keystore(String),
+}
+
+impl fmt::Display for WorkspaceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "workspace I/O error: {err}"),
+            Self::InvalidName(name) => write!(f, "invalid workspace or snapshot name {name}"),
+            Self::NotFound(name) => write!(f, "workspace {name} does not exist"),
+            Self::AlreadyExists(name) => write!(f, "workspace {name} already exists"),
+            Self::SnapshotNotFound(label) => write!(f, "snapshot {label} does not exist"),
+            Self::SnapshotExists(label) => write!(f, "snapshot {label} already exists"),
+            Self::Keystore(err) => write!(f, "failed to open the workspace keystore: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for WorkspaceError {}
+
+impl From<io::Error> for WorkspaceError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// The keystore of a workspace, in plaintext or encrypted.
+///
+/// Both have the interface of the client's `FilesystemKeyStore`. Cloning the store shares it, so
+/// a clone can be handed to the client as its authenticator while the original is used to add
+/// keys.
+#[derive(Debug, Clone)]
+pub enum WorkspaceKeyStore {
+    Plaintext(FilesystemKeyStore<StdRng>),
+    Encrypted(EncryptedKeyStore),
+}
+
+impl WorkspaceKeyStore {
+    pub fn add_key(&self, key: &AuthSecretKey) -> Result<(), KeyStoreError> {
+        match self {
+            Self::Plaintext(keystore) => keystore.add_key(key),
+            Self::Encrypted(keystore) => keystore
+                .add_key(key)
+                .map_err(|err| KeyStoreError::StorageError(err.to_string())),
+        }
+    }
+
+    pub fn get_key(&self, pub_key: Word) -> Result<Option<AuthSecretKey>, KeyStoreError> {
+        match self {
+            Self::Plaintext(keystore) => keystore.get_key(pub_key),
+            Self::Encrypted(keystore) => keystore
+                .get_key(pub_key)
+                .map_err(|err| KeyStoreError::StorageError(err.to_string())),
+        }
+    }
+}
+
+impl TransactionAuthenticator for WorkspaceKeyStore {
+    fn get_signature(
+        &self,
+        pub_key: Word,
+        message: Word,
+        account_delta: &AccountDelta,
+    ) -> Result<Vec<Felt>, AuthenticationError> {
+        match self {
+            Self::Plaintext(keystore) => keystore.get_signature(pub_key, message, account_delta),
+            Self::Encrypted(keystore) => keystore.get_signature(pub_key, message, account_delta),
+        }
+    }
+}
+
+/// A directory holding one client's store, keystore and address book.
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    name: String,
+    /// The directory the default workspace lives in and named workspaces are created under.
+    base: PathBuf,
+    root: PathBuf,
+    /// The encrypted keystore once [`Self::open_keystore`] unlocked it, shared by all clones.
+    unlocked_keystore: Arc<OnceLock<EncryptedKeyStore>>,
+}
+
+/// Workspaces are equal if they are the same directory, whether or not their keystore was
+/// unlocked yet.
+impl PartialEq for Workspace {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.base == other.base && self.root == other.root
+    }
+}
+
+impl Eq for Workspace {}
+
+impl Workspace {
+    /// The default workspace in `base`.
+    pub fn default_in(base: impl Into<PathBuf>) -> Self {
+        let base = base.into();
+        Self {
+            name: DEFAULT_WORKSPACE.to_string(),
+            root: base.clone(),
+            base,
+            unlocked_keystore: Arc::default(),
+        }
+    }
+
+    /// Creates the named workspace in `base`. Fails if it already exists.
+    pub fn create_in(base: impl Into<PathBuf>, name: &str) -> Result<Self, WorkspaceError> {
+        let workspace = Self::named(base.into(), name)?;
+        if workspace.root.exists() {
+            return Err(WorkspaceError::AlreadyExists(name.to_string()));
+        }
+        fs::create_dir_all(workspace.keystore_dir())?;
+        Ok(workspace)
+    }
+
+    /// Opens the named workspace in `base`, which has to exist unless it is the default one.
+    pub fn open_in(base: impl Into<PathBuf>, name: &str) -> Result<Self, WorkspaceError> {
+        if name == DEFAULT_WORKSPACE {
+            return Ok(Self::default_in(base));
+        }
+        let workspace = Self::named(base.into(), name)?;
+        if !workspace.root.is_dir() {
+            return Err(WorkspaceError::NotFound(name.to_string()));
+        }
+        Ok(workspace)
+    }
+
+    /// The workspace named by [`WORKSPACE_ENV`] in the current directory, created on first use,
+    /// or the default workspace if the variable is not set.
+    pub fn current() -> Result<Self, WorkspaceError> {
+        match env::var(WORKSPACE_ENV) {
+            Ok(name) => match Self::open_in(".", &name) {
+                Err(WorkspaceError::NotFound(_)) => Self::create_in(".", &name),
+                result => result,
+            },
+            Err(_) => Ok(Self::default_in(".")),
+        }
+    }
+
+    /// The names of the workspaces in `base`, starting with the default one.
+    pub fn list_in(base: impl AsRef<Path>) -> Result<Vec<String>, WorkspaceError> {
+        let mut names = vec![DEFAULT_WORKSPACE.to_string()];
+        let dir = base.as_ref().join(WORKSPACES_DIR);
+        if dir.is_dir() {
+            let mut named = vec![];
+            for entry in fs::read_dir(dir)? {
+                let entry = entry?;
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if entry.path().is_dir() && check_name(&name).is_ok() {
+                    named.push(name);
+                }
+            }
+            named.sort();
+            names.extend(named);
+        }
+        Ok(names)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn store_path(&self) -> PathBuf {
+        self.root.join(STORE_FILE)
+    }
+
+    pub fn keystore_dir(&self) -> PathBuf {
+        self.root.join(KEYSTORE_DIR)
+    }
+
+    pub fn address_book_path(&self) -> PathBuf {
+        self.root.join(ADDRESS_BOOK_FILE)
+    }
+
+    /// Whether the keystore of this workspace was encrypted, e.g. by [`migrate_keystore`].
+    ///
+    /// [`migrate_keystore`]: crate::keystore::migrate_keystore
+    pub fn is_keystore_encrypted(&self) -> bool {
+        self.keystore_dir().join(KEYSTORE_HEADER_FILE).exists()
+    }
+
+    /// The plaintext keystore of this workspace.
+    ///
+    /// Fails once the keystore is encrypted, since keys added through a `FilesystemKeyStore`
+    /// would land in plaintext next to the encrypted ones. Flows that should work in both modes
+    /// use [`Self::open_keystore`].
+    pub fn keystore(&self) -> Result<FilesystemKeyStore<StdRng>, WorkspaceError> {
+        if self.is_keystore_encrypted() {
+            return Err(WorkspaceError::Keystore(format!(
+                "{} is encrypted, unlock it with the keystore password instead",
+                self.keystore_dir().display()
+            )));
+        }
+        FilesystemKeyStore::new(self.keystore_dir())
+            .map_err(|err| WorkspaceError::Keystore(err.to_string()))
+    }
+
+    /// Unlocks the encrypted keystore of this workspace with `password`.
+    pub fn encrypted_keystore(&self, password: &str) -> Result<EncryptedKeyStore, WorkspaceError> {
+        EncryptedKeyStore::unlock(self.keystore_dir(), password)
+            .map_err(|err| WorkspaceError::Keystore(err.to_string()))
+    }
+
+    /// The keystore of this workspace, plaintext or encrypted, for flows that add keys.
+    ///
+    /// An encrypted keystore is unlocked with the password from [`read_password`] the first
+    /// time. Later calls, on this workspace or its clones, and the clients from
+    /// [`Self::instantiate_client`] share that session, so the password is asked for only once.
+    pub fn open_keystore(&self) -> Result<WorkspaceKeyStore, WorkspaceError> {
+        if !self.is_keystore_encrypted() {
+            return self.keystore().map(WorkspaceKeyStore::Plaintext);
+        }
+        if let Some(keystore) = self.unlocked_keystore.get() {
+            return Ok(WorkspaceKeyStore::Encrypted(keystore.clone()));
+        }
+        let keystore = read_password()
+            .and_then(|password| EncryptedKeyStore::unlock(self.keystore_dir(), &password))
+            .map_err(|err| WorkspaceError::Keystore(err.to_string()))?;
+        let keystore = self.unlocked_keystore.get_or_init(|| keystore);
+        Ok(WorkspaceKeyStore::Encrypted(keystore.clone()))
+    }
+
+    /// A client on `endpoint` using this workspace's keystore, configured like
+    /// `miden_client_tools::instantiate_client`.
+    ///
+    /// The client uses the workspace store, or `store_file` in the workspace for flows that run
+    /// more than one client. It signs with the keystore from [`Self::open_keystore`].
+    pub async fn instantiate_client(
+        &self,
+        endpoint: Endpoint,
+        store_file: Option<&str>,
+    ) -> Result<Client, ClientError> {
+        let timeout_ms = 10_000;
+        let rpc_api = Arc::new(TonicRpcClient::new(&endpoint, timeout_ms));
+        let store_path = self.root.join(store_file.unwrap_or(STORE_FILE));
+        let keystore = self
+            .open_keystore()
+            .map_err(|err| ClientError::ClientInitializationError(err.to_string()))?;
+        ClientBuilder::new()
+            .rpc(rpc_api)
+            .authenticator(Arc::new(keystore))
+            .sqlite_store(store_path.to_str().expect("path is valid UTF-8"))
+            .in_debug_mode(true)
+            .build()
+            .await
+    }
+
+    /// Copies the stores, keystore and address book into a snapshot named `label`.
+    pub fn snapshot(&self, label: &str) -> Result<(), WorkspaceError> {
+        let dir = self.snapshot_dir(label)?;
+        if dir.exists() {
+            return Err(WorkspaceError::SnapshotExists(label.to_string()));
+        }
+        fs::create_dir_all(&dir)?;
+        self.copy_state(&self.root, &dir)
+    }
+
+    /// The snapshots of this workspace, sorted by name.
+    pub fn snapshots(&self) -> Result<Vec<String>, WorkspaceError> {
+        let dir = self.snapshots_root();
+        if !dir.is_dir() {
+            return Ok(vec![]);
+        }
+        let mut labels = vec![];
+        for entry in fs::read_dir(dir)? {
+            labels.push(entry?.file_name().to_string_lossy().into_owned());
+        }
+        labels.sort();
+        Ok(labels)
+    }
+
+    /// Replaces the state of this workspace with the snapshot named `label`.
+    pub fn restore(&self, label: &str) -> Result<(), WorkspaceError> {
+        let dir = self.snapshot_dir(label)?;
+        if !dir.is_dir() {
+            return Err(WorkspaceError::SnapshotNotFound(label.to_string()));
+        }
+        self.reset()?;
+        self.copy_state(&dir, &self.root)
+    }
+
+    /// Deletes the stores, the keys and the address book of this workspace. Snapshots and other
+    /// workspaces are kept.
+    pub fn reset(&self) -> Result<(), WorkspaceError> {
+        for store in store_files(&self.root)? {
+            fs::remove_file(self.root.join(store))?;
+        }
+        remove_file_if_exists(&self.address_book_path())?;
+        let keystore_dir = self.keystore_dir();
+        if keystore_dir.is_dir() {
+            fs::remove_dir_all(&keystore_dir)?;
+        }
+        fs::create_dir_all(keystore_dir)?;
+        Ok(())
+    }
+
+    fn named(base: PathBuf, name: &str) -> Result<Self, WorkspaceError> {
+        check_name(name)?;
+        if name == DEFAULT_WORKSPACE {
+            return Err(WorkspaceError::InvalidName(name.to_string()));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            root: base.join(WORKSPACES_DIR).join(name),
+            base,
+            unlocked_keystore: Arc::default(),
+        })
+    }
+
+    fn snapshots_root(&self) -> PathBuf {
+        self.base
+            .join(WORKSPACES_DIR)
+            .join(SNAPSHOTS_DIR)
+            .join(&self.name)
+    }
+
+    fn snapshot_dir(&self, label: &str) -> Result<PathBuf, WorkspaceError> {
+        check_name(label)?;
+        Ok(self.snapshots_root().join(label))
+    }
+
+    /// Copies the workspace files that exist in `from` to `to`.
+    fn copy_state(&self, from: &Path, to: &Path) -> Result<(), WorkspaceError> {
+        for store in store_files(from)? {
+            fs::copy(from.join(&store), to.join(&store))?;
+        }
+        copy_file_if_exists(&from.join(ADDRESS_BOOK_FILE), &to.join(ADDRESS_BOOK_FILE))?;
+
+        let keystore_to = to.join(KEYSTORE_DIR);
+        fs::create_dir_all(&keystore_to)?;
+        let keystore_from = from.join(KEYSTORE_DIR);
+        if keystore_from.is_dir() {
+            for entry in fs::read_dir(keystore_from)? {
+                let path = entry?.path();
+                if path.is_file() {
+                    fs::copy(&path, keystore_to.join(path.file_name().unwrap()))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn check_name(name: &str) -> Result<(), WorkspaceError> {
+    let valid_chars = name
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if !valid_chars || !name.starts_with(|c: char| c.is_ascii_lowercase()) {
+        return Err(WorkspaceError::InvalidName(name.to_string()));
+    }
+    Ok(())
+}
+
+/// The names of the sqlite files directly in `dir`.
+fn store_files(dir: &Path) -> io::Result<Vec<String>> {
+    let mut files = vec![];
+    if dir.is_dir() {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if entry.path().is_file() && STORE_SUFFIXES.iter().any(|s| name.ends_with(s)) {
+                files.push(name);
+            }
+        }
+    }
+    Ok(files)
+}
+
+fn remove_file_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+fn copy_file_if_exists(from: &Path, to: &Path) -> io::Result<()> {
+    if from.is_file() {
+        fs::copy(from, to)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,125 @@
+mod common;
+
+use miden_client::{
+    asset::FungibleAsset, auth::AuthSecretKey, crypto::SecretKey, note::NoteType,
+    testing::account_id::ACCOUNT_ID_SENDER, transaction::TransactionRequestBuilder, Word,
+};
+use rust_client::{
+    account_bundle::{import_bundle, AccountBundle, AccountBundleError},
+    faucet::FaucetSpec,
+};
+
+use common::{mock_client, temp_dir};
+
+#[test]
+fn bundles_round_trip_and_reject_corruption() -> anyhow::Result<()> {
+    let key_pair = SecretKey::new();
+    let (faucet, seed) = FaucetSpec::default().build_account([7; 32], key_pair.public_key())?;
+    let bundle = AccountBundle::new(
+        faucet.clone(),
+        Some(seed),
+        vec![AuthSecretKey::RpoFalcon512(key_pair.clone())],
+    )?;
+
+    let decoded = AccountBundle::from_bytes(&bundle.to_bytes())?;
+    assert_eq!(decoded.account, faucet);
+    assert_eq!(decoded.seed, Some(seed));
+    assert_eq!(decoded.commitment, faucet.commitment());
+    assert_eq!(
+        decoded.public_keys(),
+        vec![Word::from(key_pair.public_key())]
+    );
+
+    let mut corrupted = bundle.to_bytes();
+    corrupted[10] ^= 1;
+    assert!(matches!(
+        AccountBundle::from_bytes(&corrupted),
+        Err(AccountBundleError::ChecksumMismatch)
+    ));
+    assert!(matches!(
+        AccountBundle::from_bytes(b"MNTF\x01 not an account bundle at all"),
+        Err(AccountBundleError::InvalidMagic)
+    ));
+
+    // A new account cannot be deployed from a bundle without its seed
+    assert!(matches!(
+        AccountBundle::new(faucet, None, vec![]),
+        Err(AccountBundleError::MissingSeed(_))
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn exported_account_signs_in_another_client() -> anyhow::Result<()> {
+    let dir = temp_dir()?;
+    let (mut source, source_keystore) = mock_client(&dir.path().join("source")).await?;
+    let (mut target, target_keystore) = mock_client(&dir.path().join("target")).await?;
+
+    let key_pair = SecretKey::new();
+    let (faucet, seed) = FaucetSpec::default().build_account([9; 32], key_pair.public_key())?;
+    source.add_account(&faucet, Some(seed), false).await?;
+    source_keystore.add_key(&AuthSecretKey::RpoFalcon512(key_pair))?;
+
+    // Export through a file, picking the faucet's key out of the source keystore
+    let record = source.try_get_account(faucet.id()).await?;
+    let bundle = AccountBundle::from_record(&record, |pub_key| source_keystore.get_key(pub_key))?;
+    assert_eq!(bundle.secret_keys.len(), 1);
+    let path = dir.path().join("faucet.bundle");
+    bundle.write(&path)?;
+
+    for key in import_bundle(&mut target, AccountBundle::read(&path)?, false).await? {
+        target_keystore.add_key(&key)?;
+    }
+    let imported = target.try_get_account(faucet.id()).await?;
+    assert_eq!(imported.account().commitment(), faucet.commitment());
+    assert_eq!(imported.seed(), Some(&seed));
+
+    // The target client signs for the faucet with the imported key
+    let transaction_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        FungibleAsset::new(faucet.id(), 100)?,
+        ACCOUNT_ID_SENDER.try_into()?,
+        NoteType::Public,
+        target.rng(),
+    )?;
+    let tx_execution_result = target
+        .new_transaction(faucet.id(), transaction_request)
+        .await?;
+    assert_eq!(tx_execution_result.created_notes().num_notes(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn exports_fail_without_the_auth_key() -> anyhow::Result<()> {
+    let dir = temp_dir()?;
+    let (mut client, keystore) = mock_client(dir.path()).await?;
+
+    let key_pair = SecretKey::new();
+    let (faucet, seed) = FaucetSpec::default().build_account([9; 32], key_pair.public_key())?;
+    client.add_account(&faucet, Some(seed), false).await?;
+    let record = client.try_get_account(faucet.id()).await?;
+
+    // The keystore does not hold the faucet's key
+    let pub_key: Word = key_pair.public_key().into();
+    match AccountBundle::from_record(&record, |pub_key| keystore.get_key(pub_key)) {
+        Err(AccountBundleError::MissingSecretKey {
+            account_id,
+            pub_key: missing,
+        }) => {
+            assert_eq!(account_id, faucet.id());
+            assert_eq!(missing, pub_key);
+        }
+        other => panic!("the export did not fail: {other:?}"),
+    }
+
+    // Keystore errors are returned instead of being treated as a missing key
+    keystore.add_key(&AuthSecretKey::RpoFalcon512(key_pair))?;
+    for entry in std::fs::read_dir(dir.path().join("keystore"))? {
+        std::fs::write(entry?.path(), "not a key")?;
+    }
+    assert!(matches!(
+        AccountBundle::from_record(&record, |pub_key| keystore.get_key(pub_key)),
+        Err(AccountBundleError::KeyStore(_))
+    ));
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use miden_client::{
+    account::AccountId,
+    testing::account_id::{ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET, ACCOUNT_ID_SENDER},
+};
+use miden_objects::account::NetworkId;
+use rust_client::address_book::{AddressBook, AddressBookError, Role};
+
+fn ids() -> anyhow::Result<(AccountId, AccountId)> {
+    Ok((
+        ACCOUNT_ID_SENDER.try_into()?,
+        ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET.try_into()?,
+    ))
+}
+
+fn temp_path() -> std::path::PathBuf {
+    std::env::temp_dir()
+        .join(format!("address-book-{}", rand::random::<u64>()))
+        .join("address_book.json")
+}
+
+#[test]
+fn names_persist_and_resolve_alongside_ids() -> anyhow::Result<()> {
+    let (alice, faucet) = ids()?;
+    let path = temp_path();
+    let mut book = AddressBook::open(&path, NetworkId::Testnet)?;
+    book.insert("alice", alice, Role::Wallet)?;
+    book.insert("mid-faucet", faucet, Role::Faucet)?;
+    book.save()?;
+
+    let book = AddressBook::open(&path, NetworkId::Testnet)?;
+    assert_eq!(book.resolve("alice")?, alice);
+    assert_eq!(book.resolve_as("mid-faucet", Role::Faucet)?, faucet);
+    assert_eq!(book.resolve(&alice.to_bech32(NetworkId::Testnet))?, alice);
+    assert_eq!(book.resolve(&alice.to_hex())?, alice);
+    assert_eq!(book.display(faucet), "mid-faucet");
+
+    assert!(matches!(
+        book.resolve_as("alice", Role::Faucet),
+        Err(AddressBookError::WrongRole { .. })
+    ));
+    assert!(matches!(
+        book.resolve("bob"),
+        Err(AddressBookError::UnknownName(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn ids_and_books_of_other_networks_are_rejected() -> anyhow::Result<()> {
+    let (alice, _) = ids()?;
+    let path = temp_path();
+    let mut book = AddressBook::open(&path, NetworkId::Testnet)?;
+    assert!(matches!(
+        book.resolve(&alice.to_bech32(NetworkId::Mainnet)),
+        Err(AddressBookError::WrongNetwork { expected, found })
+            if *expected == NetworkId::Testnet && *found == NetworkId::Mainnet
+    ));
+
+    book.insert("alice", alice, Role::Wallet)?;
+    book.save()?;
+    assert!(matches!(
+        AddressBook::open(&path, NetworkId::Devnet),
+        Err(AddressBookError::WrongNetwork { .. })
+    ));
+    Ok(())
+}
+
+#[test]
+fn names_must_be_unique_and_not_look_like_ids() -> anyhow::Result<()> {
+    let (alice, faucet) = ids()?;
+    let mut book = AddressBook::new(temp_path(), NetworkId::Testnet);
+    book.insert("alice", alice, Role::Wallet)?;
+
+    assert!(matches!(
+        book.insert("alice", faucet, Role::Faucet),
+        Err(AddressBookError::DuplicateName(_))
+    ));
+    for name in ["Alice", "1alice", "alice bob", "mtst1alice", ""] {
+        assert!(
+            matches!(
+                book.insert(name, faucet, Role::Faucet),
+                Err(AddressBookError::InvalidName(_))
+            ),
+            "{name} was accepted"
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use std::time::Duration;
+
+use miden_client::{note::NoteType, transaction::TransactionId, Felt};
+use rust_client::bench::{BenchConfig, BenchReport, NoteMix, Percentiles, StepRecord};
+
+fn step(chain: usize, step: usize, total_ms: f64, block_num: Option<u32>) -> StepRecord {
+    StepRecord {
+        chain,
+        step,
+        private: step % 2 == 1,
+        build_ms: 1.0,
+        execute_ms: 2.0,
+        prove_ms: 3.0,
+        submit_ms: 4.0,
+        total_ms,
+        consume_tx: TransactionId::from([Felt::new((chain * 10 + step) as u64); 4]),
+        block_num,
+    }
+}
+
+fn config() -> BenchConfig {
+    BenchConfig {
+        label: "test".into(),
+        chains: 2,
+        chain_length: 2,
+        note_mix: NoteMix::new(0.5),
+        amount: 10,
+    }
+}
+
+#[test]
+fn percentiles_use_the_nearest_rank() {
+    let percentiles = Percentiles::from_samples((1..=100).rev().map(f64::from));
+    assert_eq!(percentiles.min, 1.0);
+    assert_eq!(percentiles.p50, 50.0);
+    assert_eq!(percentiles.p90, 90.0);
+    assert_eq!(percentiles.p99, 99.0);
+    assert_eq!(percentiles.max, 100.0);
+    assert_eq!(percentiles.mean, 50.5);
+
+    let single = Percentiles::from_samples([7.0]);
+    assert_eq!(
+        (single.min, single.p50, single.p99, single.max),
+        (7.0, 7.0, 7.0, 7.0)
+    );
+
+    let empty = Percentiles::from_samples([]);
+    assert_eq!(
+        (empty.min, empty.p50, empty.max, empty.mean),
+        (0.0, 0.0, 0.0, 0.0)
+    );
+}
+
+#[test]
+fn note_mix_spreads_private_notes_evenly() {
+    let types = |ratio: f64, len: usize| -> Vec<bool> {
+        let mix = NoteMix::new(ratio);
+        (0..len)
+            .map(|step| mix.note_type(step) == NoteType::Private)
+            .collect()
+    };
+
+    assert_eq!(types(0.0, 4), [false; 4]);
+    assert_eq!(types(1.0, 4), [true; 4]);
+    assert_eq!(types(0.5, 4), [false, true, false, true]);
+    assert_eq!(types(0.25, 8).iter().filter(|private| **private).count(), 2);
+
+    // Ratios outside [0, 1] are clamped
+    assert_eq!(NoteMix::new(2.0).private_ratio, 1.0);
+    assert_eq!(NoteMix::new(-1.0).private_ratio, 0.0);
+}
+
+#[test]
+fn reports_summarize_steps_as_json_and_csv() -> anyhow::Result<()> {
+    let steps = vec![
+        step(0, 0, 10.0, Some(12)),
+        step(0, 1, 20.0, Some(14)),
+        step(1, 0, 30.0, Some(12)),
+        step(1, 1, 40.0, None),
+    ];
+    let report = BenchReport::new(config(), Duration::from_secs(2), steps);
+
+    assert_eq!(report.elapsed_ms, 2000.0);
+    assert_eq!(report.transfers_per_second, 2.0);
+    // Only committed transactions land in a block
+    assert_eq!(report.landed_blocks, [12, 14]);
+    assert_eq!(report.summary.total.p50, 20.0);
+    assert_eq!(report.summary.prove.max, 3.0);
+
+    let csv = report.to_csv();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 5);
+    assert_eq!(
+        lines[0],
+        "chain,step,private,build_ms,execute_ms,prove_ms,submit_ms,total_ms,consume_tx,block_num"
+    );
+    let consume_tx = report.steps[1].consume_tx.to_hex();
+    assert_eq!(
+        lines[2],
+        format!("0,1,true,1.000,2.000,3.000,4.000,20.000,{consume_tx},14")
+    );
+    assert!(lines[4].ends_with(','));
+
+    let json: serde_json::Value = serde_json::from_str(&report.to_json())?;
+    assert_eq!(json["config"]["note_mix"]["private_ratio"], 0.5);
+    assert_eq!(json["landed_blocks"], serde_json::json!([12, 14]));
+    assert_eq!(json["steps"][1]["consume_tx"], consume_tx);
+    assert_eq!(json["steps"][3]["block_num"], serde_json::Value::Null);
+    assert_eq!(json["summary"]["total"]["max"], 40.0);
+    Ok(())
+}
@@ -0,0 +1,156 @@
+use std::sync::Arc;
+
+use miden_client::{
+    account::{Account, AccountId},
+    asset::FungibleAsset,
+    auth::AuthSecretKey,
+    builder::ClientBuilder,
+    crypto::SecretKey,
+    keystore::FilesystemKeyStore,
+    note::{Note, NoteType},
+    testing::{account_id::ACCOUNT_ID_SENDER, mock::MockRpcApi},
+    transaction::{OutputNote, TransactionRequestBuilder},
+    Felt, Word, ZERO,
+};
+use miden_lib::account::interface::AccountInterface;
+use miden_testing::{Auth, MockChain};
+use rand::rngs::StdRng;
+use rust_client::{
+    faucet::{reconcile_supply, FaucetInfo, FaucetSpec, SupplyDiscrepancy, SupplyReport},
+    notes::BurnNote,
+};
+
+fn serial_num(seed: u64) -> Word {
+    [Felt::new(seed), ZERO, ZERO, ZERO]
+}
+
+/// A chain with a `MID` faucet that issued 100 tokens, all held by a wallet, and the committed
+/// burn note the wallet sent for `burned` of them.
+async fn burn_setup(burned: u64) -> anyhow::Result<(MockChain, Account, Note)> {
+    let mut chain = MockChain::new();
+    let faucet = chain.add_pending_existing_faucet(Auth::BasicAuth, "MID", 1000, Some(100))?;
+    let wallet = chain.add_pending_existing_wallet(Auth::BasicAuth, vec![faucet.mint(100)]);
+    chain.prove_next_block()?;
+
+    let burn_note = BurnNote::new(FungibleAsset::new(faucet.id(), burned)?).build(
+        wallet.id(),
+        NoteType::Public,
+        serial_num(1),
+    )?;
+    let send_script = AccountInterface::from(&wallet).build_send_notes_script(
+        &[burn_note.clone().into()],
+        None,
+        false,
+    )?;
+    let executed = chain
+        .build_tx_context(wallet.id(), &[], &[])?
+        .tx_script(send_script)
+        .extend_expected_output_notes(vec![OutputNote::Full(burn_note.clone())])
+        .build()?
+        .execute()
+        .await?;
+    let wallet = chain.add_pending_executed_transaction(&executed)?;
+    chain.prove_next_block()?;
+    assert_eq!(wallet.vault().get_balance(faucet.id())?, 100 - burned);
+
+    Ok((chain, faucet.account().clone(), burn_note))
+}
+
+#[tokio::test]
+async fn faucet_burns_the_tokens_of_a_burn_note() -> anyhow::Result<()> {
+    let (mut chain, faucet, burn_note) = burn_setup(30).await?;
+    assert_eq!(FaucetInfo::from_account(&faucet)?.issued, 100);
+
+    let executed = chain
+        .build_tx_context(faucet.id(), &[burn_note.id()], &[])?
+        .build()?
+        .execute()
+        .await?;
+    let faucet = chain.add_pending_executed_transaction(&executed)?;
+    chain.prove_next_block()?;
+
+    assert_eq!(FaucetInfo::from_account(&faucet)?.issued, 70);
+    Ok(())
+}
+
+#[tokio::test]
+async fn only_the_issuing_faucet_can_consume_a_burn_note() -> anyhow::Result<()> {
+    let (mut chain, _, burn_note) = burn_setup(30).await?;
+    let other_faucet = chain.add_pending_existing_faucet(Auth::BasicAuth, "OTH", 1000, Some(0))?;
+    let other_wallet = chain.add_pending_existing_wallet(Auth::BasicAuth, vec![]);
+    chain.prove_next_block()?;
+
+    for account_id in [other_faucet.id(), other_wallet.id()] {
+        let result = chain
+            .build_tx_context(account_id, &[burn_note.id()], &[])?
+            .build()?
+            .execute()
+            .await;
+        assert!(result.is_err());
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn reconcile_supply_counts_unconsumed_notes() -> anyhow::Result<()> {
+    let dir = std::env::temp_dir().join(format!("burn-{}", rand::random::<u64>()));
+    let keystore_dir = dir.join("keystore");
+    std::fs::create_dir_all(&keystore_dir)?;
+    let mut client = ClientBuilder::new()
+        .rpc(Arc::new(MockRpcApi::new()))
+        .sqlite_store(dir.join("store.sqlite3").to_str().unwrap())
+        .filesystem_keystore(keystore_dir.to_str().unwrap())
+        .in_debug_mode(true)
+        .build()
+        .await?;
+    client.sync_state().await?;
+
+    let key_pair = SecretKey::new();
+    let (faucet, seed) =
+        FaucetSpec::new("MID", 2, 1_000_000)?.build_account([3; 32], key_pair.public_key())?;
+    client.add_account(&faucet, Some(seed), false).await?;
+    FilesystemKeyStore::<StdRng>::new(keystore_dir)?
+        .add_key(&AuthSecretKey::RpoFalcon512(key_pair))?;
+
+    // The minted note is not consumed yet, so the issued tokens are all in notes
+    let alice = AccountId::try_from(ACCOUNT_ID_SENDER)?;
+    let transaction_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        FungibleAsset::new(faucet.id(), 1000)?,
+        alice,
+        NoteType::Public,
+        client.rng(),
+    )?;
+    let tx_execution_result = client
+        .new_transaction(faucet.id(), transaction_request)
+        .await?;
+    client.submit_transaction(tx_execution_result).await?;
+
+    let report = reconcile_supply(&client, faucet.id()).await?;
+    assert_eq!(report.issued, 1000);
+    assert_eq!(report.in_notes, 1000);
+    assert!(report.balances.is_empty());
+    assert_eq!(report.discrepancy(), None);
+
+    Ok(())
+}
+
+#[test]
+fn supply_report_flags_discrepancies() {
+    let faucet_id = AccountId::try_from(ACCOUNT_ID_SENDER).unwrap();
+    let report = |issued| SupplyReport {
+        faucet_id,
+        issued,
+        balances: vec![(faucet_id, 60)],
+        in_notes: 30,
+    };
+
+    assert_eq!(report(90).discrepancy(), None);
+    assert_eq!(
+        report(100).discrepancy(),
+        Some(SupplyDiscrepancy::Untracked(10))
+    );
+    assert_eq!(
+        report(80).discrepancy(),
+        Some(SupplyDiscrepancy::Excess(10))
+    );
+}
@@ -0,0 +1,31 @@
+//! Helpers shared by the integration tests.
+//!
+//! Every test binary compiles this module on its own and uses only part of it.
+#![allow(dead_code)]
+
+use std::{path::Path, sync::Arc};
+
+use miden_client::{
+    builder::ClientBuilder, keystore::FilesystemKeyStore, testing::mock::MockRpcApi, Client,
+};
+use rand::rngs::StdRng;
+use tempfile::TempDir;
+
+/// A fresh temporary directory, deleted with everything in it, keys included, when dropped.
+pub fn temp_dir() -> anyhow::Result<TempDir> {
+    Ok(tempfile::Builder::new().prefix("rust-client-").tempdir()?)
+}
+
+/// A synced mock client with its own store and keystore in `dir`.
+pub async fn mock_client(dir: &Path) -> anyhow::Result<(Client, FilesystemKeyStore<StdRng>)> {
+    let keystore = FilesystemKeyStore::new(dir.join("keystore"))?;
+    let mut client = ClientBuilder::new()
+        .rpc(Arc::new(MockRpcApi::new()))
+        .sqlite_store(dir.join("store.sqlite3").to_str().unwrap())
+        .authenticator(Arc::new(keystore.clone()))
+        .in_debug_mode(true)
+        .build()
+        .await?;
+    client.sync_state().await?;
+    Ok((client, keystore))
+}
@@ -0,0 +1,118 @@
+use miden_client::{
+    account::AccountId,
+    asset::{Asset, FungibleAsset},
+    note::{NoteAssets, NoteId},
+    testing::account_id::{
+        ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET, ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1,
+        ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_2,
+    },
+    Felt,
+};
+use miden_objects::MAX_INPUT_NOTES_PER_TX;
+use rust_client::consolidation::ConsolidationPolicy;
+
+fn faucet(raw: u128) -> AccountId {
+    raw.try_into().unwrap()
+}
+
+/// Note assets holding `amount` tokens of every faucet in `faucets`.
+fn assets(faucets: &[u128], amount: u64) -> anyhow::Result<NoteAssets> {
+    let assets = faucets
+        .iter()
+        .map(|raw| Ok(Asset::from(FungibleAsset::new(faucet(*raw), amount)?)))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(NoteAssets::new(assets)?)
+}
+
+fn note_ids(count: u64) -> Vec<NoteId> {
+    (0..count)
+        .map(|i| NoteId::from([Felt::new(i); 4]))
+        .collect()
+}
+
+#[test]
+fn note_value_counts_the_selected_faucet() -> anyhow::Result<()> {
+    let both = assets(
+        &[
+            ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET,
+            ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1,
+        ],
+        100,
+    )?;
+
+    assert_eq!(ConsolidationPolicy::all().note_value(&both), 200);
+    let policy = ConsolidationPolicy::all().for_faucet(faucet(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET));
+    assert_eq!(policy.note_value(&both), 100);
+    assert_eq!(policy.note_value(&NoteAssets::default()), 0);
+    Ok(())
+}
+
+#[test]
+fn note_value_saturates_instead_of_overflowing() -> anyhow::Result<()> {
+    // Three faucets at the asset limit add up to more than u64::MAX
+    let large = assets(
+        &[
+            ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET,
+            ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1,
+            ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_2,
+        ],
+        FungibleAsset::MAX_AMOUNT,
+    )?;
+
+    let policy = ConsolidationPolicy::all().with_min_amount(u64::MAX);
+    assert_eq!(policy.note_value(&large), u64::MAX);
+    assert!(policy.accepts(&large));
+    Ok(())
+}
+
+#[test]
+fn policies_combine_the_faucet_and_the_minimum_amount() -> anyhow::Result<()> {
+    let faucet_a = faucet(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET);
+    let dust = assets(&[ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET], 5)?;
+    let payment = assets(&[ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET], 500)?;
+    let other = assets(&[ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1], 500)?;
+
+    let all = ConsolidationPolicy::default();
+    assert!(all.accepts(&dust) && all.accepts(&payment) && all.accepts(&other));
+    assert!(all.accepts(&NoteAssets::default()));
+
+    let policy = ConsolidationPolicy::all()
+        .for_faucet(faucet_a)
+        .with_min_amount(100);
+    assert!(!policy.accepts(&dust));
+    assert!(policy.accepts(&payment));
+    // Tokens of other faucets neither count towards the minimum nor make a note acceptable
+    assert!(!policy.accepts(&other));
+    assert!(!ConsolidationPolicy::all()
+        .for_faucet(faucet_a)
+        .accepts(&NoteAssets::default()));
+    Ok(())
+}
+
+#[test]
+fn batches_respect_the_notes_per_transaction() {
+    let ids = note_ids(7);
+
+    let batches: Vec<usize> = ConsolidationPolicy::all()
+        .with_max_notes_per_tx(3)
+        .batches(&ids)
+        .map(<[NoteId]>::len)
+        .collect();
+    assert_eq!(batches, [3, 3, 1]);
+    assert_eq!(ConsolidationPolicy::all().batches(&ids).count(), 1);
+    assert_eq!(ConsolidationPolicy::all().batches(&[]).count(), 0);
+
+    // The limit is clamped between one note and the kernel limit
+    assert_eq!(
+        ConsolidationPolicy::all()
+            .with_max_notes_per_tx(0)
+            .max_notes_per_tx(),
+        1
+    );
+    assert_eq!(
+        ConsolidationPolicy::all()
+            .with_max_notes_per_tx(usize::MAX)
+            .max_notes_per_tx(),
+        MAX_INPUT_NOTES_PER_TX
+    );
+}
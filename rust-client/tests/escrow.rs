@@ -0,0 +1,258 @@
+use miden_client::{
+    account::Account,
+    asset::{Asset, FungibleAsset},
+    crypto::SecretKey,
+    note::{Note, NoteType},
+    transaction::{ExecutedTransaction, OutputNote, TransactionRequest, TransactionScriptTemplate},
+    Felt, Word,
+};
+use miden_objects::Hasher;
+use miden_testing::{Auth, MockChain};
+use rust_client::escrow::{sign_release, EscrowPayout, EscrowTerms};
+
+/// Number of blocks after which the depositor can be refunded.
+const TIMEOUT_BLOCKS: u32 = 5;
+
+struct Setup {
+    chain: MockChain,
+    terms: EscrowTerms,
+    arbiter: SecretKey,
+    escrow: Account,
+    faucet: Account,
+}
+
+/// A committed escrow holding 100 tokens, with fresh depositor, beneficiary and arbiter.
+fn setup() -> anyhow::Result<Setup> {
+    let mut chain = MockChain::new();
+    let faucet = chain.add_pending_new_faucet(Auth::BasicAuth, "MID", 1_000_000)?;
+    let depositor = chain.add_pending_existing_wallet(Auth::BasicAuth, vec![]);
+    let beneficiary = chain.add_pending_existing_wallet(Auth::BasicAuth, vec![]);
+    chain.prove_next_block()?;
+
+    let arbiter = SecretKey::new();
+    let terms = EscrowTerms::new(
+        arbiter.public_key().into(),
+        depositor.id(),
+        beneficiary.id(),
+        chain.latest_block_header().block_num() + TIMEOUT_BLOCKS,
+    );
+    let escrow = terms
+        .account_builder([7; 32])?
+        .with_assets([faucet.mint(100)])
+        .build_existing()?;
+    chain.add_pending_account(escrow.clone());
+    chain.prove_next_block()?;
+
+    Ok(Setup {
+        chain,
+        terms,
+        arbiter,
+        escrow,
+        faucet: faucet.account().clone(),
+    })
+}
+
+fn payout(faucet: &Account, amount: u64, serial: u64) -> anyhow::Result<EscrowPayout> {
+    Ok(EscrowPayout::new(
+        FungibleAsset::new(faucet.id(), amount)?.into(),
+        [Felt::new(serial); 4],
+    ))
+}
+
+/// Executes `request` against `escrow`, which pays out the `expected` note, returning the
+/// transaction and the updated escrow.
+async fn execute(
+    chain: &MockChain,
+    escrow: &Account,
+    request: TransactionRequest,
+    expected: &Note,
+) -> anyhow::Result<(ExecutedTransaction, Account)> {
+    let Some(TransactionScriptTemplate::CustomScript(script)) = request.script_template() else {
+        anyhow::bail!("escrow requests run a custom script");
+    };
+    let advice = request
+        .advice_map()
+        .clone()
+        .into_iter()
+        .map(|(key, value)| (key.into(), value));
+
+    let executed = chain
+        .build_tx_context(escrow.clone(), &[], &[])?
+        .tx_script(script.clone())
+        .extend_advice_map(advice)
+        .extend_expected_output_notes(vec![OutputNote::Full(expected.clone())])
+        .build()?
+        .execute()
+        .await?;
+    let mut escrow = escrow.clone();
+    escrow.apply_delta(executed.account_delta())?;
+    Ok((executed, escrow))
+}
+
+fn balance(escrow: &Account, faucet: &Account) -> anyhow::Result<u64> {
+    Ok(escrow.vault().get_balance(faucet.id())?)
+}
+
+#[tokio::test]
+async fn signed_releases_pay_the_beneficiary() -> anyhow::Result<()> {
+    let Setup {
+        chain,
+        terms,
+        arbiter,
+        escrow,
+        faucet,
+    } = setup()?;
+
+    let release = payout(&faucet, 60, 1)?;
+    let signature = sign_release(&arbiter, escrow.id(), &release)?;
+    let request = terms.release_request(&[release], vec![signature])?;
+    let expected = terms.payout_note(escrow.id(), terms.beneficiary, &release)?;
+    let (executed, escrow_after) = execute(&chain, &escrow, request, &expected).await?;
+
+    let created: Vec<_> = executed
+        .output_notes()
+        .iter()
+        .map(|note| note.id())
+        .collect();
+    assert_eq!(created, [expected.id()]);
+    assert_eq!(
+        expected.assets().iter().collect::<Vec<_>>(),
+        [&release.asset]
+    );
+    assert_eq!(balance(&escrow_after, &faucet)?, 40);
+    Ok(())
+}
+
+#[tokio::test]
+async fn releases_signed_by_someone_else_are_rejected() -> anyhow::Result<()> {
+    let Setup {
+        chain,
+        terms,
+        escrow,
+        faucet,
+        ..
+    } = setup()?;
+
+    let release = payout(&faucet, 60, 1)?;
+    let (_, forged) = sign_release(&SecretKey::new(), escrow.id(), &release)?;
+    // File the signature under the arbiter's key, so the escrow has to verify it
+    let key = Hasher::merge(&[
+        terms.arbiter.into(),
+        release.release_message(escrow.id()).into(),
+    ]);
+    let request = terms.release_request(&[release], vec![(key, forged)])?;
+    let expected = terms.payout_note(escrow.id(), terms.beneficiary, &release)?;
+    assert!(execute(&chain, &escrow, request, &expected).await.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn releases_cannot_be_replayed() -> anyhow::Result<()> {
+    let Setup {
+        chain,
+        terms,
+        arbiter,
+        escrow,
+        faucet,
+    } = setup()?;
+
+    let release = payout(&faucet, 30, 1)?;
+    let signature = sign_release(&arbiter, escrow.id(), &release)?;
+    let request = terms.release_request(&[release], vec![signature.clone()])?;
+    let expected = terms.payout_note(escrow.id(), terms.beneficiary, &release)?;
+    let (_, escrow) = execute(&chain, &escrow, request, &expected).await?;
+
+    // The escrow still holds enough tokens, only the serial number was used already
+    assert_eq!(balance(&escrow, &faucet)?, 70);
+    let request = terms.release_request(&[release], vec![signature])?;
+    let err = execute(&chain, &escrow, request, &expected)
+        .await
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("already released"), "{err:#}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn new_deposits_do_not_void_pending_releases() -> anyhow::Result<()> {
+    let Setup {
+        mut chain,
+        terms,
+        arbiter,
+        escrow,
+        faucet,
+    } = setup()?;
+
+    let release = payout(&faucet, 60, 1)?;
+    let signature = sign_release(&arbiter, escrow.id(), &release)?;
+
+    // Anyone can send the escrow a deposit, which increments its nonce
+    let dust: Asset = FungibleAsset::new(faucet.id(), 1)?.into();
+    let note =
+        chain.add_pending_p2id_note(terms.depositor, escrow.id(), &[dust], NoteType::Public)?;
+    chain.prove_next_block()?;
+    let executed = chain
+        .build_tx_context(escrow.clone(), &[note.id()], &[])?
+        .build()?
+        .execute()
+        .await?;
+    let mut escrow_after_deposit = escrow.clone();
+    escrow_after_deposit.apply_delta(executed.account_delta())?;
+    assert!(escrow_after_deposit.nonce().as_int() > escrow.nonce().as_int());
+
+    let request = terms.release_request(&[release], vec![signature])?;
+    let expected = terms.payout_note(escrow.id(), terms.beneficiary, &release)?;
+    let (_, escrow) = execute(&chain, &escrow_after_deposit, request, &expected).await?;
+    assert_eq!(balance(&escrow, &faucet)?, 41);
+    Ok(())
+}
+
+#[tokio::test]
+async fn refunds_only_succeed_from_the_timeout_block_on() -> anyhow::Result<()> {
+    let Setup {
+        mut chain,
+        terms,
+        escrow,
+        faucet,
+        ..
+    } = setup()?;
+
+    let refund = payout(&faucet, 100, 2)?;
+    let expected = terms.payout_note(escrow.id(), terms.depositor, &refund)?;
+    assert!(chain.latest_block_header().block_num() < terms.timeout);
+    let request = terms.refund_request(&[refund])?;
+    let err = execute(&chain, &escrow, request, &expected)
+        .await
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("timeout block"), "{err:#}");
+
+    chain.prove_until_block(terms.timeout)?;
+    let request = terms.refund_request(&[refund])?;
+    let (executed, escrow_after) = execute(&chain, &escrow, request, &expected).await?;
+    let created: Vec<_> = executed
+        .output_notes()
+        .iter()
+        .map(|note| note.id())
+        .collect();
+    assert_eq!(created, [expected.id()]);
+    assert_eq!(balance(&escrow_after, &faucet)?, 0);
+    Ok(())
+}
+
+/// A release message depends on the escrow and the payout, but not on the escrow's state.
+#[test]
+fn release_messages_bind_the_escrow_and_the_payout() -> anyhow::Result<()> {
+    let Setup { escrow, faucet, .. } = setup()?;
+    let release = payout(&faucet, 60, 1)?;
+    let message: Word = release.release_message(escrow.id());
+
+    assert_ne!(
+        message,
+        payout(&faucet, 61, 1)?.release_message(escrow.id())
+    );
+    assert_ne!(
+        message,
+        payout(&faucet, 60, 2)?.release_message(escrow.id())
+    );
+    assert_ne!(message, release.release_message(faucet.id()));
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use rust_client::faucet::{format_amount, parse_amount, FaucetError};
+
+fn rejected(input: &str, decimals: u8, reason: &str) {
+    match parse_amount(input, "MID", decimals) {
+        Err(FaucetError::InvalidAmount(found)) => assert!(found.contains(reason), "{found}"),
+        other => panic!("{input} was not rejected: {other:?}"),
+    }
+}
+
+#[test]
+fn amounts_parse_into_base_units() -> anyhow::Result<()> {
+    assert_eq!(parse_amount("12.5 MID", "MID", 8)?, 1_250_000_000);
+    assert_eq!(parse_amount("12.5 mid", "MID", 8)?, 1_250_000_000);
+    assert_eq!(parse_amount("12", "MID", 8)?, 1_200_000_000);
+    assert_eq!(parse_amount(".5", "MID", 2)?, 50);
+    assert_eq!(parse_amount("7.", "MID", 0)?, 7);
+    assert_eq!(parse_amount("0.00000001", "MID", 8)?, 1);
+
+    rejected("", 8, "empty amount");
+    rejected(".", 8, "missing digits");
+    rejected("-1", 8, "not a decimal number");
+    rejected("1e3", 8, "not a decimal number");
+    rejected("12.5 BTC", 8, "expected MID, got BTC");
+    rejected("12.5 MID extra", 8, "expected a number and a token symbol");
+    Ok(())
+}
+
+#[test]
+fn extra_fraction_digits_are_rejected_instead_of_rounded() {
+    rejected("0.000000001", 8, "the token only has 8 decimals");
+    rejected("1.99", 1, "the token only has 1 decimals");
+    rejected("1.5", 0, "the token only has 0 decimals");
+    // Trailing zeros still count as digits the token cannot hold
+    rejected("1.00", 1, "the token only has 1 decimals");
+}
+
+#[test]
+fn amounts_beyond_the_asset_limit_are_rejected() -> anyhow::Result<()> {
+    // FungibleAsset::MAX_AMOUNT is 2^63 - 1
+    assert_eq!(
+        parse_amount("92233720368.54775807", "MID", 8)?,
+        9_223_372_036_854_775_807
+    );
+    rejected("92233720368.54775808", 8, "too large");
+    rejected("100000000000000000000", 0, "too large");
+    Ok(())
+}
+
+#[test]
+fn amounts_format_without_trailing_zeros() {
+    assert_eq!(format_amount(1_250_000_000, 8), "12.5");
+    assert_eq!(format_amount(1_200_000_000, 8), "12");
+    assert_eq!(format_amount(1, 8), "0.00000001");
+    assert_eq!(format_amount(0, 8), "0");
+    assert_eq!(format_amount(42, 0), "42");
+}
+
+#[test]
+fn formatting_supports_more_decimals_than_fit_in_u64() {
+    assert_eq!(format_amount(u64::MAX, 19), "1.8446744073709551615");
+    assert_eq!(format_amount(u64::MAX, 20), "0.18446744073709551615");
+    assert_eq!(format_amount(5, 255).len(), 2 + 255);
+    assert_eq!(format_amount(0, 255), "0");
+}
+
+#[test]
+fn formatted_amounts_parse_back_to_the_same_base_units() -> anyhow::Result<()> {
+    for decimals in [0, 1, 6, 8, 12, 19, 20, 30] {
+        for amount in [0, 1, 10, 123_456_789, 1_000_000_000_000, (1 << 63) - 1] {
+            let formatted = format_amount(amount, decimals);
+            assert_eq!(
+                parse_amount(&formatted, "MID", decimals)?,
+                amount,
+                "{formatted} with {decimals} decimals"
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,217 @@
+mod common;
+
+use std::{path::Path, time::Duration};
+
+use miden_client::{
+    account::AccountId,
+    auth::AuthSecretKey,
+    crypto::SecretKey,
+    note::NoteType,
+    testing::account_id::{
+        ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET, ACCOUNT_ID_REGULAR_PRIVATE_ACCOUNT_UPDATABLE_CODE,
+        ACCOUNT_ID_SENDER,
+    },
+    Client,
+};
+use rust_client::{
+    faucet::FaucetSpec,
+    faucet_service::{
+        read_log, request_mint, FaucetService, FaucetServiceConfig, MintRequest, RateLimit,
+        ServiceError,
+    },
+    note_file::NoteExport,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+use common::{mock_client, temp_dir};
+
+/// A client on a mock node with its store and keystore in `dir`, tracking a new `MID` faucet
+/// with 2 decimals and a max supply of 10,000 MID.
+async fn faucet_client(dir: &Path) -> anyhow::Result<(Client, AccountId)> {
+    let (mut client, keystore) = mock_client(dir).await?;
+
+    let key_pair = SecretKey::new();
+    let (faucet, seed) =
+        FaucetSpec::new("MID", 2, 1_000_000)?.build_account([3; 32], key_pair.public_key())?;
+    client.add_account(&faucet, Some(seed), false).await?;
+    keystore.add_key(&AuthSecretKey::RpoFalcon512(key_pair))?;
+
+    Ok((client, faucet.id()))
+}
+
+fn alice() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_SENDER).unwrap()
+}
+
+fn bob() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_REGULAR_PRIVATE_ACCOUNT_UPDATABLE_CODE).unwrap()
+}
+
+#[tokio::test]
+async fn faucet_service_mints_public_and_private_notes_over_http() -> anyhow::Result<()> {
+    let dir = temp_dir()?;
+    let (client, faucet_id) = faucet_client(dir.path()).await?;
+    let config = FaucetServiceConfig::new(dir.path().join("requests.jsonl"));
+    let log_path = config.log_path.clone();
+    let service = FaucetService::new(client, faucet_id, config)?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    // The service runs on this task, so the requests are raced against it
+    let serve = service.serve(listener);
+    tokio::pin!(serve);
+
+    let request = MintRequest::new(alice(), "12.5 MID");
+    let public = tokio::select! {
+        _ = &mut serve => unreachable!("the service stopped"),
+        response = request_mint(addr, &request) => response?,
+    };
+    assert_eq!(public.amount, 1250);
+    assert_eq!(public.note, None);
+
+    let request = MintRequest::new(alice(), "0.01").with_note_type(NoteType::Private);
+    let private = tokio::select! {
+        _ = &mut serve => unreachable!("the service stopped"),
+        response = request_mint(addr, &request) => response?,
+    };
+    assert_eq!(private.amount, 1);
+    let note = NoteExport::from_text(private.note.as_ref().unwrap())?.note;
+    assert_eq!(note.id().to_hex(), private.note_id);
+    assert_eq!(note.metadata().note_type(), NoteType::Private);
+
+    // Amounts beyond the token's decimals never reach the faucet
+    let request = MintRequest::new(bob(), "0.001 MID");
+    let rejected = tokio::select! {
+        _ = &mut serve => unreachable!("the service stopped"),
+        response = request_mint(addr, &request) => response,
+    };
+    assert!(matches!(
+        rejected,
+        Err(ServiceError::Rejected { status: 400, .. })
+    ));
+
+    let log = read_log(&log_path)?;
+    assert_eq!(log.len(), 3);
+    assert_eq!(log[0].response.as_ref(), Some(&public));
+    assert_eq!(log[1].response.as_ref(), Some(&private));
+    assert!(log[2].error.as_ref().unwrap().contains("decimals"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn faucet_service_enforces_rate_limits_across_restarts() -> anyhow::Result<()> {
+    let dir = temp_dir()?;
+    let hour = Duration::from_secs(60 * 60);
+    let config = FaucetServiceConfig::new(dir.path().join("requests.jsonl"))
+        .with_per_account_limit(RateLimit::new(1, hour))
+        .with_global_limit(RateLimit::new(2, hour));
+
+    let (client, faucet_id) = faucet_client(&dir.path().join("first")).await?;
+    let mut service = FaucetService::new(client, faucet_id, config.clone())?;
+
+    service.mint(MintRequest::new(alice(), "1")).await?;
+    assert!(matches!(
+        service.mint(MintRequest::new(alice(), "1")).await,
+        Err(ServiceError::RateLimited { retry_after }) if retry_after > 0
+    ));
+    service.mint(MintRequest::new(bob(), "1")).await?;
+
+    // A restarted service restores the limits from the request log
+    let (client, faucet_id) = faucet_client(&dir.path().join("restarted")).await?;
+    let mut service = FaucetService::new(client, faucet_id, config)?;
+    let carol = AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET)?;
+    assert!(matches!(
+        service.mint(MintRequest::new(carol, "1")).await,
+        Err(ServiceError::RateLimited { .. })
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn faucet_service_rejects_mints_beyond_remaining_supply() -> anyhow::Result<()> {
+    let dir = temp_dir()?;
+    let (client, faucet_id) = faucet_client(dir.path()).await?;
+    let config = FaucetServiceConfig::new(dir.path().join("requests.jsonl"));
+    let mut service = FaucetService::new(client, faucet_id, config)?;
+
+    service.mint(MintRequest::new(alice(), "9999 MID")).await?;
+    let err = service
+        .mint(MintRequest::new(bob(), "2 MID"))
+        .await
+        .unwrap_err();
+    assert_eq!(err.status(), 400);
+    assert!(err.to_string().contains("can only issue 100 more"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn faucet_service_answers_while_another_connection_stalls() -> anyhow::Result<()> {
+    let dir = temp_dir()?;
+    let (client, faucet_id) = faucet_client(dir.path()).await?;
+    let config = FaucetServiceConfig::new(dir.path().join("requests.jsonl"));
+    let service = FaucetService::new(client, faucet_id, config)?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    let serve = service.serve(listener);
+    tokio::pin!(serve);
+
+    // This client never finishes its request, which must not hold up the next one
+    let mut stalled = TcpStream::connect(addr).await?;
+    stalled.write_all(b"POST /mint HTTP/1.1\r\n").await?;
+
+    let answer = async {
+        let mut stream = TcpStream::connect(addr).await?;
+        stream.write_all(b"GET /status HTTP/1.1\r\n\r\n").await?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await?;
+        anyhow::Ok(response)
+    };
+    let response = tokio::select! {
+        _ = &mut serve => unreachable!("the service stopped"),
+        response = tokio::time::timeout(Duration::from_secs(5), answer) => {
+            response.expect("the request was answered before the stalled one timed out")?
+        }
+    };
+    assert!(response.starts_with("HTTP/1.1 404"), "{response}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn faucet_service_returns_mints_whose_log_entry_failed() -> anyhow::Result<()> {
+    let dir = temp_dir()?;
+    let (client, faucet_id) = faucet_client(dir.path()).await?;
+    // The log's directory does not exist, so appending to it fails
+    let log_path = dir.path().join("missing").join("requests.jsonl");
+    let config = FaucetServiceConfig::new(&log_path);
+    let mut service = FaucetService::new(client, faucet_id, config)?;
+
+    let response = service.mint(MintRequest::new(alice(), "1 MID")).await?;
+    assert_eq!(response.amount, 100);
+    assert!(!log_path.exists());
+    Ok(())
+}
+
+#[tokio::test]
+async fn faucet_service_forgets_accounts_whose_mints_left_the_window() -> anyhow::Result<()> {
+    let dir = temp_dir()?;
+    let config = FaucetServiceConfig::new(dir.path().join("requests.jsonl"))
+        .with_per_account_limit(RateLimit::new(1, Duration::from_secs(1)));
+    let (client, faucet_id) = faucet_client(dir.path()).await?;
+    let mut service = FaucetService::new(client, faucet_id, config)?;
+
+    service.mint(MintRequest::new(alice(), "1")).await?;
+    assert_eq!(service.rate_limited_accounts(), 1);
+
+    // The next request drops Alice, whose only mint left the window
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    service.mint(MintRequest::new(bob(), "1")).await?;
+    assert_eq!(service.rate_limited_accounts(), 1);
+    Ok(())
+}
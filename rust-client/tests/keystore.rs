@@ -0,0 +1,198 @@
+mod common;
+
+use std::sync::Arc;
+
+use miden_client::{
+    asset::FungibleAsset,
+    auth::AuthSecretKey,
+    builder::ClientBuilder,
+    crypto::SecretKey,
+    keystore::FilesystemKeyStore,
+    note::NoteType,
+    testing::{account_id::ACCOUNT_ID_SENDER, mock::MockRpcApi},
+    transaction::TransactionRequestBuilder,
+    utils::Serializable,
+    Word,
+};
+use rust_client::{
+    faucet::FaucetSpec,
+    keystore::{migrate_keystore, EncryptedKeyStore, KeystoreError, KEYSTORE_HEADER_FILE},
+};
+
+use common::temp_dir;
+
+fn public_key(key: &SecretKey) -> Word {
+    key.public_key().into()
+}
+
+#[test]
+fn encrypted_keystore_round_trips_keys_across_sessions() -> anyhow::Result<()> {
+    let temp = temp_dir()?;
+    let dir = temp.path().join("keystore");
+    let key = SecretKey::new();
+    let keystore = EncryptedKeyStore::create(&dir, "correct horse")?;
+    keystore.add_key(&AuthSecretKey::RpoFalcon512(key.clone()))?;
+
+    // The key file does not hold the key in plaintext
+    let key_hex = hex::encode(AuthSecretKey::RpoFalcon512(key.clone()).to_bytes());
+    for entry in std::fs::read_dir(&dir)? {
+        assert!(!std::fs::read_to_string(entry?.path())?.contains(&key_hex));
+    }
+
+    let keystore = EncryptedKeyStore::unlock(&dir, "correct horse")?;
+    let AuthSecretKey::RpoFalcon512(stored) = keystore.get_key(public_key(&key))?.unwrap();
+    assert_eq!(stored.to_bytes(), key.to_bytes());
+    assert_eq!(keystore.public_keys()?, vec![public_key(&key)]);
+    assert!(keystore.get_key(public_key(&SecretKey::new()))?.is_none());
+
+    assert!(matches!(
+        EncryptedKeyStore::unlock(&dir, "wrong horse"),
+        Err(KeystoreError::WrongPassword)
+    ));
+    Ok(())
+}
+
+#[test]
+fn migration_encrypts_a_plaintext_keystore_in_place() -> anyhow::Result<()> {
+    let temp = temp_dir()?;
+    let dir = temp.path().join("keystore");
+    let plaintext = FilesystemKeyStore::new(dir.clone())?;
+    let keys = [SecretKey::new(), SecretKey::new()];
+    for key in &keys {
+        plaintext.add_key(&AuthSecretKey::RpoFalcon512(key.clone()))?;
+    }
+
+    // A plaintext keystore cannot be opened as an encrypted one without migrating it
+    assert!(matches!(
+        EncryptedKeyStore::open(&dir, "hunter2"),
+        Err(KeystoreError::NotEmpty(_))
+    ));
+
+    assert_eq!(migrate_keystore(&dir, "hunter2")?, 2);
+    assert!(dir.join(KEYSTORE_HEADER_FILE).exists());
+    assert!(plaintext.get_key(public_key(&keys[0])).is_err());
+
+    let keystore = EncryptedKeyStore::open(&dir, "hunter2")?;
+    for key in &keys {
+        assert!(keystore.get_key(public_key(key))?.is_some());
+    }
+    assert!(matches!(
+        migrate_keystore(&dir, "hunter2"),
+        Err(KeystoreError::AlreadyEncrypted(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn failed_migrations_leave_the_plaintext_keystore_in_place() -> anyhow::Result<()> {
+    let temp = temp_dir()?;
+    let dir = temp.path().join("keystore");
+    let plaintext = FilesystemKeyStore::new(dir.clone())?;
+    let key = SecretKey::new();
+    plaintext.add_key(&AuthSecretKey::RpoFalcon512(key.clone()))?;
+    std::fs::write(dir.join("README"), "not a key")?;
+
+    // An unexpected directory stops the migration before anything is written
+    std::fs::create_dir(dir.join("backup"))?;
+    assert!(matches!(
+        migrate_keystore(&dir, "hunter2"),
+        Err(KeystoreError::Io(_))
+    ));
+    assert!(!dir.join(KEYSTORE_HEADER_FILE).exists());
+    assert!(plaintext.get_key(public_key(&key))?.is_some());
+    std::fs::remove_dir(dir.join("backup"))?;
+
+    // The output of an interrupted migration is discarded, the plaintext keys are still there
+    let staging = dir.with_file_name("keystore.migrating");
+    EncryptedKeyStore::create(&staging, "hunter2")?;
+    std::fs::write(staging.join("12345"), "partial")?;
+
+    assert_eq!(migrate_keystore(&dir, "hunter2")?, 1);
+    assert!(!staging.exists());
+    assert!(!dir.with_file_name("keystore.plaintext").exists());
+    assert_eq!(std::fs::read_to_string(dir.join("README"))?, "not a key");
+    let keystore = EncryptedKeyStore::unlock(&dir, "hunter2")?;
+    assert_eq!(keystore.public_keys()?, vec![public_key(&key)]);
+    Ok(())
+}
+
+#[test]
+fn migrations_interrupted_during_the_swap_are_rolled_back_or_finished() -> anyhow::Result<()> {
+    let temp = temp_dir()?;
+    let dir = temp.path().join("keystore");
+    let plaintext_dir = dir.with_file_name("keystore.plaintext");
+    let staging = dir.with_file_name("keystore.migrating");
+    let key = SecretKey::new();
+    FilesystemKeyStore::new(dir.clone())?.add_key(&AuthSecretKey::RpoFalcon512(key.clone()))?;
+
+    // Stopped after the plaintext keys were moved aside, before the encrypted ones took their
+    // place: the plaintext keys are moved back and migrated again
+    std::fs::rename(&dir, &plaintext_dir)?;
+    EncryptedKeyStore::create(&staging, "hunter2")?;
+    assert_eq!(migrate_keystore(&dir, "hunter2")?, 1);
+    assert!(!plaintext_dir.exists());
+    assert!(!staging.exists());
+    let keystore = EncryptedKeyStore::unlock(&dir, "hunter2")?;
+    assert_eq!(keystore.public_keys()?, vec![public_key(&key)]);
+
+    // Stopped before the plaintext keys were removed: they are removed, the keystore is left
+    std::fs::create_dir(&plaintext_dir)?;
+    std::fs::write(plaintext_dir.join("12345"), "plaintext key")?;
+    assert!(matches!(
+        migrate_keystore(&dir, "hunter2"),
+        Err(KeystoreError::AlreadyEncrypted(_))
+    ));
+    assert!(!plaintext_dir.exists());
+    assert_eq!(keystore.public_keys()?, vec![public_key(&key)]);
+    Ok(())
+}
+
+#[test]
+fn plaintext_keys_in_an_encrypted_keystore_are_skipped() -> anyhow::Result<()> {
+    let temp = temp_dir()?;
+    let dir = temp.path().join("keystore");
+    let keystore = EncryptedKeyStore::create(&dir, "hunter2")?;
+    let encrypted = SecretKey::new();
+    keystore.add_key(&AuthSecretKey::RpoFalcon512(encrypted.clone()))?;
+
+    // A FilesystemKeyStore opened on the same directory writes its keys in plaintext
+    FilesystemKeyStore::new(dir.clone())?
+        .add_key(&AuthSecretKey::RpoFalcon512(SecretKey::new()))?;
+
+    assert_eq!(keystore.public_keys()?, vec![public_key(&encrypted)]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn client_signs_with_the_encrypted_keystore() -> anyhow::Result<()> {
+    let dir = temp_dir()?;
+    let keystore = EncryptedKeyStore::create(dir.path().join("keystore"), "hunter2")?;
+    let mut client = ClientBuilder::new()
+        .rpc(Arc::new(MockRpcApi::new()))
+        .sqlite_store(dir.path().join("store.sqlite3").to_str().unwrap())
+        .authenticator(Arc::new(keystore.clone()))
+        .in_debug_mode(true)
+        .build()
+        .await?;
+    client.sync_state().await?;
+
+    let key_pair = SecretKey::new();
+    let (faucet, seed) =
+        FaucetSpec::new("MID", 2, 1_000_000)?.build_account([4; 32], key_pair.public_key())?;
+    client.add_account(&faucet, Some(seed), false).await?;
+    keystore.add_key(&AuthSecretKey::RpoFalcon512(key_pair))?;
+
+    // Minting needs the faucet's signature, which the client gets from the encrypted keystore
+    let transaction_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        FungibleAsset::new(faucet.id(), 100)?,
+        ACCOUNT_ID_SENDER.try_into()?,
+        NoteType::Public,
+        client.rng(),
+    )?;
+    let tx_execution_result = client
+        .new_transaction(faucet.id(), transaction_request)
+        .await?;
+    assert_eq!(tx_execution_result.created_notes().num_notes(), 1);
+
+    Ok(())
+}
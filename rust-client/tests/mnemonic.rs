@@ -0,0 +1,330 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::{Arc, Mutex},
+};
+
+use miden_client::{
+    account::{Account, AccountId, AccountStorageMode, AccountType},
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    rpc::{
+        domain::{
+            account::{AccountProofs, AccountUpdateSummary, FetchedAccount},
+            note::{FetchedNote, NoteSyncInfo},
+            nullifier::NullifierUpdate,
+            sync::StateSyncInfo,
+        },
+        NodeRpcClient, RpcError,
+    },
+    testing::mock::MockRpcApi,
+    transaction::ForeignAccount,
+    utils::Serializable,
+    Client, ClientError, Felt, Word,
+};
+use miden_objects::{
+    account::{AccountCode, AccountDelta},
+    block::{BlockHeader, BlockNumber, ProvenBlock},
+    crypto::merkle::{MmrProof, SmtProof},
+    note::{NoteId, NoteTag, Nullifier},
+    transaction::ProvenTransaction,
+};
+use rust_client::{
+    faucet::FaucetSpec,
+    mnemonic::{
+        generate_mnemonic, recover_accounts, AccountDeriver, AccountTemplate, MnemonicError,
+    },
+};
+
+/// The BIP39 test vector mnemonic for all-zero entropy.
+const PHRASE: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+                      abandon abandon about";
+
+#[test]
+fn derivation_is_deterministic() -> anyhow::Result<()> {
+    let first = AccountDeriver::from_mnemonic(PHRASE, "")?;
+    let second = AccountDeriver::from_mnemonic(PHRASE, "")?;
+
+    for template in [
+        AccountTemplate::Wallet(AccountStorageMode::Public),
+        AccountTemplate::Faucet(FaucetSpec::default()),
+    ] {
+        let a = first.account(&template, 3)?;
+        let b = second.account(&template, 3)?;
+        assert_eq!(a.account.id(), b.account.id());
+        assert_eq!(a.seed, b.seed);
+        assert_eq!(a.secret_key.to_bytes(), b.secret_key.to_bytes());
+
+        // The account is authenticated by the derived key
+        let public_key: Word = a.secret_key.public_key().into();
+        assert!(a
+            .account
+            .storage()
+            .slots()
+            .iter()
+            .any(|slot| Word::from(slot.value()) == public_key));
+    }
+    Ok(())
+}
+
+#[test]
+fn index_type_and_passphrase_give_different_accounts() -> anyhow::Result<()> {
+    let deriver = AccountDeriver::from_mnemonic(PHRASE, "")?;
+    let wallet = AccountType::RegularAccountUpdatableCode;
+
+    assert_ne!(deriver.init_seed(wallet, 0), deriver.init_seed(wallet, 1));
+    assert_ne!(
+        deriver.init_seed(wallet, 0),
+        deriver.init_seed(AccountType::FungibleFaucet, 0)
+    );
+    assert_ne!(
+        deriver.secret_key(wallet, 0).to_bytes(),
+        deriver.secret_key(wallet, 1).to_bytes()
+    );
+
+    let with_passphrase = AccountDeriver::from_mnemonic(PHRASE, "TREZOR")?;
+    assert_ne!(
+        deriver.init_seed(wallet, 0),
+        with_passphrase.init_seed(wallet, 0)
+    );
+    assert_ne!(
+        deriver.secret_key(wallet, 0).to_bytes(),
+        with_passphrase.secret_key(wallet, 0).to_bytes()
+    );
+    Ok(())
+}
+
+#[test]
+fn generated_mnemonics_parse_and_invalid_ones_are_rejected() -> anyhow::Result<()> {
+    let phrase = generate_mnemonic();
+    assert_eq!(phrase.split_whitespace().count(), 24);
+    AccountDeriver::from_mnemonic(&phrase, "")?;
+
+    // Wrong checksum word
+    let invalid = PHRASE.replace("about", "abandon");
+    assert!(matches!(
+        AccountDeriver::from_mnemonic(&invalid, ""),
+        Err(MnemonicError::InvalidMnemonic(_))
+    ));
+    Ok(())
+}
+
+/// A mock node that answers account lookups like the real one: public accounts with their
+/// state, private accounts with their commitment only and unknown ids with a `NotFound` status.
+struct RecoveryNode {
+    chain: MockRpcApi,
+    accounts: BTreeMap<AccountId, Account>,
+    /// Lookups of this id fail as if the node were down.
+    unavailable: Option<AccountId>,
+    lookups: Mutex<Vec<AccountId>>,
+}
+
+impl RecoveryNode {
+    /// A node that knows the accounts of `derived` with a nonce of one, as if they were used.
+    fn new(derived: &[Account]) -> Self {
+        let accounts = derived
+            .iter()
+            .map(|account| {
+                let used = Account::from_parts(
+                    account.id(),
+                    account.vault().clone(),
+                    account.storage().clone(),
+                    account.code().clone(),
+                    Felt::new(1),
+                );
+                (account.id(), used)
+            })
+            .collect();
+        Self {
+            chain: MockRpcApi::new(),
+            accounts,
+            unavailable: None,
+            lookups: Mutex::new(vec![]),
+        }
+    }
+
+    fn request_error(status: tonic::Status) -> RpcError {
+        RpcError::RequestError("GetAccountDetails".to_string(), status.to_string())
+    }
+}
+
+#[async_trait::async_trait]
+impl NodeRpcClient for RecoveryNode {
+    async fn get_account_details(&self, account_id: AccountId) -> Result<FetchedAccount, RpcError> {
+        self.lookups.lock().unwrap().push(account_id);
+        if self.unavailable == Some(account_id) {
+            return Err(Self::request_error(tonic::Status::unavailable(
+                "connection refused",
+            )));
+        }
+        let Some(account) = self.accounts.get(&account_id) else {
+            return Err(Self::request_error(tonic::Status::not_found(
+                "account not found",
+            )));
+        };
+        let summary = AccountUpdateSummary::new(account.commitment(), 1);
+        if account.is_public() {
+            Ok(FetchedAccount::Public(account.clone(), summary))
+        } else {
+            Ok(FetchedAccount::Private(account_id, summary))
+        }
+    }
+
+    async fn submit_proven_transaction(
+        &self,
+        proven_transaction: ProvenTransaction,
+    ) -> Result<BlockNumber, RpcError> {
+        self.chain
+            .submit_proven_transaction(proven_transaction)
+            .await
+    }
+
+    async fn get_block_header_by_number(
+        &self,
+        block_num: Option<BlockNumber>,
+        include_mmr_proof: bool,
+    ) -> Result<(BlockHeader, Option<MmrProof>), RpcError> {
+        self.chain
+            .get_block_header_by_number(block_num, include_mmr_proof)
+            .await
+    }
+
+    async fn get_block_by_number(&self, block_num: BlockNumber) -> Result<ProvenBlock, RpcError> {
+        self.chain.get_block_by_number(block_num).await
+    }
+
+    async fn get_notes_by_id(&self, note_ids: &[NoteId]) -> Result<Vec<FetchedNote>, RpcError> {
+        self.chain.get_notes_by_id(note_ids).await
+    }
+
+    async fn sync_state(
+        &self,
+        block_num: BlockNumber,
+        account_ids: &[AccountId],
+        note_tags: &BTreeSet<NoteTag>,
+    ) -> Result<StateSyncInfo, RpcError> {
+        self.chain
+            .sync_state(block_num, account_ids, note_tags)
+            .await
+    }
+
+    async fn sync_notes(
+        &self,
+        block_num: BlockNumber,
+        note_tags: &BTreeSet<NoteTag>,
+    ) -> Result<NoteSyncInfo, RpcError> {
+        self.chain.sync_notes(block_num, note_tags).await
+    }
+
+    async fn check_nullifiers_by_prefix(
+        &self,
+        prefix: &[u16],
+        block_num: BlockNumber,
+    ) -> Result<Vec<NullifierUpdate>, RpcError> {
+        self.chain
+            .check_nullifiers_by_prefix(prefix, block_num)
+            .await
+    }
+
+    async fn check_nullifiers(&self, nullifiers: &[Nullifier]) -> Result<Vec<SmtProof>, RpcError> {
+        self.chain.check_nullifiers(nullifiers).await
+    }
+
+    async fn get_account_proofs(
+        &self,
+        account_storage_requests: &BTreeSet<ForeignAccount>,
+        known_account_codes: Vec<AccountCode>,
+    ) -> Result<AccountProofs, RpcError> {
+        self.chain
+            .get_account_proofs(account_storage_requests, known_account_codes)
+            .await
+    }
+
+    async fn get_account_state_delta(
+        &self,
+        account_id: AccountId,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+    ) -> Result<AccountDelta, RpcError> {
+        self.chain
+            .get_account_state_delta(account_id, from_block, to_block)
+            .await
+    }
+}
+
+/// A client on `node` with its own store and keystore in a fresh temporary directory.
+async fn client_on(node: Arc<RecoveryNode>) -> anyhow::Result<Client> {
+    let dir = std::env::temp_dir().join(format!("mnemonic-{}", rand::random::<u64>()));
+    std::fs::create_dir_all(&dir)?;
+    let keystore = FilesystemKeyStore::<rand::prelude::StdRng>::new(dir.join("keystore"))?;
+    Ok(ClientBuilder::new()
+        .rpc(node)
+        .sqlite_store(dir.join("store.sqlite3").to_str().unwrap())
+        .authenticator(Arc::new(keystore))
+        .in_debug_mode(true)
+        .build()
+        .await?)
+}
+
+#[tokio::test]
+async fn recovery_imports_public_accounts_and_reports_private_ones() -> anyhow::Result<()> {
+    let deriver = AccountDeriver::from_mnemonic(PHRASE, "")?;
+    let public = AccountTemplate::Wallet(AccountStorageMode::Public);
+    let private = AccountTemplate::Wallet(AccountStorageMode::Private);
+
+    // Public accounts 0 and 2 and private account 0 were used, the gap at 1 is within the limit
+    let used = [
+        deriver.account(&public, 0)?.account,
+        deriver.account(&public, 2)?.account,
+        deriver.account(&private, 0)?.account,
+    ];
+    let node = Arc::new(RecoveryNode::new(&used));
+    let mut client = client_on(node.clone()).await?;
+
+    let recovery = recover_accounts(&mut client, &deriver, &public, 2).await?;
+    let indices: Vec<u32> = recovery
+        .imported
+        .iter()
+        .map(|derived| derived.index)
+        .collect();
+    assert_eq!(indices, [0, 2]);
+    assert!(recovery.private.is_empty());
+    for account in &used[..2] {
+        let record = client.get_account(account.id()).await?.expect("imported");
+        assert_eq!(record.account().nonce(), Felt::new(1));
+    }
+    // The scan stopped after two unknown ids in a row
+    assert_eq!(node.lookups.lock().unwrap().len(), 5);
+
+    let recovery = recover_accounts(&mut client, &deriver, &private, 2).await?;
+    assert!(recovery.imported.is_empty());
+    let private_ids: Vec<AccountId> = recovery
+        .private
+        .iter()
+        .map(|derived| derived.account.id())
+        .collect();
+    assert_eq!(private_ids, [used[2].id()]);
+    assert!(client.get_account(used[2].id()).await?.is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn recovery_stops_when_the_node_fails() -> anyhow::Result<()> {
+    let deriver = AccountDeriver::from_mnemonic(PHRASE, "")?;
+    let template = AccountTemplate::Wallet(AccountStorageMode::Public);
+    let used = [
+        deriver.account(&template, 0)?.account,
+        deriver.account(&template, 2)?.account,
+    ];
+
+    // An unavailable node does not mean that account 1 is unused
+    let mut node = RecoveryNode::new(&used);
+    node.unavailable = Some(deriver.account(&template, 1)?.account.id());
+    let mut client = client_on(Arc::new(node)).await?;
+
+    assert!(matches!(
+        recover_accounts(&mut client, &deriver, &template, 5).await,
+        Err(MnemonicError::Client(err))
+            if matches!(*err, ClientError::RpcError(RpcError::RequestError(..)))
+    ));
+    Ok(())
+}
@@ -0,0 +1,262 @@
+use std::collections::BTreeMap;
+
+use miden_client::{
+    account::{Account, AccountId},
+    asset::{Asset, FungibleAsset, NonFungibleAsset},
+    note::{NoteTag, NoteType},
+    testing::account_id::ACCOUNT_ID_PUBLIC_NON_FUNGIBLE_FAUCET,
+    transaction::OutputNote,
+    Felt, Word,
+};
+use miden_objects::asset::NonFungibleAssetDetails;
+use miden_testing::{Auth, MockChain};
+use rust_client::notes::{
+    HashPreimageNote, IterativeOutputNote, PreimageNote, SerialTransform, DEFAULT_DEPTH,
+};
+
+const SECRET: Word = [Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)];
+
+struct Setup {
+    chain: MockChain,
+    alice: Account,
+    bob: Account,
+    assets: Vec<Asset>,
+}
+
+/// Creates two wallets and the assets of two fungible faucets plus one non-fungible asset.
+fn setup() -> anyhow::Result<Setup> {
+    let mut chain = MockChain::new();
+    let faucet_a = chain.add_pending_new_faucet(Auth::BasicAuth, "AAA", 1_000_000)?;
+    let faucet_b = chain.add_pending_new_faucet(Auth::BasicAuth, "BBB", 1_000_000)?;
+    let alice = chain.add_pending_new_wallet(Auth::BasicAuth);
+    let bob = chain.add_pending_new_wallet(Auth::BasicAuth);
+    chain.prove_next_block()?;
+
+    let nft_faucet = AccountId::try_from(ACCOUNT_ID_PUBLIC_NON_FUNGIBLE_FAUCET)?;
+    let nft = NonFungibleAsset::new(&NonFungibleAssetDetails::new(
+        nft_faucet.prefix(),
+        vec![1, 2, 3],
+    )?)?;
+
+    Ok(Setup {
+        chain,
+        alice,
+        bob,
+        assets: vec![faucet_a.mint(100), faucet_b.mint(31), nft.into()],
+    })
+}
+
+fn vault_assets(account: &Account) -> Vec<Asset> {
+    sorted(account.vault().assets().collect())
+}
+
+fn sorted(mut assets: Vec<Asset>) -> Vec<Asset> {
+    assets.sort_by_key(|asset| asset.vault_key().map(|felt| felt.as_int()));
+    assets
+}
+
+#[tokio::test]
+async fn hash_preimage_note_receives_every_asset() -> anyhow::Result<()> {
+    let Setup {
+        mut chain,
+        alice,
+        bob,
+        assets,
+    } = setup()?;
+
+    let note = HashPreimageNote::new(SECRET, assets.clone()).build(
+        alice.id(),
+        NoteType::Public,
+        [Felt::new(7); 4],
+    )?;
+    chain.add_pending_note(OutputNote::Full(note.clone()));
+    chain.prove_next_block()?;
+
+    let executed = chain
+        .build_tx_context(bob.id(), &[note.id()], &[])?
+        .extend_note_args(BTreeMap::from([(note.id(), SECRET)]))
+        .build()?
+        .execute()
+        .await?;
+
+    let mut bob_after = bob.clone();
+    bob_after.apply_delta(executed.account_delta())?;
+    assert_eq!(vault_assets(&bob_after), sorted(assets));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn bound_preimage_note_receives_every_asset() -> anyhow::Result<()> {
+    let Setup {
+        chain,
+        alice,
+        bob,
+        assets,
+    } = setup()?;
+
+    let preimage_note = PreimageNote::new(SECRET, bob.id(), assets.clone());
+    let note = preimage_note.build(alice.id(), NoteType::Private, [Felt::new(7); 4])?;
+
+    let executed = chain
+        .build_tx_context(bob.id(), &[], std::slice::from_ref(&note))?
+        .extend_note_args(BTreeMap::from([(note.id(), preimage_note.secret())]))
+        .build()?
+        .execute()
+        .await?;
+
+    let mut bob_after = bob.clone();
+    bob_after.apply_delta(executed.account_delta())?;
+    assert_eq!(vault_assets(&bob_after), sorted(assets));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn iterative_output_note_splits_fungible_assets() -> anyhow::Result<()> {
+    let Setup {
+        chain,
+        alice,
+        bob,
+        assets,
+    } = setup()?;
+
+    let tag = NoteTag::for_public_use_case(0, 0, miden_client::note::NoteExecutionMode::Local)?;
+    let serial_num = [Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)];
+    let note =
+        IterativeOutputNote::new(alice.id(), tag, assets.clone()).build(alice.id(), serial_num)?;
+
+    // The next note holds half of every fungible asset, rounded down
+    let halved: Vec<Asset> = assets
+        .iter()
+        .filter_map(|asset| match asset {
+            Asset::Fungible(fungible) => Some(
+                FungibleAsset::new(fungible.faucet_id(), fungible.amount() / 2)
+                    .unwrap()
+                    .into(),
+            ),
+            Asset::NonFungible(_) => None,
+        })
+        .collect();
+    let expected = IterativeOutputNote::new(alice.id(), tag, halved.clone())
+        .with_depth(DEFAULT_DEPTH - 1)
+        .build(bob.id(), SerialTransform::Increment.apply(serial_num))?;
+
+    let executed = chain
+        .build_tx_context(bob.id(), &[], std::slice::from_ref(&note))?
+        .extend_expected_output_notes(vec![OutputNote::Full(expected.clone())])
+        .build()?
+        .execute()
+        .await?;
+
+    assert_eq!(executed.output_notes().num_notes(), 1);
+    assert_eq!(executed.output_notes().get_note(0).id(), expected.id());
+
+    // Bob keeps the non-fungible asset and the other half of each fungible asset
+    let kept: Vec<Asset> = assets
+        .iter()
+        .map(|asset| match asset {
+            Asset::Fungible(fungible) => FungibleAsset::new(
+                fungible.faucet_id(),
+                fungible.amount() - fungible.amount() / 2,
+            )
+            .unwrap()
+            .into(),
+            Asset::NonFungible(_) => *asset,
+        })
+        .collect();
+    let mut bob_after = bob.clone();
+    bob_after.apply_delta(executed.account_delta())?;
+    assert_eq!(vault_assets(&bob_after), sorted(kept));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn iterative_output_note_chain_matches_simulation() -> anyhow::Result<()> {
+    let Setup {
+        chain,
+        alice,
+        bob,
+        assets,
+    } = setup()?;
+
+    let tag = NoteTag::for_public_use_case(0, 0, miden_client::note::NoteExecutionMode::Local)?;
+    let serial_num = [Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)];
+    let first = IterativeOutputNote::new(alice.id(), tag, assets).with_min_amount(5);
+
+    // 100 -> 50, 25, 12, 6 and 31 -> 15, 7; every further half is below 5
+    let future_notes = first.simulate(serial_num)?;
+    let amounts: Vec<Vec<u64>> = future_notes
+        .iter()
+        .map(|future| {
+            future
+                .note
+                .assets()
+                .iter()
+                .map(|asset| asset.unwrap_fungible().amount())
+                .collect()
+        })
+        .collect();
+    assert_eq!(amounts, [vec![50, 15], vec![25, 7], vec![12], vec![6]]);
+
+    let mut note = first.build(alice.id(), serial_num)?;
+    for future in &future_notes {
+        let expected = future.note.build(bob.id(), future.serial_num)?;
+        let executed = chain
+            .build_tx_context(bob.id(), &[], std::slice::from_ref(&note))?
+            .extend_expected_output_notes(vec![OutputNote::Full(expected.clone())])
+            .build()?
+            .execute()
+            .await?;
+
+        assert_eq!(executed.output_notes().num_notes(), 1);
+        assert_eq!(executed.output_notes().get_note(0).id(), expected.id());
+        note = expected;
+    }
+
+    // The last note ends the chain and its consumer keeps the remainder
+    let executed = chain
+        .build_tx_context(bob.id(), &[], std::slice::from_ref(&note))?
+        .build()?
+        .execute()
+        .await?;
+    assert_eq!(executed.output_notes().num_notes(), 0);
+
+    let mut bob_after = bob.clone();
+    bob_after.apply_delta(executed.account_delta())?;
+    assert_eq!(
+        vault_assets(&bob_after),
+        sorted(note.assets().iter().copied().collect())
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn iterative_output_note_stops_at_depth_zero() -> anyhow::Result<()> {
+    let Setup {
+        chain,
+        alice,
+        bob,
+        assets,
+    } = setup()?;
+
+    let tag = NoteTag::for_public_use_case(0, 0, miden_client::note::NoteExecutionMode::Local)?;
+    let last = IterativeOutputNote::new(alice.id(), tag, assets.clone()).with_depth(0);
+    assert!(last.simulate([Felt::new(1); 4])?.is_empty());
+
+    let note = last.build(alice.id(), [Felt::new(1); 4])?;
+    let executed = chain
+        .build_tx_context(bob.id(), &[], std::slice::from_ref(&note))?
+        .build()?
+        .execute()
+        .await?;
+    assert_eq!(executed.output_notes().num_notes(), 0);
+
+    let mut bob_after = bob.clone();
+    bob_after.apply_delta(executed.account_delta())?;
+    assert_eq!(vault_assets(&bob_after), sorted(assets));
+
+    Ok(())
+}
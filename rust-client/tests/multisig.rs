@@ -0,0 +1,187 @@
+use std::path::PathBuf;
+
+use miden_client::{
+    account::{
+        component::BasicWallet, Account, AccountBuilder, AccountId, AccountStorageMode, AccountType,
+    },
+    auth::AuthSecretKey,
+    crypto::{Digest, SecretKey},
+    keystore::FilesystemKeyStore,
+    note::{Note, NoteType},
+    transaction::{ExecutedTransaction, OutputNotes},
+    Felt, Word,
+};
+use miden_testing::{Auth, MockChain, TxContextInput};
+use rand::rngs::StdRng;
+use rust_client::multisig::{
+    MultisigConfig, MultisigError, SigningCoordinator, TransactionSummary,
+};
+
+struct Setup {
+    chain: MockChain,
+    account: Account,
+    faucet_id: AccountId,
+    note: Note,
+    config: MultisigConfig,
+    keystores: Vec<FilesystemKeyStore<StdRng>>,
+}
+
+/// A fresh keystore directory holding one new signer key.
+fn signer_keystore() -> anyhow::Result<(FilesystemKeyStore<StdRng>, Word)> {
+    let dir: PathBuf = std::env::temp_dir().join(format!("multisig-{}", rand::random::<u64>()));
+    std::fs::create_dir_all(&dir)?;
+    let keystore = FilesystemKeyStore::new(dir)?;
+
+    let key = SecretKey::new();
+    let pub_key: Word = key.public_key().into();
+    keystore.add_key(&AuthSecretKey::RpoFalcon512(key))?;
+    Ok((keystore, pub_key))
+}
+
+/// A `threshold`-of-`signers` multisig wallet with a P2ID note of 100 tokens waiting for it.
+/// Every signer keeps their key in a keystore of their own.
+fn setup(threshold: usize, signers: usize) -> anyhow::Result<Setup> {
+    let (keystores, pub_keys): (Vec<_>, Vec<_>) = (0..signers)
+        .map(|_| signer_keystore())
+        .collect::<anyhow::Result<Vec<_>>>()?
+        .into_iter()
+        .unzip();
+    let config = MultisigConfig::new(threshold, pub_keys)?;
+
+    let mut chain = MockChain::new();
+    let faucet = chain.add_pending_new_faucet(Auth::BasicAuth, "AAA", 1_000_000)?;
+    let account = AccountBuilder::new([7; 32])
+        .account_type(AccountType::RegularAccountUpdatableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_component(BasicWallet)
+        .with_auth_component(config.component()?)
+        .build_existing()?;
+    chain.add_pending_account(account.clone());
+    let note = chain.add_pending_p2id_note(
+        faucet.id(),
+        account.id(),
+        &[faucet.mint(100)],
+        NoteType::Public,
+    )?;
+    chain.prove_next_block()?;
+
+    Ok(Setup {
+        chain,
+        account,
+        faucet_id: faucet.id(),
+        note,
+        config,
+        keystores,
+    })
+}
+
+impl Setup {
+    /// The summary of the transaction consuming the note.
+    fn summary(&self) -> anyhow::Result<TransactionSummary> {
+        let tx_context = self
+            .chain
+            .build_tx_context(
+                TxContextInput::Account(self.account.clone()),
+                &[self.note.id()],
+                &[],
+            )?
+            .build()?;
+        Ok(TransactionSummary::new(
+            self.account.id(),
+            self.account.nonce(),
+            tx_context.input_notes(),
+            &OutputNotes::new(vec![])?,
+        ))
+    }
+
+    /// A coordinator that can reach the keystores of the signers at `indices`.
+    fn coordinator(&self, indices: &[usize]) -> SigningCoordinator {
+        indices.iter().fold(
+            SigningCoordinator::new(self.config.clone()),
+            |coordinator, &index| coordinator.with_keystore(self.keystores[index].clone()),
+        )
+    }
+
+    /// Consumes the note with the multisig account, passing `advice` to the transaction.
+    async fn consume(
+        &self,
+        advice: Vec<(Digest, Vec<Felt>)>,
+    ) -> anyhow::Result<ExecutedTransaction> {
+        let executed = self
+            .chain
+            .build_tx_context(
+                TxContextInput::Account(self.account.clone()),
+                &[self.note.id()],
+                &[],
+            )?
+            .extend_advice_map(advice.into_iter().map(|(key, value)| (key.into(), value)))
+            .build()?
+            .execute()
+            .await?;
+        Ok(executed)
+    }
+}
+
+#[tokio::test]
+async fn multisig_accepts_transaction_when_threshold_is_met() -> anyhow::Result<()> {
+    let setup = setup(2, 3)?;
+    let summary = setup.summary()?;
+
+    // Signers 0 and 2 are reachable, signer 1 is not
+    let advice = setup.coordinator(&[0, 2]).collect(&summary)?;
+    let executed = setup.consume(advice).await?;
+
+    let mut account = setup.account.clone();
+    account.apply_delta(executed.account_delta())?;
+    assert_eq!(account.nonce(), setup.account.nonce() + Felt::new(1));
+    assert_eq!(account.vault().get_balance(setup.faucet_id)?, 100);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn multisig_rejects_transaction_below_threshold() -> anyhow::Result<()> {
+    let setup = setup(2, 3)?;
+    let summary = setup.summary()?;
+
+    // The coordinator refuses to approve with a single reachable signer
+    let coordinator = setup.coordinator(&[1]);
+    assert!(matches!(
+        coordinator.collect(&summary),
+        Err(MultisigError::ThresholdNotMet {
+            threshold: 2,
+            collected: 1
+        })
+    ));
+
+    // And the account rejects a transaction carrying a single signature
+    let signature = coordinator.sign(&summary, 1)?.unwrap();
+    let advice = setup.config.approval_advice(&summary, vec![(1, signature)]);
+    let err = setup.consume(advice).await.unwrap_err();
+    assert!(format!("{err:?}").contains("not enough signers approved the transaction"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn multisig_counts_a_duplicate_signer_once() -> anyhow::Result<()> {
+    let setup = setup(2, 3)?;
+    let summary = setup.summary()?;
+
+    // Signer 0 signs twice to reach the threshold on their own
+    let signature = setup.coordinator(&[0]).sign(&summary, 0)?.unwrap();
+    let advice = setup
+        .config
+        .approval_advice(&summary, vec![(0, signature.clone()), (0, signature)]);
+    let err = setup.consume(advice).await.unwrap_err();
+    assert!(format!("{err:?}").contains("each signer counts once"));
+
+    // A key cannot be registered as two signers either
+    let pub_key = setup.config.signers()[0];
+    assert!(matches!(
+        MultisigConfig::new(2, vec![pub_key, pub_key]),
+        Err(MultisigError::DuplicateSigner(key)) if key == pub_key
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,206 @@
+use miden_client::{
+    account::{Account, AccountBuilder, AccountStorageMode, AccountType},
+    crypto::RpoRandomCoin,
+    note::{create_p2id_note, Note, NoteType},
+    transaction::{ExecutedTransaction, OutputNote, TransactionScript, TransactionScriptTemplate},
+    Felt, Word, ZERO,
+};
+use miden_lib::account::interface::AccountInterface;
+use miden_testing::{AccountState, Auth, MockChain};
+use rust_client::nft::{
+    metadata_commitment, mint_request, nft_asset, nft_faucet_component, owned_nfts, NftMetadata,
+    NftMint,
+};
+
+struct Setup {
+    chain: MockChain,
+    faucet: Account,
+    alice: Account,
+    bob: Account,
+}
+
+/// An NFT faucet and two empty wallets.
+fn setup() -> anyhow::Result<Setup> {
+    let mut chain = MockChain::new();
+    let builder = AccountBuilder::new([5; 32])
+        .account_type(AccountType::NonFungibleFaucet)
+        .storage_mode(AccountStorageMode::Public)
+        .with_component(nft_faucet_component()?);
+    let faucet =
+        chain.add_pending_account_from_builder(Auth::BasicAuth, builder, AccountState::Exists)?;
+    let alice = chain.add_pending_existing_wallet(Auth::BasicAuth, vec![]);
+    let bob = chain.add_pending_existing_wallet(Auth::BasicAuth, vec![]);
+    chain.prove_next_block()?;
+
+    Ok(Setup {
+        chain,
+        faucet,
+        alice,
+        bob,
+    })
+}
+
+fn metadata(token_id: u64) -> NftMetadata {
+    NftMetadata::new(
+        format!("Miden Tutorial #{token_id}"),
+        "An NFT minted in the NFT tutorial",
+        format!("ipfs://tutorial/{token_id}.json"),
+    )
+}
+
+fn serial_num(seed: u64) -> Word {
+    [Felt::new(seed), ZERO, ZERO, ZERO]
+}
+
+fn custom_script(mints: &[NftMint]) -> anyhow::Result<TransactionScript> {
+    match mint_request(mints)?.script_template() {
+        Some(TransactionScriptTemplate::CustomScript(script)) => Ok(script.clone()),
+        _ => unreachable!("mint requests use a custom script"),
+    }
+}
+
+impl Setup {
+    /// Executes the mint of `mints` against the faucet.
+    async fn mint(&self, mints: &[NftMint]) -> anyhow::Result<(ExecutedTransaction, Vec<Note>)> {
+        let notes = mints
+            .iter()
+            .map(|mint| mint.note(self.faucet.id()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let executed = self
+            .chain
+            .build_tx_context(self.faucet.id(), &[], &[])?
+            .tx_script(custom_script(mints)?)
+            .extend_expected_output_notes(notes.iter().cloned().map(OutputNote::Full).collect())
+            .build()?
+            .execute()
+            .await?;
+        Ok((executed, notes))
+    }
+
+    /// Commits `executed` and the account state it leads to.
+    fn commit(&mut self, executed: &ExecutedTransaction) -> anyhow::Result<Account> {
+        let account = self.chain.add_pending_executed_transaction(executed)?;
+        self.chain.prove_next_block()?;
+        Ok(account)
+    }
+
+    /// Consumes `notes` with `account` and commits the transaction.
+    async fn consume(&mut self, account: &Account, notes: &[Note]) -> anyhow::Result<Account> {
+        let note_ids: Vec<_> = notes.iter().map(Note::id).collect();
+        let executed = self
+            .chain
+            .build_tx_context(account.id(), &note_ids, &[])?
+            .build()?
+            .execute()
+            .await?;
+        self.commit(&executed)
+    }
+}
+
+#[tokio::test]
+async fn nft_faucet_mints_nfts_with_metadata_commitments() -> anyhow::Result<()> {
+    let mut setup = setup()?;
+    let mints: Vec<_> = (1..=2)
+        .map(|token_id| {
+            Ok(NftMint::new(
+                nft_asset(setup.faucet.id(), token_id)?,
+                metadata(token_id),
+                setup.alice.id(),
+                serial_num(token_id),
+            ))
+        })
+        .collect::<anyhow::Result<_>>()?;
+
+    let (executed, notes) = setup.mint(&mints).await?;
+    assert_eq!(executed.output_notes().num_notes(), 2);
+    for (index, note) in notes.iter().enumerate() {
+        assert_eq!(executed.output_notes().get_note(index).id(), note.id());
+    }
+
+    // The faucet remembers the metadata of every NFT it minted
+    let faucet = setup.commit(&executed)?;
+    for mint in &mints {
+        assert_eq!(
+            metadata_commitment(&faucet, &mint.asset)?,
+            Some(mint.metadata.commitment())
+        );
+    }
+    let unminted = nft_asset(setup.faucet.id(), 3)?;
+    assert_eq!(metadata_commitment(&faucet, &unminted)?, None);
+
+    // Alice consumes both notes and holds both NFTs
+    let alice = setup.alice.clone();
+    let alice = setup.consume(&alice, &notes).await?;
+    let mut owned = owned_nfts(&alice);
+    let mut minted: Vec<_> = mints.iter().map(|mint| mint.asset).collect();
+    owned.sort();
+    minted.sort();
+    assert_eq!(owned, minted);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn nft_moves_between_wallets_in_a_p2id_note() -> anyhow::Result<()> {
+    let mut setup = setup()?;
+    let nft = nft_asset(setup.faucet.id(), 7)?;
+    let mint = NftMint::new(nft, metadata(7), setup.alice.id(), serial_num(7));
+    let (executed, notes) = setup.mint(std::slice::from_ref(&mint)).await?;
+    setup.commit(&executed)?;
+    let alice = setup.alice.clone();
+    let alice = setup.consume(&alice, &notes).await?;
+
+    // Alice sends the NFT to Bob like any other asset
+    let mut rng = RpoRandomCoin::new([Felt::new(11); 4]);
+    let transfer = create_p2id_note(
+        alice.id(),
+        setup.bob.id(),
+        vec![nft.into()],
+        NoteType::Public,
+        ZERO,
+        &mut rng,
+    )?;
+    let send_script = AccountInterface::from(&alice).build_send_notes_script(
+        &[transfer.clone().into()],
+        None,
+        false,
+    )?;
+    let executed = setup
+        .chain
+        .build_tx_context(alice.id(), &[], &[])?
+        .tx_script(send_script)
+        .extend_expected_output_notes(vec![OutputNote::Full(transfer.clone())])
+        .build()?
+        .execute()
+        .await?;
+    let alice = setup.commit(&executed)?;
+    assert!(owned_nfts(&alice).is_empty());
+
+    let bob = setup.bob.clone();
+    let bob = setup.consume(&bob, &[transfer]).await?;
+    assert_eq!(owned_nfts(&bob), vec![nft]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn nft_faucet_rejects_minting_an_nft_twice() -> anyhow::Result<()> {
+    let mut setup = setup()?;
+    let nft = nft_asset(setup.faucet.id(), 1)?;
+    let (executed, _) = setup
+        .mint(&[NftMint::new(
+            nft,
+            metadata(1),
+            setup.alice.id(),
+            serial_num(1),
+        )])
+        .await?;
+    setup.commit(&executed)?;
+
+    // Minting the same NFT again, even with other metadata, is rejected by the kernel
+    let again = NftMint::new(nft, metadata(2), setup.bob.id(), serial_num(2));
+    let err = setup.mint(&[again]).await.unwrap_err();
+    assert!(format!("{err:?}").contains("already issued"));
+
+    Ok(())
+}
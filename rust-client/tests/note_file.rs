@@ -0,0 +1,179 @@
+mod common;
+
+use miden_client::{
+    asset::FungibleAsset,
+    auth::AuthSecretKey,
+    crypto::SecretKey,
+    note::{Note, NoteType},
+    testing::account_id::ACCOUNT_ID_SENDER,
+    transaction::{OutputNote, TransactionRequestBuilder},
+    Felt, Word,
+};
+use rust_client::{
+    faucet::FaucetSpec,
+    note_file::{find_note, NoteExport, NoteFileError, NoteFileFormat, VERSION},
+    notes::HashPreimageNote,
+};
+
+use common::{mock_client, temp_dir};
+
+const SECRET: Word = [Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)];
+
+/// A private hash-preimage note with its secret as note args.
+fn note_export() -> anyhow::Result<NoteExport> {
+    let faucet = FaucetSpec::default()
+        .build_account([3; 32], SecretKey::new().public_key())?
+        .0;
+    let asset = FungibleAsset::new(faucet.id(), 100)?;
+    let note = HashPreimageNote::new(SECRET, vec![asset.into()]).build(
+        ACCOUNT_ID_SENDER.try_into()?,
+        NoteType::Private,
+        [Felt::new(7); 4],
+    )?;
+    Ok(NoteExport::new(note).with_note_args(SECRET))
+}
+
+#[test]
+fn note_files_round_trip_in_both_encodings() -> anyhow::Result<()> {
+    let export = note_export()?;
+
+    assert_eq!(NoteExport::from_bytes(&export.to_bytes())?, export);
+    let text = export.to_text();
+    assert!(text.starts_with("mnote1"));
+    assert_eq!(NoteExport::from_text(&text)?, export);
+    assert_eq!(NoteExport::from_text(&format!("  {text}\n"))?, export);
+
+    let without_args = NoteExport::new(export.note.clone());
+    assert_eq!(
+        NoteExport::from_bytes(&without_args.to_bytes())?.note_args,
+        None
+    );
+
+    let dir = temp_dir()?;
+    for (name, format) in [
+        ("note.mnote", NoteFileFormat::Binary),
+        ("note.txt", NoteFileFormat::Text),
+    ] {
+        export.write(dir.path().join(name), format)?;
+        assert_eq!(NoteExport::read(dir.path().join(name))?, export);
+    }
+    Ok(())
+}
+
+#[test]
+fn corrupted_note_files_are_rejected() -> anyhow::Result<()> {
+    let bytes = note_export()?.to_bytes();
+
+    let mut corrupted = bytes.clone();
+    corrupted[20] ^= 1;
+    assert!(matches!(
+        NoteExport::from_bytes(&corrupted),
+        Err(NoteFileError::ChecksumMismatch)
+    ));
+
+    // Changes a character in the middle of the note, past the magic and version
+    let mut text = note_export()?.to_text();
+    let replacement = if &text[30..31] == "q" { "p" } else { "q" };
+    text.replace_range(30..31, replacement);
+    assert!(matches!(
+        NoteExport::from_text(&text),
+        Err(NoteFileError::ChecksumMismatch)
+    ));
+    assert!(matches!(
+        NoteExport::from_text("mtst1qq932n3zkt5rxgpw5tgd9szxp58tllml"),
+        Err(NoteFileError::InvalidText(_))
+    ));
+
+    assert!(matches!(
+        NoteExport::from_bytes(b"ABCD\x01 not a note file at all, but long enough to hold one"),
+        Err(NoteFileError::InvalidMagic)
+    ));
+    Ok(())
+}
+
+#[test]
+fn other_versions_are_unsupported() -> anyhow::Result<()> {
+    let mut bytes = note_export()?.to_bytes();
+    bytes[4] = VERSION + 1;
+    assert!(matches!(
+        NoteExport::from_bytes(&bytes),
+        Err(NoteFileError::UnsupportedVersion(version)) if version == VERSION + 1
+    ));
+    Ok(())
+}
+
+#[test]
+fn truncated_note_files_are_reported_as_truncated() -> anyhow::Result<()> {
+    let bytes = note_export()?.to_bytes();
+
+    // Cut inside the magic, before and after the version, and before a full checksum fits
+    for len in [0, 2, 4, 5, 20] {
+        assert!(
+            matches!(
+                NoteExport::from_bytes(&bytes[..len]),
+                Err(NoteFileError::Truncated)
+            ),
+            "{len} bytes"
+        );
+    }
+    // Reading a truncated file does not mistake it for the text encoding
+    let dir = temp_dir()?;
+    let path = dir.path().join("truncated.mnote");
+    std::fs::write(&path, &bytes[..3])?;
+    assert!(matches!(
+        NoteExport::read(&path),
+        Err(NoteFileError::Truncated)
+    ));
+
+    // A longer file that lost its tail no longer matches its checksum
+    assert!(matches!(
+        NoteExport::from_bytes(&bytes[..bytes.len() - 1]),
+        Err(NoteFileError::ChecksumMismatch)
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn exported_notes_are_imported_by_another_client() -> anyhow::Result<()> {
+    let dir = temp_dir()?;
+    let (mut sender, keystore) = mock_client(&dir.path().join("sender")).await?;
+    let (mut receiver, _) = mock_client(&dir.path().join("receiver")).await?;
+
+    let key_pair = SecretKey::new();
+    let (faucet, seed) = FaucetSpec::default().build_account([9; 32], key_pair.public_key())?;
+    sender.add_account(&faucet, Some(seed), false).await?;
+    keystore.add_key(&AuthSecretKey::RpoFalcon512(key_pair))?;
+
+    // The sender mints a private note, which only it knows in full
+    let request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        FungibleAsset::new(faucet.id(), 100)?,
+        ACCOUNT_ID_SENDER.try_into()?,
+        NoteType::Private,
+        sender.rng(),
+    )?;
+    let tx_result = sender.new_transaction(faucet.id(), request).await?;
+    let OutputNote::Full(minted) = tx_result.created_notes().get_note(0).clone() else {
+        panic!("mint notes are full notes");
+    };
+    sender.testing_apply_transaction(tx_result).await?;
+
+    let note: Note = find_note(&sender, minted.id())
+        .await?
+        .expect("the sender has the note it created");
+    assert_eq!(note, minted);
+    assert_eq!(find_note(&receiver, minted.id()).await?, None);
+
+    let path = dir.path().join("note.mnote");
+    NoteExport::new(note).write(&path, NoteFileFormat::Binary)?;
+
+    let imported_id = NoteExport::read(&path)?
+        .import(&mut receiver, 0.into())
+        .await?;
+    assert_eq!(imported_id, minted.id());
+    let record = receiver
+        .get_input_note(minted.id())
+        .await?
+        .expect("the receiver tracks the imported note");
+    assert_eq!(record.details().id(), minted.id());
+    Ok(())
+}
@@ -0,0 +1,165 @@
+mod common;
+
+use miden_client::{
+    account::AccountId,
+    note::{Note, NoteType},
+    testing::account_id::{ACCOUNT_ID_REGULAR_PRIVATE_ACCOUNT_UPDATABLE_CODE, ACCOUNT_ID_SENDER},
+    Felt, Word,
+};
+use miden_objects::account::NetworkId;
+use rust_client::{
+    address_book::{AddressBook, Role},
+    note_file::NoteExport,
+    note_policy::{
+        parse_note_type, NoteDelivery, NotePolicy, NotePolicyError, NOTE_POLICY_ENV, NOTE_TYPE_ENV,
+    },
+    notes::HashPreimageNote,
+};
+
+use common::{mock_client, temp_dir};
+
+const SECRET: Word = [Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)];
+
+/// A note of `note_type`, public and private notes get different serial numbers and ids.
+fn note(note_type: NoteType) -> anyhow::Result<Note> {
+    Ok(HashPreimageNote::new(SECRET, vec![]).build(
+        ACCOUNT_ID_SENDER.try_into()?,
+        note_type,
+        [Felt::new(note_type as u64); 4],
+    )?)
+}
+
+fn sender() -> AccountId {
+    ACCOUNT_ID_SENDER.try_into().unwrap()
+}
+
+fn receiver() -> AccountId {
+    ACCOUNT_ID_REGULAR_PRIVATE_ACCOUNT_UPDATABLE_CODE
+        .try_into()
+        .unwrap()
+}
+
+#[test]
+fn note_types_parse_ignoring_case() {
+    assert_eq!(parse_note_type("public").unwrap(), NoteType::Public);
+    assert_eq!(parse_note_type(" Private\n").unwrap(), NoteType::Private);
+    assert!(matches!(
+        parse_note_type("encrypted"),
+        Err(NotePolicyError::InvalidConfig(_))
+    ));
+}
+
+#[test]
+fn recipients_override_the_default_note_type() {
+    let policy = NotePolicy::new(NoteType::Private).with_recipient(receiver(), NoteType::Public);
+    assert_eq!(policy.note_type_for(receiver()), NoteType::Public);
+    assert_eq!(policy.note_type_for(sender()), NoteType::Private);
+
+    assert_eq!(
+        NotePolicy::default().note_type_for(sender()),
+        NoteType::Public
+    );
+}
+
+#[test]
+fn policies_load_from_files_and_the_environment() -> anyhow::Result<()> {
+    let dir = temp_dir()?;
+    let mut address_book =
+        AddressBook::new(dir.path().join("address_book.json"), NetworkId::Testnet);
+    address_book.insert("bob", receiver(), Role::Wallet)?;
+
+    let path = dir.path().join("policy.json");
+    std::fs::write(
+        &path,
+        r#"{ "default": "private", "recipients": { "bob": "public" } }"#,
+    )?;
+    let expected = NotePolicy::new(NoteType::Private).with_recipient(receiver(), NoteType::Public);
+    assert_eq!(NotePolicy::from_file(&path, &address_book)?, expected);
+
+    // Recipients are unknown names or ids of another network
+    for recipient in [
+        "carol".to_string(),
+        receiver().to_bech32(NetworkId::Mainnet),
+    ] {
+        let invalid = dir.path().join("invalid.json");
+        std::fs::write(
+            &invalid,
+            format!(r#"{{ "default": "private", "recipients": {{ "{recipient}": "public" }} }}"#),
+        )?;
+        assert!(matches!(
+            NotePolicy::from_file(&invalid, &address_book),
+            Err(NotePolicyError::InvalidConfig(_))
+        ));
+    }
+    assert!(matches!(
+        NotePolicy::from_file(dir.path().join("missing.json"), &address_book),
+        Err(NotePolicyError::Io(_))
+    ));
+
+    // The environment is shared by all tests in this file, so only this test touches it
+    std::env::remove_var(NOTE_POLICY_ENV);
+    std::env::remove_var(NOTE_TYPE_ENV);
+    assert_eq!(NotePolicy::from_env(&address_book)?, NotePolicy::default());
+
+    std::env::set_var(NOTE_TYPE_ENV, "private");
+    assert_eq!(
+        NotePolicy::from_env(&address_book)?,
+        NotePolicy::new(NoteType::Private)
+    );
+
+    // A policy file takes precedence over the note type
+    std::env::set_var(NOTE_POLICY_ENV, &path);
+    assert_eq!(NotePolicy::from_env(&address_book)?, expected);
+
+    std::env::remove_var(NOTE_POLICY_ENV);
+    std::env::set_var(NOTE_TYPE_ENV, "secret");
+    assert!(NotePolicy::from_env(&address_book).is_err());
+    std::env::remove_var(NOTE_TYPE_ENV);
+    Ok(())
+}
+
+#[tokio::test]
+async fn private_notes_are_exported_and_public_notes_are_skipped() -> anyhow::Result<()> {
+    let temp = temp_dir()?;
+    let dir = temp.path().join("notes");
+
+    let public = note(NoteType::Public)?;
+    assert_eq!(NoteDelivery::Export(&dir).deliver(&public).await?, None);
+    assert!(!dir.exists());
+
+    let private = note(NoteType::Private)?;
+    let delivered = NoteDelivery::Export(&dir).deliver(&private).await?;
+    assert_eq!(delivered, Some(private.id()));
+    let exported = NoteExport::read(dir.join(format!("{}.mnote", private.id().to_hex())))?;
+    assert_eq!(exported.note, private);
+    Ok(())
+}
+
+#[tokio::test]
+async fn private_notes_are_imported_into_the_receivers_client() -> anyhow::Result<()> {
+    let dir = temp_dir()?;
+    let (mut receiver, _) = mock_client(dir.path()).await?;
+    let private = note(NoteType::Private)?;
+
+    let delivery = NoteDelivery::Import {
+        receiver: &mut receiver,
+        after_block_num: 0.into(),
+    };
+    assert_eq!(delivery.deliver(&private).await?, Some(private.id()));
+
+    let record = receiver
+        .get_input_note(private.id())
+        .await?
+        .expect("the receiver tracks the delivered note");
+    assert_eq!(record.details().id(), private.id());
+
+    // Public notes are found by syncing, so they are not imported
+    let public = note(NoteType::Public)?;
+    let delivery = NoteDelivery::Import {
+        receiver: &mut receiver,
+        after_block_num: 0.into(),
+    };
+    assert_eq!(delivery.deliver(&public).await?, None);
+    assert!(receiver.get_input_note(public.id()).await?.is_none());
+    Ok(())
+}
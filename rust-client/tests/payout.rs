@@ -0,0 +1,142 @@
+use miden_client::{
+    account::AccountId,
+    asset::FungibleAsset,
+    testing::account_id::{
+        ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET, ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1,
+        ACCOUNT_ID_PUBLIC_NON_FUNGIBLE_FAUCET, ACCOUNT_ID_SENDER,
+    },
+};
+use miden_objects::account::NetworkId;
+use rust_client::{
+    address_book::{AddressBook, Role},
+    payout::{parse_payouts, totals, Payout, PayoutError, PayoutReport},
+};
+
+fn id(raw: u128) -> AccountId {
+    raw.try_into().unwrap()
+}
+
+fn bech32(raw: u128) -> String {
+    id(raw).to_bech32(NetworkId::Testnet)
+}
+
+fn address_book() -> AddressBook {
+    let path = std::env::temp_dir()
+        .join(format!("payout-{}", rand::random::<u64>()))
+        .join("address_book.json");
+    AddressBook::new(path, NetworkId::Testnet)
+}
+
+fn payout(faucet: u128, amount: u64) -> Payout {
+    Payout {
+        recipient: id(ACCOUNT_ID_SENDER),
+        faucet_id: id(faucet),
+        amount,
+    }
+}
+
+#[test]
+fn payout_files_skip_the_header_comments_and_blank_lines() -> anyhow::Result<()> {
+    let csv = format!(
+        "recipient,faucet,amount\n\n# first payout\n {} , {} , 100 \n{},{},250\n",
+        bech32(ACCOUNT_ID_SENDER),
+        bech32(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET),
+        bech32(ACCOUNT_ID_SENDER),
+        bech32(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1),
+    );
+    assert_eq!(
+        parse_payouts(&csv, &address_book())?,
+        [
+            payout(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET, 100),
+            payout(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1, 250)
+        ]
+    );
+    assert_eq!(parse_payouts("", &address_book())?, []);
+    Ok(())
+}
+
+#[test]
+fn payout_files_accept_names() -> anyhow::Result<()> {
+    let mut book = address_book();
+    book.insert("alice", id(ACCOUNT_ID_SENDER), Role::Wallet)?;
+    book.insert(
+        "mid-faucet",
+        id(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET),
+        Role::Faucet,
+    )?;
+
+    assert_eq!(
+        parse_payouts("alice,mid-faucet,100", &book)?,
+        [payout(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET, 100)]
+    );
+    assert!(parse_payouts("bob,mid-faucet,100", &book).is_err());
+    Ok(())
+}
+
+#[test]
+fn invalid_rows_report_their_line() {
+    let recipient = bech32(ACCOUNT_ID_SENDER);
+    let faucet = bech32(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET);
+    let non_fungible = bech32(ACCOUNT_ID_PUBLIC_NON_FUNGIBLE_FAUCET);
+    let too_much = FungibleAsset::MAX_AMOUNT + 1;
+
+    for (row, reason) in [
+        (format!("{recipient},{faucet}"), "expected 3 fields"),
+        (format!("{recipient},{faucet},-5"), "invalid amount"),
+        (format!("{recipient},{faucet},{too_much}"), ""),
+        (format!("{recipient},{non_fungible},100"), ""),
+        (format!("{recipient},{recipient},100"), ""),
+        (format!("nobody,{faucet},100"), "invalid account nobody"),
+    ] {
+        let csv = format!("# payouts\n{row}\n");
+        match parse_payouts(&csv, &address_book()) {
+            Err(PayoutError::InvalidRow {
+                line: 2,
+                reason: found,
+            }) => {
+                assert!(found.contains(reason), "{row}: {found}")
+            }
+            other => panic!("{row} was not rejected on line 2: {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn totals_add_up_payouts_per_faucet() -> anyhow::Result<()> {
+    let payouts = [
+        payout(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET, 100),
+        payout(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1, 7),
+        payout(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET, 250),
+    ];
+    let totals = totals(&payouts)?;
+    assert_eq!(totals.len(), 2);
+    assert_eq!(totals[&id(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET)], 350);
+    assert_eq!(totals[&id(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1)], 7);
+    Ok(())
+}
+
+#[test]
+fn totals_that_overflow_are_rejected() {
+    // Every payout is within the asset limit, but three of them exceed u64::MAX
+    let payouts = [payout(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET, FungibleAsset::MAX_AMOUNT); 3];
+    assert!(matches!(
+        totals(&payouts),
+        Err(PayoutError::TotalOverflow(faucet_id))
+            if faucet_id == id(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET)
+    ));
+}
+
+#[test]
+fn interrupted_payouts_keep_the_report_of_sent_batches() {
+    let report = PayoutReport {
+        transactions: vec!["0x01".into()],
+        ..PayoutReport::default()
+    };
+    let err = PayoutError::Interrupted {
+        report: Box::new(report),
+        cause: Box::new(PayoutError::UnknownAccount(id(ACCOUNT_ID_SENDER))),
+    };
+    assert!(err
+        .to_string()
+        .starts_with("payouts stopped after 1 transaction(s): account"));
+}
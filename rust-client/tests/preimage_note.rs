@@ -0,0 +1,125 @@
+use std::collections::BTreeMap;
+
+use miden_client::{
+    account::Account,
+    asset::Asset,
+    note::{Note, NoteType},
+    Felt, Word,
+};
+use miden_testing::{Auth, MockChain};
+use rust_client::notes::{secret_digest, PreimageNote};
+
+const SECRET: Word = [Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)];
+
+struct Setup {
+    chain: MockChain,
+    alice: Account,
+    bob: Account,
+    preimage_note: PreimageNote,
+    note: Note,
+    asset: Asset,
+}
+
+/// A preimage note from Alice bound to Bob.
+fn setup() -> anyhow::Result<Setup> {
+    let mut chain = MockChain::new();
+    let faucet = chain.add_pending_new_faucet(Auth::BasicAuth, "MID", 1_000_000)?;
+    let alice = chain.add_pending_new_wallet(Auth::BasicAuth);
+    let bob = chain.add_pending_new_wallet(Auth::BasicAuth);
+    chain.prove_next_block()?;
+
+    let asset = faucet.mint(100);
+    let preimage_note = PreimageNote::new(SECRET, bob.id(), vec![asset]);
+    let note = preimage_note.build(alice.id(), NoteType::Private, [Felt::new(7); 4])?;
+    Ok(Setup {
+        chain,
+        alice,
+        bob,
+        preimage_note,
+        note,
+        asset,
+    })
+}
+
+/// Executes `account` consuming `note` as an unauthenticated note with `secret` as note args.
+async fn consume(
+    chain: &MockChain,
+    account: &Account,
+    note: &Note,
+    secret: Word,
+) -> anyhow::Result<Account> {
+    let executed = chain
+        .build_tx_context(account.id(), &[], std::slice::from_ref(note))?
+        .extend_note_args(BTreeMap::from([(note.id(), secret)]))
+        .build()?
+        .execute()
+        .await?;
+    let mut account = account.clone();
+    account.apply_delta(executed.account_delta())?;
+    Ok(account)
+}
+
+#[test]
+fn inputs_hold_the_padded_digest_and_the_consumer() -> anyhow::Result<()> {
+    let Setup {
+        bob, preimage_note, ..
+    } = setup()?;
+
+    let inputs = preimage_note.inputs()?;
+    let values = inputs.values();
+    assert_eq!(values.len(), 6);
+    assert_eq!(&values[..4], secret_digest(SECRET).as_elements());
+    assert_eq!(values[4], bob.id().suffix());
+    assert_eq!(values[5], bob.id().prefix().as_felt());
+    Ok(())
+}
+
+#[tokio::test]
+async fn consumer_with_the_secret_receives_the_assets() -> anyhow::Result<()> {
+    let Setup {
+        chain,
+        bob,
+        preimage_note,
+        note,
+        asset,
+        ..
+    } = setup()?;
+
+    let bob_after = consume(&chain, &bob, &note, preimage_note.secret()).await?;
+    assert_eq!(bob_after.vault().assets().collect::<Vec<_>>(), [asset]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn another_account_with_the_secret_cannot_front_run_the_consumer() -> anyhow::Result<()> {
+    let Setup {
+        chain,
+        alice,
+        preimage_note,
+        note,
+        ..
+    } = setup()?;
+
+    // Alice knows the secret, but the note is bound to Bob
+    let err = consume(&chain, &alice, &note, preimage_note.secret())
+        .await
+        .unwrap_err();
+    assert!(format!("{err:?}")
+        .contains("consuming account does not match the account bound to the note"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn consumer_without_the_secret_is_rejected() -> anyhow::Result<()> {
+    let Setup {
+        chain, bob, note, ..
+    } = setup()?;
+
+    let err = consume(&chain, &bob, &note, [Felt::new(9); 4])
+        .await
+        .unwrap_err();
+    assert!(
+        format!("{err:?}").contains("hash of the provided secret does not match the note digest")
+    );
+    Ok(())
+}
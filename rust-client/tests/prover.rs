@@ -0,0 +1,180 @@
+use std::{
+    convert::Infallible,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use miden_client::{
+    note::NoteType,
+    transaction::{LocalTransactionProver, ProvenTransaction, TransactionProver},
+    utils::{Deserializable, Serializable},
+};
+use miden_objects::transaction::TransactionWitness;
+use miden_remote_prover_client::remote_prover::generated::{ProvingRequest, ProvingResponse};
+use miden_testing::{Auth, MockChain};
+use rust_client::prover::{ProofSource, ProverSelector};
+use tokio::net::TcpListener;
+use tonic::{
+    body::Body,
+    codec::ProstCodec,
+    codegen::{http, BoxFuture, Service},
+    server::{Grpc, NamedService, UnaryService},
+    transport::{server::TcpIncoming, Server},
+    Status,
+};
+
+/// How a stand-in prover service answers proving requests.
+#[derive(Clone)]
+enum Behaviour {
+    /// Returns a proof computed in advance, since proving in a debug build takes longer than the
+    /// remote prover client waits.
+    Respond(Box<ProvenTransaction>),
+    Fail,
+    Hang,
+}
+
+/// A stand-in for a remote prover, serving the same gRPC route on localhost.
+#[derive(Clone)]
+struct StandInProver(Behaviour);
+
+impl NamedService for StandInProver {
+    const NAME: &'static str = "remote_prover.Api";
+}
+
+impl Service<http::Request<Body>> for StandInProver {
+    type Response = http::Response<Body>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<Body>) -> Self::Future {
+        let prove = Prove(self.0.clone());
+        Box::pin(async move {
+            let mut grpc = Grpc::new(ProstCodec::default());
+            Ok(grpc.unary(prove, request).await)
+        })
+    }
+}
+
+struct Prove(Behaviour);
+
+impl UnaryService<ProvingRequest> for Prove {
+    type Response = ProvingResponse;
+    type Future = BoxFuture<tonic::Response<ProvingResponse>, Status>;
+
+    fn call(&mut self, request: tonic::Request<ProvingRequest>) -> Self::Future {
+        let behaviour = self.0.clone();
+        Box::pin(async move {
+            match behaviour {
+                Behaviour::Fail => Err(Status::unavailable("no workers available")),
+                Behaviour::Hang => {
+                    tokio::time::sleep(Duration::from_secs(600)).await;
+                    Err(Status::deadline_exceeded("still proving"))
+                }
+                Behaviour::Respond(proven) => {
+                    let witness =
+                        TransactionWitness::read_from_bytes(&request.into_inner().payload)
+                            .map_err(|err| Status::invalid_argument(err.to_string()))?;
+                    if witness.tx_inputs.account().id() != proven.account_id() {
+                        return Err(Status::invalid_argument("unexpected transaction"));
+                    }
+                    Ok(tonic::Response::new(ProvingResponse {
+                        payload: proven.to_bytes(),
+                    }))
+                }
+            }
+        })
+    }
+}
+
+/// Starts a stand-in prover service, returning its endpoint.
+async fn spawn_stand_in(behaviour: Behaviour) -> anyhow::Result<String> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let endpoint = format!("http://{}", listener.local_addr()?);
+    tokio::spawn(
+        Server::builder()
+            .add_service(StandInProver(behaviour))
+            .serve_with_incoming(TcpIncoming::from(listener)),
+    );
+    Ok(endpoint)
+}
+
+/// An endpoint nothing listens on.
+async fn closed_endpoint() -> anyhow::Result<String> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    Ok(format!("http://{}", listener.local_addr()?))
+}
+
+/// The witness of Alice consuming a P2ID note.
+async fn witness() -> anyhow::Result<TransactionWitness> {
+    let mut chain = MockChain::new();
+    let faucet = chain.add_pending_new_faucet(Auth::BasicAuth, "MID", 1_000_000)?;
+    let alice = chain.add_pending_existing_wallet(Auth::BasicAuth, vec![]);
+    let bob = chain.add_pending_existing_wallet(Auth::BasicAuth, vec![]);
+    let note =
+        chain.add_pending_p2id_note(bob.id(), alice.id(), &[faucet.mint(100)], NoteType::Public)?;
+    chain.prove_next_block()?;
+
+    let executed = chain
+        .build_tx_context(alice.id(), &[note.id()], &[])?
+        .build()?
+        .execute()
+        .await?;
+    Ok(executed.into())
+}
+
+#[tokio::test]
+async fn remote_provers_are_tried_in_order() -> anyhow::Result<()> {
+    let witness = witness().await?;
+    let expected = LocalTransactionProver::default()
+        .prove(witness.clone())
+        .await?;
+
+    let unreachable = closed_endpoint().await?;
+    let failing = spawn_stand_in(Behaviour::Fail).await?;
+    let proving = spawn_stand_in(Behaviour::Respond(Box::new(expected.clone()))).await?;
+    let selector = ProverSelector::new([&unreachable, &failing, &proving], Duration::from_secs(60));
+
+    let proven = selector.prove(witness).await?;
+    assert_eq!(proven.id(), expected.id());
+
+    let records = selector.records();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].transaction_id, proven.id());
+    assert_eq!(
+        records[0].source,
+        ProofSource::Remote(proving),
+        "{:?}",
+        records[0].failures
+    );
+    let failed: Vec<&str> = records[0]
+        .failures
+        .iter()
+        .map(|failure| failure.endpoint.as_str())
+        .collect();
+    assert_eq!(failed, [unreachable.as_str(), failing.as_str()]);
+    assert!(records[0].failures[1]
+        .reason
+        .contains("no workers available"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn slow_remote_provers_fall_back_to_local_proving() -> anyhow::Result<()> {
+    let hanging = spawn_stand_in(Behaviour::Hang).await?;
+    let selector = ProverSelector::new([&hanging], Duration::from_millis(500));
+
+    let proven = selector.prove(witness().await?).await?;
+
+    let records = selector.records();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].transaction_id, proven.id());
+    assert_eq!(records[0].source, ProofSource::Local);
+    assert_eq!(records[0].failures.len(), 1);
+    assert_eq!(records[0].failures[0].endpoint, hanging);
+    assert!(records[0].failures[0].reason.starts_with("no proof after"));
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use std::time::Duration;
+
+use miden_client::{
+    note::NoteType,
+    transaction::{ExecutedTransaction, LocalTransactionProver, TransactionProver},
+    RemoteTransactionProver,
+};
+use miden_testing::{Auth, MockChain};
+use rust_client::{
+    prover::ProofSource,
+    proving_bench::{measure_proving, ProvingBenchReport, Workload},
+};
+use tokio::net::TcpListener;
+
+/// Alice consuming a P2ID note.
+async fn executed_transaction() -> anyhow::Result<ExecutedTransaction> {
+    let mut chain = MockChain::new();
+    let faucet = chain.add_pending_new_faucet(Auth::BasicAuth, "MID", 1_000_000)?;
+    let alice = chain.add_pending_existing_wallet(Auth::BasicAuth, vec![]);
+    let bob = chain.add_pending_existing_wallet(Auth::BasicAuth, vec![]);
+    let note =
+        chain.add_pending_p2id_note(bob.id(), alice.id(), &[faucet.mint(100)], NoteType::Public)?;
+    chain.prove_next_block()?;
+
+    Ok(chain
+        .build_tx_context(alice.id(), &[note.id()], &[])?
+        .build()?
+        .execute()
+        .await?)
+}
+
+#[tokio::test]
+async fn every_prover_is_measured_on_the_same_transaction() -> anyhow::Result<()> {
+    let executed = executed_transaction().await?;
+
+    // Nothing listens on this port, so the remote prover fails
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let endpoint = format!("http://{}", listener.local_addr()?);
+    drop(listener);
+    let remote = RemoteTransactionProver::new(endpoint.clone());
+    let local = LocalTransactionProver::default();
+    let provers: [(ProofSource, &dyn TransactionProver); 2] = [
+        (ProofSource::Remote(endpoint.clone()), &remote),
+        (ProofSource::Local, &local),
+    ];
+
+    let (samples, proven) = measure_proving(
+        Workload::P2idBatch,
+        &executed,
+        Duration::from_millis(5),
+        &provers,
+        1,
+        Duration::from_secs(30),
+    )
+    .await;
+    assert_eq!(proven.map(|proven| proven.id()), Some(executed.id()));
+    assert_eq!(samples.len(), 2);
+
+    let failed = &samples[0];
+    assert_eq!(failed.prover, ProofSource::Remote(endpoint));
+    assert!(failed.error.is_some());
+    assert_eq!(failed.prove_ms, None);
+    assert_eq!(failed.proof_bytes, None);
+
+    let proven = &samples[1];
+    assert_eq!(proven.prover, ProofSource::Local);
+    assert_eq!(proven.error, None);
+    assert!(proven.prove_ms.unwrap() > 0.0);
+    assert!(proven.proof_bytes.unwrap() > 0);
+    assert!(proven.proven_tx_bytes.unwrap() > proven.proof_bytes.unwrap());
+    assert!((proven.execute_ms - 5.0).abs() < 1e-9);
+
+    // Both provers ran on the same execution, so they share its cycle counts
+    assert_eq!(failed.cycles, proven.cycles);
+    let cycles = &proven.cycles;
+    assert!(cycles.total > 0);
+    assert!(cycles.trace_length.is_power_of_two());
+    assert!(cycles.trace_length >= cycles.total);
+
+    let report = ProvingBenchReport::new("test".into(), 1, samples);
+    assert_eq!(report.workloads.len(), 1);
+    let comparison = &report.workloads[0];
+    assert_eq!(comparison.workload, Workload::P2idBatch);
+    let failures: Vec<usize> = comparison
+        .provers
+        .iter()
+        .map(|summary| summary.failures)
+        .collect();
+    assert_eq!(failures, [1, 0]);
+    assert!(comparison
+        .provers
+        .iter()
+        .all(|summary| summary.speedup.is_none()));
+
+    let csv = report.to_csv();
+    assert_eq!(csv.lines().count(), 3);
+    assert!(csv
+        .lines()
+        .nth(2)
+        .unwrap()
+        .starts_with("p2id_batch,local,0,"));
+    assert!(report.to_json().contains("\"workload\": \"p2id_batch\""));
+    Ok(())
+}
+
+#[tokio::test]
+async fn remote_provers_that_do_not_answer_time_out() -> anyhow::Result<()> {
+    let executed = executed_transaction().await?;
+
+    // The listener accepts connections but never answers
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let endpoint = format!("http://{}", listener.local_addr()?);
+    let remote = RemoteTransactionProver::new(endpoint.clone());
+    let provers: [(ProofSource, &dyn TransactionProver); 1] =
+        [(ProofSource::Remote(endpoint.clone()), &remote)];
+
+    let (samples, proven) = tokio::time::timeout(
+        Duration::from_secs(10),
+        measure_proving(
+            Workload::P2idBatch,
+            &executed,
+            Duration::from_millis(5),
+            &provers,
+            2,
+            Duration::from_millis(200),
+        ),
+    )
+    .await?;
+    assert!(proven.is_none());
+    assert_eq!(samples.len(), 2);
+    for sample in &samples {
+        assert_eq!(sample.prover, ProofSource::Remote(endpoint.clone()));
+        assert_eq!(sample.prove_ms, None);
+        assert_eq!(sample.error.as_deref(), Some("no proof after 200ms"));
+    }
+
+    let report = ProvingBenchReport::new("test".into(), 2, samples);
+    assert_eq!(report.workloads[0].provers[0].failures, 2);
+    drop(listener);
+    Ok(())
+}
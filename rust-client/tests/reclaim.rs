@@ -0,0 +1,120 @@
+use miden_client::{
+    account::Account,
+    asset::Asset,
+    crypto::RpoRandomCoin,
+    note::{BlockNumber, Note, NoteType, WellKnownNote},
+    transaction::{OutputNote, PaymentNoteDescription, TransactionRequestBuilder},
+    ClientRng, Felt,
+};
+use miden_testing::{Auth, MockChain};
+use rust_client::reclaim::reclaimable_after;
+
+/// Number of blocks the target has to claim the payment.
+const RECLAIM_AFTER: u32 = 5;
+
+struct Setup {
+    chain: MockChain,
+    alice: Account,
+    bob: Account,
+    note: Note,
+    asset: Asset,
+    reclaim_height: BlockNumber,
+}
+
+/// A committed P2IDE payment from Alice to Bob that Alice can reclaim after `RECLAIM_AFTER`
+/// blocks.
+fn setup() -> anyhow::Result<Setup> {
+    let mut chain = MockChain::new();
+    let faucet = chain.add_pending_new_faucet(Auth::BasicAuth, "MID", 1_000_000)?;
+    let alice = chain.add_pending_existing_wallet(Auth::BasicAuth, vec![]);
+    let bob = chain.add_pending_existing_wallet(Auth::BasicAuth, vec![]);
+    chain.prove_next_block()?;
+
+    let asset = faucet.mint(100);
+    let current_block = chain.latest_block_header().block_num();
+    let payment = reclaimable_after(
+        PaymentNoteDescription::new(vec![asset], alice.id(), bob.id()),
+        current_block,
+        RECLAIM_AFTER,
+    );
+    let reclaim_height = payment
+        .reclaim_height()
+        .expect("the payment is reclaimable");
+    assert_eq!(reclaim_height, current_block + RECLAIM_AFTER);
+
+    let mut rng = ClientRng::new(Box::new(RpoRandomCoin::new([Felt::new(7); 4])));
+    let request =
+        TransactionRequestBuilder::new().build_pay_to_id(payment, NoteType::Public, &mut rng)?;
+    let note = request.expected_output_own_notes().remove(0);
+    assert_eq!(note.script().root(), WellKnownNote::P2IDE.script_root());
+
+    chain.add_pending_note(OutputNote::Full(note.clone()));
+    chain.prove_next_block()?;
+    Ok(Setup {
+        chain,
+        alice,
+        bob,
+        note,
+        asset,
+        reclaim_height,
+    })
+}
+
+/// Executes `account` consuming the committed `note`.
+async fn consume(chain: &MockChain, account: &Account, note: &Note) -> anyhow::Result<Account> {
+    let executed = chain
+        .build_tx_context(account.id(), &[note.id()], &[])?
+        .build()?
+        .execute()
+        .await?;
+    let mut account = account.clone();
+    account.apply_delta(executed.account_delta())?;
+    Ok(account)
+}
+
+#[tokio::test]
+async fn sender_cannot_reclaim_before_the_reclaim_height() -> anyhow::Result<()> {
+    let Setup {
+        chain,
+        alice,
+        note,
+        reclaim_height,
+        ..
+    } = setup()?;
+
+    assert!(chain.latest_block_header().block_num() < reclaim_height);
+    assert!(consume(&chain, &alice, &note).await.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn target_can_claim_before_the_reclaim_height() -> anyhow::Result<()> {
+    let Setup {
+        chain,
+        bob,
+        note,
+        asset,
+        ..
+    } = setup()?;
+
+    let bob_after = consume(&chain, &bob, &note).await?;
+    assert_eq!(bob_after.vault().assets().collect::<Vec<_>>(), [asset]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn sender_reclaims_once_the_reclaim_height_is_reached() -> anyhow::Result<()> {
+    let Setup {
+        mut chain,
+        alice,
+        note,
+        asset,
+        reclaim_height,
+        ..
+    } = setup()?;
+
+    chain.prove_until_block(reclaim_height)?;
+    let alice_after = consume(&chain, &alice, &note).await?;
+    assert_eq!(alice_after.vault().assets().collect::<Vec<_>>(), [asset]);
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use miden_client::{note::NoteScript, transaction::TransactionKernel, Felt, Word};
+use miden_testing::{TransactionContext, TransactionContextBuilder};
+use proptest::prelude::*;
+use rust_client::notes::{pad_inputs, word_to_masm, RecursiveNote, SerialTransform};
+
+/// Where the MASM programs below write the note inputs before hashing them.
+const INPUTS_PTR: usize = 1000;
+
+fn tx_context() -> TransactionContext {
+    TransactionContextBuilder::with_existing_mock_account()
+        .build()
+        .unwrap()
+}
+
+fn script(source: &str) -> NoteScript {
+    NoteScript::compile(source, TransactionKernel::assembler()).unwrap()
+}
+
+/// Computes the recipient in the VM the same way the note scripts do: the inputs are written to
+/// memory, the zero-padded region is hashed and passed to `tx::build_recipient_hash`.
+fn masm_recipient(
+    tx_context: &TransactionContext,
+    serial_num: Word,
+    script: &NoteScript,
+    inputs: &[Felt],
+) -> Word {
+    let store_inputs: String = inputs
+        .iter()
+        .enumerate()
+        .map(|(i, input)| format!("push.{} mem_store.{}\n", input.as_int(), INPUTS_PTR + i))
+        .collect();
+    let code = format!(
+        "
+        use.miden::tx
+        use.std::crypto::hashes::rpo
+
+        begin
+            {store_inputs}
+            push.{num_inputs}.{INPUTS_PTR}
+            exec.rpo::hash_memory
+            # => [INPUTS_COMMITMENT]
+
+            push.{script_root}
+            push.{serial_num}
+            # => [SERIAL_NUM, SCRIPT_ROOT, INPUTS_COMMITMENT]
+
+            exec.tx::build_recipient_hash
+            # => [RECIPIENT, pad(16)]
+
+            swapw dropw
+            # => [RECIPIENT, pad(12)]
+        end
+        ",
+        num_inputs = pad_inputs(inputs).len(),
+        script_root = word_to_masm(script.root().into()),
+        serial_num = word_to_masm(serial_num),
+    );
+
+    let process = tx_context.execute_code(&code).unwrap();
+    process.stack.get_word(0)
+}
+
+fn felt() -> impl Strategy<Value = Felt> {
+    any::<u64>().prop_map(Felt::new)
+}
+
+fn word() -> impl Strategy<Value = Word> {
+    [felt(), felt(), felt(), felt()]
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(24))]
+
+    #[test]
+    fn recipient_matches_masm(
+        serial_num in word(),
+        inputs in prop::collection::vec(felt(), 1..20),
+        increment in any::<bool>(),
+        tag in 0u32..1000,
+    ) {
+        let tx_context = tx_context();
+        // Different sources give different script roots
+        let script = script(&format!("begin push.{tag} drop end"));
+        let transform = if increment { SerialTransform::Increment } else { SerialTransform::Keep };
+        let note = RecursiveNote::new(script.clone(), inputs.clone()).with_serial_transform(transform);
+
+        let recipient = note.recipient(serial_num).unwrap();
+        prop_assert_eq!(note.recipient_digest(serial_num), recipient.digest());
+
+        let masm = masm_recipient(&tx_context, note.serial_num(serial_num), &script, &inputs);
+        prop_assert_eq!(Word::from(recipient.digest()), masm);
+    }
+}
+
+#[test]
+fn trailing_zero_inputs_share_the_padded_commitment() {
+    let tx_context = tx_context();
+    let script = script("begin push.0 drop end");
+    let inputs = [Felt::new(1), Felt::new(2), Felt::new(3)];
+    let serial_num = [Felt::new(5); 4];
+
+    let expected = RecursiveNote::new(script.clone(), inputs.to_vec())
+        .with_serial_transform(SerialTransform::Keep)
+        .recipient(serial_num)
+        .unwrap()
+        .digest();
+
+    // Hashing an explicit trailing zero gives the same commitment as the padding
+    let mut padded = inputs.to_vec();
+    padded.push(Felt::new(0));
+    assert_eq!(
+        masm_recipient(&tx_context, serial_num, &script, &padded),
+        Word::from(expected)
+    );
+}
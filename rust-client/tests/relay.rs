@@ -0,0 +1,171 @@
+use std::time::Duration;
+
+use miden_client::{
+    note::{NoteTag, NoteType},
+    testing::account_id::ACCOUNT_ID_SENDER,
+    Felt, Word,
+};
+use rust_client::{
+    note_file::NoteExport,
+    notes::HashPreimageNote,
+    relay::{post_note, post_notes, NoteRelay, RelaySubscription},
+};
+
+const SECRET: Word = [Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)];
+
+/// Distinct hash-preimage notes, told apart by their serial numbers.
+fn notes(count: u64) -> anyhow::Result<Vec<NoteExport>> {
+    (0..count)
+        .map(|i| {
+            let note = HashPreimageNote::new(SECRET, vec![]).build(
+                ACCOUNT_ID_SENDER.try_into()?,
+                NoteType::Private,
+                [Felt::new(i); 4],
+            )?;
+            Ok(NoteExport::new(note).with_note_args(SECRET))
+        })
+        .collect()
+}
+
+fn tag() -> NoteTag {
+    NoteTag::for_local_use_case(7, 0).unwrap()
+}
+
+/// Starts `relay` in the background and returns its address.
+fn spawn(relay: NoteRelay) -> anyhow::Result<String> {
+    let addr = relay.local_addr()?.to_string();
+    tokio::spawn(relay.run());
+    Ok(addr)
+}
+
+/// The next `count` notes of `subscription`, failing if they do not arrive in time.
+async fn receive(
+    subscription: &mut RelaySubscription,
+    count: usize,
+) -> anyhow::Result<Vec<NoteExport>> {
+    let mut received = Vec::new();
+    while received.len() < count {
+        let note = tokio::time::timeout(Duration::from_secs(10), subscription.next_note())
+            .await??
+            .expect("the relay keeps the connection open");
+        received.push(note);
+    }
+    Ok(received)
+}
+
+/// Waits until the relay handled the posts up to `last`, by subscribing until it arrives.
+async fn wait_until_posted(addr: &str, last: &NoteExport) -> anyhow::Result<()> {
+    let mut probe = RelaySubscription::connect(addr, tag()).await?;
+    while receive(&mut probe, 1).await?[0] != *last {}
+    Ok(())
+}
+
+#[tokio::test]
+async fn subscribers_receive_earlier_and_later_notes() -> anyhow::Result<()> {
+    let addr = spawn(NoteRelay::bind("127.0.0.1:0").await?)?;
+    let notes = notes(3)?;
+
+    post_note(&addr, tag(), &notes[0]).await?;
+    // A note with another tag is not delivered
+    post_note(&addr, NoteTag::for_local_use_case(8, 0)?, &notes[1]).await?;
+
+    let mut subscription = RelaySubscription::connect(&addr, tag()).await?;
+    assert_eq!(receive(&mut subscription, 1).await?, [notes[0].clone()]);
+
+    post_note(&addr, tag(), &notes[2]).await?;
+    assert_eq!(receive(&mut subscription, 1).await?, [notes[2].clone()]);
+    Ok(())
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn lagging_subscribers_are_caught_up_from_the_mailbox() -> anyhow::Result<()> {
+    // With room for a single live note, the subscriber lags behind a batch of posts
+    let relay = NoteRelay::bind("127.0.0.1:0")
+        .await?
+        .with_channel_capacity(1);
+    let addr = spawn(relay)?;
+    let notes = notes(20)?;
+
+    let mut subscription = RelaySubscription::connect(&addr, tag()).await?;
+    // Posting a first note makes sure the relay handled the subscription
+    post_note(&addr, tag(), &notes[0]).await?;
+    assert_eq!(receive(&mut subscription, 1).await?, [notes[0].clone()]);
+
+    post_notes(&addr, tag(), &notes[1..]).await?;
+    assert_eq!(receive(&mut subscription, 19).await?, notes[1..]);
+
+    // Nothing was sent twice
+    post_note(&addr, tag(), &notes[0]).await?;
+    assert_eq!(receive(&mut subscription, 1).await?, [notes[0].clone()]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn mailboxes_keep_only_the_latest_notes() -> anyhow::Result<()> {
+    let relay = NoteRelay::bind("127.0.0.1:0")
+        .await?
+        .with_retention(3, Duration::from_secs(60));
+    let addr = spawn(relay)?;
+    let notes = notes(6)?;
+
+    post_notes(&addr, tag(), &notes[..5]).await?;
+    wait_until_posted(&addr, &notes[4]).await?;
+    let mut subscription = RelaySubscription::connect(&addr, tag()).await?;
+    assert_eq!(receive(&mut subscription, 3).await?, notes[2..5]);
+
+    post_note(&addr, tag(), &notes[5]).await?;
+    assert_eq!(receive(&mut subscription, 1).await?, [notes[5].clone()]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn expired_notes_are_not_delivered() -> anyhow::Result<()> {
+    let relay = NoteRelay::bind("127.0.0.1:0")
+        .await?
+        .with_retention(10, Duration::from_millis(50));
+    let addr = spawn(relay)?;
+    let notes = notes(2)?;
+
+    post_note(&addr, tag(), &notes[0]).await?;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    // Posting prunes the expired note
+    post_note(&addr, tag(), &notes[1]).await?;
+    wait_until_posted(&addr, &notes[1]).await?;
+
+    let mut subscription = RelaySubscription::connect(&addr, tag()).await?;
+    assert_eq!(receive(&mut subscription, 1).await?, [notes[1].clone()]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn subscribers_are_told_when_an_emptied_mailbox_lost_their_notes() -> anyhow::Result<()> {
+    let relay = NoteRelay::bind("127.0.0.1:0")
+        .await?
+        .with_channel_capacity(1)
+        .with_retention(100_000, Duration::from_secs(2));
+    let addr = spawn(relay)?;
+    let notes = notes(3)?;
+    // Enough notes to fill the socket buffers of a subscriber that does not read
+    let backlog = vec![notes[0].clone(); 5_000];
+
+    post_notes(&addr, tag(), &backlog).await?;
+    wait_until_posted(&addr, &notes[0]).await?;
+    let mut subscription = RelaySubscription::connect(&addr, tag()).await?;
+
+    // The relay is stuck writing the backlog, so the subscriber lags behind these notes
+    post_notes(&addr, tag(), &notes[1..]).await?;
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    // Posting with another tag prunes the expired notes, emptying the subscribed mailbox
+    let other_tag = NoteTag::for_local_use_case(8, 0)?;
+    post_note(&addr, other_tag, &notes[0]).await?;
+    let mut probe = RelaySubscription::connect(&addr, other_tag).await?;
+    receive(&mut probe, 1).await?;
+
+    // The relay closes the connection instead of leaving the gap unnoticed
+    while let Some(note) =
+        tokio::time::timeout(Duration::from_secs(10), subscription.next_note()).await??
+    {
+        assert_eq!(note, notes[0]);
+    }
+    Ok(())
+}
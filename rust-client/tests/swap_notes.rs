@@ -0,0 +1,111 @@
+use miden_client::{
+    asset::{Asset, FungibleAsset},
+    crypto::RpoRandomCoin,
+    note::NoteType,
+    transaction::OutputNote,
+    Felt,
+};
+use miden_lib::account::interface::AccountInterface;
+use miden_testing::{Auth, MockChain};
+use rust_client::swap::{offered_asset, requested_asset, SwapOffer};
+
+fn fungible(asset: Asset) -> FungibleAsset {
+    asset.unwrap_fungible()
+}
+
+#[tokio::test]
+async fn swap_note_pays_the_creator_back() -> anyhow::Result<()> {
+    let mut chain = MockChain::new();
+    let faucet_a = chain.add_pending_new_faucet(Auth::BasicAuth, "AAA", 1_000_000)?;
+    let faucet_b = chain.add_pending_new_faucet(Auth::BasicAuth, "BBB", 1_000_000)?;
+    let alice = chain.add_pending_existing_wallet(Auth::BasicAuth, vec![faucet_a.mint(100)]);
+    let bob = chain.add_pending_existing_wallet(Auth::BasicAuth, vec![faucet_b.mint(50)]);
+    chain.prove_next_block()?;
+
+    // Alice offers 40 A for 30 B
+    let offer = SwapOffer::new(
+        alice.id(),
+        fungible(faucet_a.mint(40)),
+        fungible(faucet_b.mint(30)),
+    );
+    let mut rng = RpoRandomCoin::new([Felt::new(7); 4]);
+    let swap = offer.build(NoteType::Public, &mut rng)?;
+    assert_eq!(offered_asset(&swap.note), Some(faucet_a.mint(40)));
+    assert_eq!(requested_asset(&swap.note), Some(faucet_b.mint(30)));
+
+    // Alice sends the SWAP note, which takes the offered 40 A out of her vault
+    let send_script = AccountInterface::from(&alice).build_send_notes_script(
+        &[swap.note.clone().into()],
+        None,
+        false,
+    )?;
+    let executed = chain
+        .build_tx_context(alice.id(), &[], &[])?
+        .tx_script(send_script)
+        .extend_expected_output_notes(vec![OutputNote::Full(swap.note.clone())])
+        .build()?
+        .execute()
+        .await?;
+    let alice = chain.add_pending_executed_transaction(&executed)?;
+    chain.prove_next_block()?;
+    assert_eq!(alice.vault().get_balance(faucet_a.id())?, 60);
+
+    // Bob consumes the SWAP note, which creates the payback note for Alice
+    let payback = swap.payback_note(bob.id())?;
+    let executed = chain
+        .build_tx_context(bob.id(), &[swap.note.id()], &[])?
+        .extend_expected_output_notes(vec![OutputNote::Full(payback.clone())])
+        .build()?
+        .execute()
+        .await?;
+
+    assert_eq!(executed.output_notes().num_notes(), 1);
+    assert_eq!(executed.output_notes().get_note(0).id(), payback.id());
+
+    let mut bob_after = bob.clone();
+    bob_after.apply_delta(executed.account_delta())?;
+    assert_eq!(bob_after.vault().get_balance(faucet_a.id())?, 40);
+    assert_eq!(bob_after.vault().get_balance(faucet_b.id())?, 20);
+
+    // Alice claims the payback note
+    let executed = chain
+        .build_tx_context(alice.id(), &[], std::slice::from_ref(&payback))?
+        .build()?
+        .execute()
+        .await?;
+
+    let mut alice_after = alice.clone();
+    alice_after.apply_delta(executed.account_delta())?;
+    assert_eq!(alice_after.vault().get_balance(faucet_a.id())?, 60);
+    assert_eq!(alice_after.vault().get_balance(faucet_b.id())?, 30);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_note_fails_without_the_requested_asset() -> anyhow::Result<()> {
+    let mut chain = MockChain::new();
+    let faucet_a = chain.add_pending_new_faucet(Auth::BasicAuth, "AAA", 1_000_000)?;
+    let faucet_b = chain.add_pending_new_faucet(Auth::BasicAuth, "BBB", 1_000_000)?;
+    let alice = chain.add_pending_existing_wallet(Auth::BasicAuth, vec![faucet_a.mint(100)]);
+    let bob = chain.add_pending_existing_wallet(Auth::BasicAuth, vec![faucet_b.mint(10)]);
+    chain.prove_next_block()?;
+
+    let offer = SwapOffer::new(
+        alice.id(),
+        fungible(faucet_a.mint(40)),
+        fungible(faucet_b.mint(30)),
+    );
+    let mut rng = RpoRandomCoin::new([Felt::new(7); 4]);
+    let swap = offer.build(NoteType::Public, &mut rng)?;
+
+    // Bob only holds 10 B, so neither leg of the swap happens
+    let result = chain
+        .build_tx_context(bob.id(), &[], std::slice::from_ref(&swap.note))?
+        .build()?
+        .execute()
+        .await;
+    assert!(result.is_err());
+
+    Ok(())
+}
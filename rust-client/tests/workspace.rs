@@ -0,0 +1,187 @@
+use std::{path::PathBuf, sync::Arc};
+
+use miden_client::{
+    asset::FungibleAsset,
+    auth::AuthSecretKey,
+    builder::ClientBuilder,
+    crypto::SecretKey,
+    note::NoteType,
+    rpc::Endpoint,
+    testing::{account_id::ACCOUNT_ID_SENDER, mock::MockRpcApi},
+    transaction::TransactionRequestBuilder,
+    Word,
+};
+use miden_lib::{account::interface::AccountInterface, AuthScheme};
+use rust_client::{
+    accounts::create_basic_faucet,
+    keystore::{migrate_keystore, KEYSTORE_PASSWORD_ENV},
+    workspace::{Workspace, WorkspaceError, WorkspaceKeyStore, WORKSPACES_DIR},
+};
+
+/// A fresh temporary directory to create workspaces in.
+fn temp_base() -> anyhow::Result<PathBuf> {
+    let dir = std::env::temp_dir().join(format!("workspaces-{}", rand::random::<u64>()));
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Adds a new key to the workspace's keystore, returning its public key.
+fn add_key(workspace: &Workspace) -> anyhow::Result<Word> {
+    let key = SecretKey::new();
+    workspace
+        .keystore()?
+        .add_key(&AuthSecretKey::RpoFalcon512(key.clone()))?;
+    Ok(key.public_key().into())
+}
+
+fn has_key(workspace: &Workspace, public_key: Word) -> anyhow::Result<bool> {
+    Ok(workspace.keystore()?.get_key(public_key)?.is_some())
+}
+
+#[test]
+fn workspaces_are_isolated() -> anyhow::Result<()> {
+    let base = temp_base()?;
+    let alpha = Workspace::create_in(&base, "alpha")?;
+    let beta = Workspace::create_in(&base, "beta")?;
+    let default = Workspace::default_in(&base);
+    assert_eq!(alpha.root(), base.join(WORKSPACES_DIR).join("alpha"));
+    assert_eq!(default.store_path(), base.join("store.sqlite3"));
+
+    let alpha_key = add_key(&alpha)?;
+    let beta_key = add_key(&beta)?;
+    let default_key = add_key(&default)?;
+    assert!(!has_key(&beta, alpha_key)?);
+
+    // Flows with several clients keep extra stores in the workspace
+    let extra_store = alpha.root().join("alice_store.sqlite3");
+    std::fs::write(&extra_store, "")?;
+    alpha.reset()?;
+    assert!(!has_key(&alpha, alpha_key)?);
+    assert!(!extra_store.exists());
+    assert!(has_key(&beta, beta_key)?);
+    assert!(has_key(&default, default_key)?);
+
+    assert_eq!(Workspace::list_in(&base)?, vec!["default", "alpha", "beta"]);
+    assert!(matches!(
+        Workspace::create_in(&base, "alpha"),
+        Err(WorkspaceError::AlreadyExists(_))
+    ));
+    assert!(matches!(
+        Workspace::open_in(&base, "gamma"),
+        Err(WorkspaceError::NotFound(_))
+    ));
+    for name in ["default", "../escape", "Alpha", ".snapshots"] {
+        assert!(matches!(
+            Workspace::create_in(&base, name),
+            Err(WorkspaceError::InvalidName(_))
+        ));
+    }
+    Ok(())
+}
+
+#[test]
+fn snapshots_restore_a_workspace() -> anyhow::Result<()> {
+    let base = temp_base()?;
+    let workspace = Workspace::create_in(&base, "alpha")?;
+    let first = add_key(&workspace)?;
+    std::fs::write(workspace.address_book_path(), "{}")?;
+
+    workspace.snapshot("funded")?;
+    let second = add_key(&workspace)?;
+    assert!(matches!(
+        workspace.snapshot("funded"),
+        Err(WorkspaceError::SnapshotExists(_))
+    ));
+
+    workspace.restore("funded")?;
+    assert!(has_key(&workspace, first)?);
+    assert!(!has_key(&workspace, second)?);
+    assert!(workspace.address_book_path().exists());
+    assert_eq!(workspace.snapshots()?, vec!["funded"]);
+
+    // Snapshots belong to their workspace
+    let other = Workspace::create_in(&base, "beta")?;
+    assert!(matches!(
+        other.restore("funded"),
+        Err(WorkspaceError::SnapshotNotFound(_))
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn clients_use_the_workspace_store() -> anyhow::Result<()> {
+    let base = temp_base()?;
+    let workspace = Workspace::create_in(&base, "alpha")?;
+
+    // Building the client does not talk to the node
+    workspace
+        .instantiate_client(Endpoint::localhost(), None)
+        .await?;
+    assert!(workspace.store_path().exists());
+    assert!(!Workspace::default_in(&base).store_path().exists());
+    Ok(())
+}
+
+#[test]
+fn encrypted_workspace_keystores_are_not_opened_in_plaintext() -> anyhow::Result<()> {
+    let workspace = Workspace::create_in(temp_base()?, "alpha")?;
+    assert!(!workspace.is_keystore_encrypted());
+    let public_key = add_key(&workspace)?;
+
+    migrate_keystore(workspace.keystore_dir(), "hunter2")?;
+    assert!(workspace.is_keystore_encrypted());
+    assert!(matches!(
+        workspace.keystore(),
+        Err(WorkspaceError::Keystore(_))
+    ));
+
+    let keystore = workspace.encrypted_keystore("hunter2")?;
+    assert!(keystore.get_key(public_key)?.is_some());
+    assert!(workspace.encrypted_keystore("wrong").is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn flows_add_keys_and_sign_in_a_migrated_workspace() -> anyhow::Result<()> {
+    let workspace = Workspace::create_in(temp_base()?, "alpha")?;
+    add_key(&workspace)?;
+    migrate_keystore(workspace.keystore_dir(), "hunter2")?;
+
+    // The password is read once, the client shares the session instead of asking again
+    std::env::set_var(KEYSTORE_PASSWORD_ENV, "hunter2");
+    let keystore = workspace.open_keystore()?;
+    assert!(matches!(keystore, WorkspaceKeyStore::Encrypted(_)));
+    std::env::set_var(KEYSTORE_PASSWORD_ENV, "wrong");
+    workspace
+        .clone()
+        .instantiate_client(Endpoint::localhost(), None)
+        .await?;
+    std::env::remove_var(KEYSTORE_PASSWORD_ENV);
+
+    // A flow creates a faucet and mints with it, on a mock node
+    let mut client = ClientBuilder::new()
+        .rpc(Arc::new(MockRpcApi::new()))
+        .sqlite_store(workspace.root().join("mock.sqlite3").to_str().unwrap())
+        .authenticator(Arc::new(keystore.clone()))
+        .in_debug_mode(true)
+        .build()
+        .await?;
+    client.sync_state().await?;
+    let faucet = create_basic_faucet(&mut client, keystore).await?;
+    let transaction_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        FungibleAsset::new(faucet.id(), 100)?,
+        ACCOUNT_ID_SENDER.try_into()?,
+        NoteType::Public,
+        client.rng(),
+    )?;
+    let tx_execution_result = client
+        .new_transaction(faucet.id(), transaction_request)
+        .await?;
+    assert_eq!(tx_execution_result.created_notes().num_notes(), 1);
+
+    // The faucet's key was added encrypted
+    let AuthScheme::RpoFalcon512 { pub_key } = AccountInterface::from(&faucet).auth()[0];
+    let encrypted = workspace.encrypted_keystore("hunter2")?;
+    assert!(encrypted.public_keys()?.contains(&pub_key.into()));
+    Ok(())
+}